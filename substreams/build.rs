@@ -1,14 +1,55 @@
-use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use anyhow::{Context, Result};
 use substreams_antelope_abigen::Abigen;
 
+/// Optional code -> name table of the contract's content types
+const CONTENT_TYPES: &str = "abi/content_types.json";
+
 fn main() -> Result<()> {
     // Generate Rust bindings for Polaris Music Registry contract
     Abigen::new("PolarisMusic", "abi/polaris.music.json")?
         .generate()?
         .write_to_file("src/abi/polaris_music.rs")?;
 
-    // Rebuild if ABI changes
+    // Generate the content type name lookup
+    std::fs::write("src/abi/content_types.rs", content_types_module()?)?;
+
+    // Rebuild if ABI or content types change
     println!("cargo:rerun-if-changed=abi/polaris.music.json");
+    println!("cargo:rerun-if-changed={}", CONTENT_TYPES);
 
     Ok(())
 }
+
+/// Source of `abi::content_types`; without the JSON file every code is `UNKNOWN`
+fn content_types_module() -> Result<String> {
+    let names: BTreeMap<u32, String> = if Path::new(CONTENT_TYPES).exists() {
+        let json = std::fs::read_to_string(CONTENT_TYPES)?;
+        let raw: BTreeMap<String, String> =
+            serde_json::from_str(&json).with_context(|| format!("invalid {}", CONTENT_TYPES))?;
+        raw.into_iter()
+            .map(|(code, name)| {
+                let code = code
+                    .parse()
+                    .with_context(|| format!("invalid content type code {:?}", code))?;
+                Ok((code, name))
+            })
+            .collect::<Result<_>>()?
+    } else {
+        BTreeMap::new()
+    };
+
+    let mut source = String::from(
+        "// Generated by build.rs from abi/content_types.json, do not edit\n\n\
+         /// Name of a `put` type / `attest` confirmed type code, `UNKNOWN` if unmapped\n\
+         pub fn content_type_name(code: u32) -> &'static str {\n    match code {\n",
+    );
+    for (code, name) in &names {
+        writeln!(source, "        {} => {:?},", code, name)?;
+    }
+    source.push_str("        _ => \"UNKNOWN\",\n    }\n}\n");
+    Ok(source)
+}
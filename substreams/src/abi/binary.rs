@@ -0,0 +1,304 @@
+//! Binary action data decoding
+//!
+//! Some block providers only ship `raw_data`. The bytes are decoded against the
+//! contract ABI into the same JSON shape nodeos writes to `json_data`, so the
+//! generated bindings deserialize either source unchanged.
+
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use serde_json::{Map, Value};
+use substreams_antelope::abi::ABI;
+
+use crate::asset::format_asset;
+
+/// Contract ABI the bindings in `polaris_music` are generated from
+const CONTRACT_ABI: &str = include_str!("../../abi/polaris.music.json");
+
+/// Standard `eosio.token` transfer, for reward payouts issued by the contract
+const TOKEN_ABI: &str = r#"{
+    "version": "eosio::abi/1.1",
+    "structs": [{
+        "name": "transfer",
+        "base": "",
+        "fields": [
+            {"name": "from", "type": "name"},
+            {"name": "to", "type": "name"},
+            {"name": "quantity", "type": "asset"},
+            {"name": "memo", "type": "string"}
+        ]
+    }],
+    "actions": [{"name": "transfer", "type": "transfer"}]
+}"#;
+
+/// Layout of the contract's `globals` singleton row (`global_state`), which
+/// the bundled ABI does not declare; field order follows `EOSLIB_SERIALIZE`
+const GLOBALS_ABI: &str = r#"{
+    "version": "eosio::abi/1.1",
+    "structs": [{
+        "name": "global_state",
+        "base": "",
+        "fields": [
+            {"name": "x", "type": "uint64"},
+            {"name": "carry", "type": "float64"},
+            {"name": "round", "type": "uint64"},
+            {"name": "fractally_oracle", "type": "name"},
+            {"name": "token_contract", "type": "name"},
+            {"name": "token_symbol", "type": "symbol"},
+            {"name": "council_account", "type": "name"},
+            {"name": "approval_threshold_bp", "type": "uint64"},
+            {"name": "max_vote_weight", "type": "uint32"},
+            {"name": "attestor_respect_threshold", "type": "uint32"},
+            {"name": "paused", "type": "bool"},
+            {"name": "vote_window_release", "type": "uint32"},
+            {"name": "vote_window_mint", "type": "uint32"},
+            {"name": "vote_window_resolve", "type": "uint32"},
+            {"name": "vote_window_claim", "type": "uint32"},
+            {"name": "vote_window_merge", "type": "uint32"},
+            {"name": "vote_window_default", "type": "uint32"},
+            {"name": "multiplier_release", "type": "uint64"},
+            {"name": "multiplier_mint", "type": "uint64"},
+            {"name": "multiplier_resolve", "type": "uint64"},
+            {"name": "multiplier_add_claim", "type": "uint64"},
+            {"name": "multiplier_edit_claim", "type": "uint64"},
+            {"name": "multiplier_merge", "type": "uint64"},
+            {"name": "approved_author_pct", "type": "uint64"},
+            {"name": "approved_voters_pct", "type": "uint64"},
+            {"name": "approved_stakers_pct", "type": "uint64"},
+            {"name": "rejected_voters_pct", "type": "uint64"},
+            {"name": "rejected_stakers_pct", "type": "uint64"}
+        ]
+    }],
+    "actions": []
+}"#;
+
+/// Layout of an `anchors` table row (`anchor`), which the bundled ABI does not
+/// declare; field order follows `EOSLIB_SERIALIZE`
+const ANCHOR_ABI: &str = r#"{
+    "version": "eosio::abi/1.1",
+    "structs": [{
+        "name": "anchor",
+        "base": "",
+        "fields": [
+            {"name": "id", "type": "uint64"},
+            {"name": "author", "type": "name"},
+            {"name": "type", "type": "uint8"},
+            {"name": "hash", "type": "checksum256"},
+            {"name": "event_cid", "type": "string"},
+            {"name": "parent", "type": "checksum256?"},
+            {"name": "ts", "type": "uint32"},
+            {"name": "tags", "type": "name[]"},
+            {"name": "expires_at", "type": "uint32"},
+            {"name": "finalized", "type": "bool"},
+            {"name": "escrowed_amount", "type": "uint64"},
+            {"name": "submission_x", "type": "uint64"}
+        ]
+    }],
+    "actions": []
+}"#;
+
+const NAME_CHARS: &[u8; 32] = b".12345abcdefghijklmnopqrstuvwxyz";
+
+/// Struct layouts and type aliases of one ABI, indexed by name
+struct Schema {
+    aliases: HashMap<String, String>,
+    structs: HashMap<String, (String, Vec<(String, String)>)>,
+    actions: HashMap<String, String>,
+}
+
+impl Schema {
+    fn load(json: &str) -> Schema {
+        let abi = ABI::try_from(json).expect("bundled ABI is valid JSON");
+        Schema {
+            aliases: abi
+                .types
+                .into_iter()
+                .map(|t| (t.new_type_name, t.ty))
+                .collect(),
+            structs: abi
+                .structs
+                .into_iter()
+                .map(|s| {
+                    let fields = s.fields.into_iter().map(|f| (f.name, f.ty)).collect();
+                    (s.name, (s.base, fields))
+                })
+                .collect(),
+            actions: abi.actions.into_iter().map(|a| (a.name, a.ty)).collect(),
+        }
+    }
+
+    fn decode_action(&self, action: &str, data: &[u8]) -> Option<Value> {
+        self.decode_exact(self.actions.get(action)?, data)
+    }
+
+    fn decode_exact(&self, ty: &str, data: &[u8]) -> Option<Value> {
+        let mut reader = Reader { data, pos: 0 };
+        let value = self.decode_type(ty, &mut reader)?;
+        // Trailing bytes mean the ABI does not describe this payload
+        (reader.pos == data.len()).then_some(value)
+    }
+
+    fn decode_type(&self, ty: &str, reader: &mut Reader) -> Option<Value> {
+        if let Some(inner) = ty.strip_suffix("[]") {
+            let len = reader.varuint32()?;
+            return (0..len)
+                .map(|_| self.decode_type(inner, reader))
+                .collect::<Option<Vec<_>>>()
+                .map(Value::Array);
+        }
+        if let Some(inner) = ty.strip_suffix('?') {
+            return match reader.u8()? {
+                0 => Some(Value::Null),
+                _ => self.decode_type(inner, reader),
+            };
+        }
+        if let Some(target) = self.aliases.get(ty) {
+            return self.decode_type(target, reader);
+        }
+
+        Some(match ty {
+            "bool" => Value::Bool(reader.u8()? != 0),
+            "uint8" => reader.u8()?.into(),
+            "int8" => (reader.u8()? as i8).into(),
+            "uint16" => u16::from_le_bytes(reader.array()?).into(),
+            "int16" => i16::from_le_bytes(reader.array()?).into(),
+            "uint32" => u32::from_le_bytes(reader.array()?).into(),
+            "int32" => i32::from_le_bytes(reader.array()?).into(),
+            "uint64" => u64::from_le_bytes(reader.array()?).into(),
+            "int64" => i64::from_le_bytes(reader.array()?).into(),
+            "float64" => f64::from_le_bytes(reader.array()?).into(),
+            "varuint32" => reader.varuint32()?.into(),
+            "name" => Value::String(name_to_string(u64::from_le_bytes(reader.array()?))),
+            "string" => {
+                let len = reader.varuint32()? as usize;
+                Value::String(String::from_utf8(reader.take(len)?.to_vec()).ok()?)
+            }
+            "checksum256" => Value::String(hex::encode(reader.take(32)?)),
+            "symbol" => {
+                let (precision, symbol) = symbol_parts(u64::from_le_bytes(reader.array()?));
+                Value::String(format!("{},{}", precision, symbol))
+            }
+            "asset" => {
+                let amount = i64::from_le_bytes(reader.array()?);
+                let (precision, symbol) = symbol_parts(u64::from_le_bytes(reader.array()?));
+                Value::String(format_asset(amount, precision, &symbol))
+            }
+            _ => Value::Object(self.decode_struct(ty, reader)?),
+        })
+    }
+
+    fn decode_struct(&self, name: &str, reader: &mut Reader) -> Option<Map<String, Value>> {
+        let (base, fields) = self.structs.get(name)?;
+        let mut object = if base.is_empty() {
+            Map::new()
+        } else {
+            self.decode_struct(base, reader)?
+        };
+        for (field, ty) in fields {
+            object.insert(field.clone(), self.decode_type(ty, reader)?);
+        }
+        Some(object)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn varuint32(&mut self) -> Option<u32> {
+        let mut value: u32 = 0;
+        for shift in (0..35).step_by(7) {
+            let byte = self.u8()?;
+            value |= u32::from(byte & 0x7f).checked_shl(shift)?;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// Antelope base32 name encoding (`polaris`, `eosio.token`, ...)
+fn name_to_string(value: u64) -> String {
+    let mut chars = [b'.'; 13];
+    let mut rest = value;
+    for (i, slot) in chars.iter_mut().enumerate().rev() {
+        let (mask, bits) = if i == 12 { (0x0f, 4) } else { (0x1f, 5) };
+        *slot = NAME_CHARS[(rest & mask) as usize];
+        rest >>= bits;
+    }
+    let len = chars.iter().rposition(|&c| c != b'.').map_or(0, |i| i + 1);
+    String::from_utf8_lossy(&chars[..len]).into_owned()
+}
+
+/// Split a packed symbol into (precision, code)
+fn symbol_parts(value: u64) -> (u8, String) {
+    let bytes = value.to_le_bytes();
+    let code = bytes[1..]
+        .iter()
+        .take_while(|&&b| b != 0)
+        .map(|&b| b as char)
+        .collect();
+    (bytes[0], code)
+}
+
+/// `amount` of a binary `stakes` table row (`stake_record`)
+///
+/// The bundled ABI declares no tables, so the layout is read directly: the
+/// asset follows the 8-byte `id` and the 32-byte `node_id`. The trailing
+/// `staked_at`/`last_updated` time points are not needed.
+pub fn decode_stake_row_amount(data: &[u8]) -> Option<String> {
+    let mut reader = Reader { data, pos: 0 };
+    reader.take(8 + 32)?;
+    let amount = i64::from_le_bytes(reader.array()?);
+    let (precision, symbol) = symbol_parts(u64::from_le_bytes(reader.array()?));
+    Some(format_asset(amount, precision, &symbol))
+}
+
+/// Decode a binary `globals` singleton row into its JSON equivalent
+pub fn decode_globals_row(data: &[u8]) -> Option<Value> {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA
+        .get_or_init(|| Schema::load(GLOBALS_ABI))
+        .decode_exact("global_state", data)
+}
+
+/// Decode a binary `anchors` table row into its JSON equivalent
+pub fn decode_anchor_row(data: &[u8]) -> Option<Value> {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA
+        .get_or_init(|| Schema::load(ANCHOR_ABI))
+        .decode_exact("anchor", data)
+}
+
+/// Decode a contract action's `raw_data` into its `json_data` equivalent
+pub fn decode_contract_action(action: &str, data: &[u8]) -> Option<Value> {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA
+        .get_or_init(|| Schema::load(CONTRACT_ABI))
+        .decode_action(action, data)
+}
+
+/// Decode a token `transfer`'s `raw_data` into its `json_data` equivalent
+pub fn decode_token_transfer(data: &[u8]) -> Option<Value> {
+    static SCHEMA: OnceLock<Schema> = OnceLock::new();
+    SCHEMA
+        .get_or_init(|| Schema::load(TOKEN_ABI))
+        .decode_action("transfer", data)
+}
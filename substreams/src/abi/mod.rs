@@ -1,2 +1,9 @@
 // Generated ABI bindings
+#[allow(dead_code)]
 pub mod polaris_music;
+
+// Content type names, generated from abi/content_types.json
+pub mod content_types;
+
+// Binary (raw_data) decoding against the bundled ABI
+pub mod binary;
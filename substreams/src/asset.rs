@@ -0,0 +1,181 @@
+//! Antelope asset strings (`"12.5000 MUS"`)
+//!
+//! Amounts are kept as raw integers scaled by the symbol precision, the same
+//! representation the contract uses on-chain, so sums never lose precision.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Asset {
+    /// Raw amount in the smallest unit (`12.5000 MUS` -> 125000)
+    pub amount: i64,
+    /// Digits after the decimal point
+    pub precision: u8,
+    pub symbol: String,
+}
+
+/// Parse an asset string such as `"12.5000 MUS"`.
+///
+/// Returns `None` for malformed quantities rather than guessing, so callers
+/// never fold a corrupt value into a total.
+pub fn parse(quantity: &str) -> Option<Asset> {
+    let (number, symbol) = quantity.trim().split_once(' ')?;
+    let symbol = symbol.trim();
+    if symbol.is_empty() || symbol.len() > 7 || !symbol.bytes().all(|b| b.is_ascii_uppercase()) {
+        return None;
+    }
+
+    let (amount, precision) = parse_decimal(number)?;
+    Some(Asset {
+        amount,
+        precision,
+        symbol: symbol.to_string(),
+    })
+}
+
+/// Parse a plain decimal (`"12.5000"` -> `(125000, 4)`), the number part of an
+/// asset string
+pub fn parse_decimal(number: &str) -> Option<(i64, u8)> {
+    let (negative, digits) = match number.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, number),
+    };
+    // Zero-precision assets (`"5 MUS"`) have no decimal point at all
+    let (whole, fraction) = match digits.split_once('.') {
+        Some((_, "")) => return None,
+        Some(parts) => parts,
+        None => (digits, ""),
+    };
+    if whole.is_empty() || !whole.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if !fraction.bytes().all(|b| b.is_ascii_digit()) || fraction.len() > 18 {
+        return None;
+    }
+
+    let mut amount: i64 = 0;
+    for b in whole.bytes().chain(fraction.bytes()) {
+        amount = amount.checked_mul(10)?.checked_add(i64::from(b - b'0'))?;
+    }
+
+    Some((
+        if negative { -amount } else { amount },
+        fraction.len() as u8,
+    ))
+}
+
+/// Render a raw amount back into an asset string (`125000, 4, "MUS"` -> `"12.5000 MUS"`)
+pub fn format_asset(amount: i64, precision: u8, symbol: &str) -> String {
+    format!("{} {}", format_decimal(amount.into(), precision), symbol)
+}
+
+/// Render a raw amount as a plain decimal (`125000, 4` -> `"12.5000"`)
+pub fn format_decimal(amount: i128, precision: u8) -> String {
+    let sign = if amount < 0 { "-" } else { "" };
+    let magnitude = amount.unsigned_abs();
+    if precision == 0 {
+        return format!("{}{}", sign, magnitude);
+    }
+
+    let scale = 10u128.pow(u32::from(precision));
+    format!(
+        "{}{}.{:0width$}",
+        sign,
+        magnitude / scale,
+        magnitude % scale,
+        width = usize::from(precision)
+    )
+}
+
+/// Whether raw `amount` at `precision` is below `floor`, rescaling so that
+/// `1 MUS` and `1.0000 MUS` compare equal (symbols are not compared)
+pub fn is_below(amount: i64, precision: u8, floor: &Asset) -> bool {
+    i128::from(amount) * 10i128.pow(u32::from(floor.precision))
+        < i128::from(floor.amount) * 10i128.pow(u32::from(precision))
+}
+
+/// Value of `asset` at `price` (a `parse_decimal` pair, per whole unit of the
+/// asset), rounded half away from zero to `decimals` digits
+///
+/// Computed on integers, so `"0.0001 MUS"` at `"0.0125"` is exactly
+/// `0.00000125` before rounding. `None` only when `decimals` is absurdly large.
+pub fn fiat_value(asset: &Asset, price: (i64, u8), decimals: u8) -> Option<String> {
+    let (price, price_precision) = price;
+    let precision = u32::from(asset.precision) + u32::from(price_precision);
+    let exact = i128::from(asset.amount) * i128::from(price);
+
+    let decimals_u32 = u32::from(decimals);
+    let rounded = if precision > decimals_u32 {
+        let scale = 10i128.checked_pow(precision - decimals_u32)?;
+        let half = scale / 2;
+        let magnitude = (exact.abs() + half) / scale;
+        if exact < 0 {
+            -magnitude
+        } else {
+            magnitude
+        }
+    } else {
+        exact.checked_mul(10i128.checked_pow(decimals_u32 - precision)?)?
+    };
+    Some(format_decimal(rounded, decimals))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(quantity: &str) -> Asset {
+        let asset = parse(quantity).expect("valid asset");
+        assert_eq!(
+            format_asset(asset.amount, asset.precision, &asset.symbol),
+            quantity
+        );
+        asset
+    }
+
+    #[test]
+    fn zero_precision_assets() {
+        let five = round_trip("5 MUS");
+        assert_eq!((five.amount, five.precision), (5, 0));
+        let zero = round_trip("0 MUS");
+        assert_eq!((zero.amount, zero.precision), (0, 0));
+        assert_eq!(round_trip("-5 MUS").amount, -5);
+    }
+
+    #[test]
+    fn non_default_precisions() {
+        let eos = round_trip("1.00000000 EOS");
+        assert_eq!((eos.amount, eos.precision), (100_000_000, 8));
+        let mus = round_trip("12.5000 MUS");
+        assert_eq!((mus.amount, mus.precision), (125_000, 4));
+        assert_eq!(round_trip("0.1 ABC").amount, 1);
+    }
+
+    #[test]
+    fn fiat_values_round_to_the_requested_decimals() {
+        let price = parse_decimal("0.0125").unwrap();
+        let value =
+            |quantity: &str, decimals| fiat_value(&parse(quantity).unwrap(), price, decimals);
+
+        assert_eq!(value("1000.0000 MUS", 2).as_deref(), Some("12.50"));
+        assert_eq!(value("0.0001 MUS", 8).as_deref(), Some("0.00000125"));
+        assert_eq!(value("0.0001 MUS", 10).as_deref(), Some("0.0000012500"));
+        assert_eq!(value("0.4000 MUS", 2).as_deref(), Some("0.01"));
+        assert_eq!(value("-0.4000 MUS", 2).as_deref(), Some("-0.01"));
+        assert_eq!(value("5 MUS", 0).as_deref(), Some("0"));
+    }
+
+    #[test]
+    fn floors_compare_across_precisions() {
+        let floor = parse("1 MUS").unwrap();
+        assert!(is_below(9_999, 4, &floor));
+        assert!(!is_below(10_000, 4, &floor));
+        assert!(!is_below(1, 0, &parse("1.0000 MUS").unwrap()));
+        assert!(!is_below(0, 4, &parse("0 MUS").unwrap()));
+    }
+
+    #[test]
+    fn malformed_quantities() {
+        for quantity in ["5. MUS", ".5 MUS", "5", "5 mus", "1.2.3 MUS", "x MUS", ""] {
+            assert_eq!(parse(quantity), None, "{:?}", quantity);
+        }
+    }
+}
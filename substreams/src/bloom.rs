@@ -0,0 +1,84 @@
+//! Bloom filter over account names
+//!
+//! Stores cannot OR bit sets together, so the filter is kept as the list of
+//! its set bit positions (see `store_account_bloom`) and rebuilt here for
+//! lookups. Positions come from SHA-256 of the account name by double hashing,
+//! which keeps them identical across runs and providers.
+
+use sha2::{Digest, Sha256};
+
+/// Filter size in bits; about 6,800 accounts fit at a 1% false positive rate
+pub const BITS: u32 = 1 << 16;
+
+/// Bit positions set per account
+pub const HASHES: u32 = 7;
+
+/// Bit positions of `account`, `HASHES` of them (duplicates possible)
+pub fn positions(account: &str) -> impl Iterator<Item = u32> {
+    let digest = Sha256::digest(account.as_bytes());
+    let h1 = u32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    let h2 = u32::from_le_bytes([digest[4], digest[5], digest[6], digest[7]]) | 1;
+    (0..HASHES).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % BITS)
+}
+
+/// Bit set rebuilt from set positions
+pub struct Filter {
+    words: Vec<u64>,
+    set_bits: u32,
+}
+
+impl Filter {
+    pub fn from_positions(set: impl IntoIterator<Item = u32>) -> Self {
+        let mut filter = Filter {
+            words: vec![0; (BITS / 64) as usize],
+            set_bits: 0,
+        };
+        for position in set.into_iter().filter(|&p| p < BITS) {
+            let (word, mask) = ((position / 64) as usize, 1u64 << (position % 64));
+            if filter.words[word] & mask == 0 {
+                filter.words[word] |= mask;
+                filter.set_bits += 1;
+            }
+        }
+        filter
+    }
+
+    /// False for accounts never added; true for added ones and false positives
+    pub fn contains(&self, account: &str) -> bool {
+        positions(account).all(|p| self.words[(p / 64) as usize] & (1u64 << (p % 64)) != 0)
+    }
+
+    pub fn set_bits(&self) -> u32 {
+        self.set_bits
+    }
+
+    /// Chance that an account never added is reported, from the fill ratio
+    pub fn false_positive_rate(&self) -> f64 {
+        (f64::from(self.set_bits) / f64::from(BITS)).powi(HASHES as i32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn added_accounts_are_always_found() {
+        let accounts: Vec<String> = (0..1_000).map(|i| format!("acct{}", i)).collect();
+        let filter = Filter::from_positions(accounts.iter().flat_map(|a| positions(a)));
+
+        assert!(accounts.iter().all(|a| filter.contains(a)));
+        let false_positives = (0..1_000)
+            .filter(|i| filter.contains(&format!("other{}", i)))
+            .count();
+        assert!(false_positives < 10, "{}", false_positives);
+        assert!(filter.false_positive_rate() < 0.01);
+    }
+
+    #[test]
+    fn empty_filter_knows_nobody() {
+        let filter = Filter::from_positions([]);
+        assert!(!filter.contains("alice"));
+        assert_eq!(filter.set_bits(), 0);
+    }
+}
@@ -0,0 +1,243 @@
+//! Compact JSON rendering of events for bandwidth-sensitive sinks
+//!
+//! Protobuf already omits default fields on the wire, but JSON sinks render
+//! every field of a message. Here events are rebuilt as schema-less JSON
+//! objects that only carry populated fields: empty strings and lists, zero
+//! numbers and `false` are left out. Field names follow `polaris.proto`.
+
+use serde_json::{Map, Number, Value};
+
+use crate::pb::polaris::v1::event_data::Event as Data;
+use crate::pb::polaris::v1::Event;
+
+/// JSON object under construction, dropping default values unless `full`
+struct Fields {
+    map: Map<String, Value>,
+    full: bool,
+}
+
+impl Fields {
+    fn new(full: bool) -> Self {
+        Fields {
+            map: Map::new(),
+            full,
+        }
+    }
+
+    fn insert(mut self, key: &str, value: Value, is_default: bool) -> Self {
+        if self.full || !is_default {
+            self.map.insert(key.to_string(), value);
+        }
+        self
+    }
+
+    fn str(self, key: &str, value: &str) -> Self {
+        self.insert(key, Value::String(value.to_string()), value.is_empty())
+    }
+
+    fn num<T: Into<Number> + Default + PartialEq>(self, key: &str, value: T) -> Self {
+        let is_default = value == T::default();
+        self.insert(key, Value::Number(value.into()), is_default)
+    }
+
+    fn flag(self, key: &str, value: bool) -> Self {
+        self.insert(key, Value::Bool(value), !value)
+    }
+
+    fn strs(self, key: &str, values: &[String]) -> Self {
+        let list = values.iter().cloned().map(Value::String).collect();
+        self.insert(key, Value::Array(list), values.is_empty())
+    }
+
+    fn list(self, key: &str, values: Vec<Value>) -> Self {
+        let is_default = values.is_empty();
+        self.insert(key, Value::Array(values), is_default)
+    }
+
+    fn obj(self, key: &str, value: Fields) -> Self {
+        self.insert(key, Value::Object(value.map), false)
+    }
+
+    fn into_value(self) -> Value {
+        Value::Object(self.map)
+    }
+}
+
+/// JSON object for `event`; `full` keeps default fields (for size comparisons)
+pub fn event_json(event: &Event, full: bool) -> Value {
+    let f = || Fields::new(full);
+    let data = event
+        .data
+        .as_ref()
+        .and_then(|d| d.event.as_ref())
+        .map(|data| match data {
+            Data::Put(e) => f().obj(
+                "put",
+                f().str("author", &e.author)
+                    .num("type", e.type_)
+                    .str("type_name", &e.type_name)
+                    .str("hash", &e.hash)
+                    .str("parent", &e.parent)
+                    .num("ts", e.ts)
+                    .strs("tags", &e.tags)
+                    .num("expires_at", e.expires_at)
+                    .flag("is_root", e.is_root),
+            ),
+            Data::Attest(e) => f().obj(
+                "attest",
+                f().str("attestor", &e.attestor)
+                    .str("tx_hash", &e.tx_hash)
+                    .num("confirmed_type", e.confirmed_type)
+                    .num("content_type", e.content_type)
+                    .str("confirmed_type_name", &e.confirmed_type_name),
+            ),
+            Data::Vote(e) => f().obj(
+                "vote",
+                f().str("voter", &e.voter)
+                    .str("tx_hash", &e.tx_hash)
+                    .num("val", e.val)
+                    .num("weight", e.weight)
+                    .num("voter_respect", e.voter_respect),
+            ),
+            Data::Finalize(e) => f().obj(
+                "finalize",
+                f().str("tx_hash", &e.tx_hash)
+                    .flag("accepted", e.accepted)
+                    .num("approval_percent", e.approval_percent)
+                    .num("reward_amount", e.reward_amount)
+                    .num("action_ordinal", e.action_ordinal),
+            ),
+            Data::Stake(e) => f().obj(
+                "stake",
+                f().str("account", &e.account)
+                    .str("node_id", &e.node_id)
+                    .str("quantity", &e.quantity)
+                    .num("content_type", e.content_type),
+            ),
+            Data::Unstake(e) => f().obj(
+                "unstake",
+                f().str("account", &e.account)
+                    .str("node_id", &e.node_id)
+                    .str("quantity", &e.quantity)
+                    .str("reverses_tx_hash", &e.reverses_tx_hash)
+                    .num("content_type", e.content_type),
+            ),
+            Data::Like(e) => f().obj(
+                "like",
+                f().str("account", &e.account)
+                    .str("node_id", &e.node_id)
+                    .strs("path", &e.path)
+                    .str("content_author", &e.content_author)
+                    .num("content_type", e.content_type),
+            ),
+            Data::Unlike(e) => f().obj(
+                "unlike",
+                f().str("account", &e.account)
+                    .str("node_id", &e.node_id)
+                    .str("content_author", &e.content_author)
+                    .str("reverses_tx_hash", &e.reverses_tx_hash)
+                    .num("content_type", e.content_type),
+            ),
+            Data::Updrespect(e) => f().obj(
+                "update_respect",
+                f().list(
+                    "updates",
+                    e.updates
+                        .iter()
+                        .map(|u| {
+                            f().str("account", &u.account)
+                                .num("respect", u.respect)
+                                .into_value()
+                        })
+                        .collect(),
+                )
+                .num("election_round", e.election_round)
+                .num("part", e.part)
+                .num("parts", e.parts),
+            ),
+            Data::Reward(e) => f().obj(
+                "reward",
+                f().str("to", &e.to)
+                    .str("quantity", &e.quantity)
+                    .str("memo", &e.memo)
+                    .str("source_action", &e.source_action)
+                    .str("submission_hash", &e.submission_hash)
+                    .num("action_ordinal", e.action_ordinal)
+                    .num("source_action_ordinal", e.source_action_ordinal),
+            ),
+            Data::Paused(e) => f().obj(
+                "paused",
+                f().str("admin", &e.admin).num("timestamp", e.timestamp),
+            ),
+            Data::Unpaused(e) => f().obj(
+                "unpaused",
+                f().str("admin", &e.admin).num("timestamp", e.timestamp),
+            ),
+            Data::OracleSet(e) => f().obj(
+                "oracle_set",
+                f().str("oracle", &e.oracle).str("admin", &e.admin),
+            ),
+        });
+
+    let fields = f()
+        .str("tx_hash", &event.tx_hash)
+        .num("block_num", event.block_num)
+        .num("timestamp", event.timestamp)
+        .str("event_type", &event.event_type);
+    let fields = match data {
+        Some(data) => fields.obj("data", data),
+        None => fields,
+    };
+    fields
+        .str("timestamp_rfc3339", &event.timestamp_rfc3339)
+        .str("network", &event.network)
+        .flag("deferred", event.deferred)
+        .str("fingerprint", &hex::encode(&event.fingerprint))
+        .num("seq", event.seq)
+        .list(
+            "inline_actions",
+            event
+                .inline_actions
+                .iter()
+                .map(|a| {
+                    f().str("name", &a.name)
+                        .str("account", &a.account)
+                        .str("receiver", &a.receiver)
+                        .num("depth", a.depth)
+                        .into_value()
+                })
+                .collect(),
+        )
+        .str("uuid", &event.uuid)
+        .str("order_key", &event.order_key)
+        .into_value()
+}
+
+/// Convert a JSON object into a `google.protobuf.Struct`
+pub fn to_struct(value: Value) -> prost_types::Struct {
+    match to_proto_value(value).kind {
+        Some(prost_types::value::Kind::StructValue(s)) => s,
+        _ => prost_types::Struct::default(),
+    }
+}
+
+fn to_proto_value(value: Value) -> prost_types::Value {
+    use prost_types::value::Kind;
+
+    let kind = match value {
+        Value::Null => Kind::NullValue(0),
+        Value::Bool(b) => Kind::BoolValue(b),
+        Value::Number(n) => Kind::NumberValue(n.as_f64().unwrap_or(0.0)),
+        Value::String(s) => Kind::StringValue(s),
+        Value::Array(items) => Kind::ListValue(prost_types::ListValue {
+            values: items.into_iter().map(to_proto_value).collect(),
+        }),
+        Value::Object(map) => Kind::StructValue(prost_types::Struct {
+            fields: map
+                .into_iter()
+                .map(|(k, v)| (k, to_proto_value(v)))
+                .collect(),
+        }),
+    };
+    prost_types::Value { kind: Some(kind) }
+}
@@ -0,0 +1,62 @@
+//! UTC calendar helpers for bucketing block timestamps.
+//!
+//! Wasm builds have no system clock or timezone database, so dates are derived
+//! arithmetically from Unix seconds (proleptic Gregorian calendar, always UTC).
+
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Convert days since 1970-01-01 into a (year, month, day) civil date.
+///
+/// Algorithm from Howard Hinnant's `civil_from_days`.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// UTC calendar date (`YYYY-MM-DD`) for a Unix timestamp in seconds
+pub fn utc_date(seconds: u64) -> String {
+    let (year, month, day) = civil_from_days((seconds / SECONDS_PER_DAY) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// UTC calendar month (`YYYY-MM`) for a Unix timestamp in seconds
+pub fn utc_month(seconds: u64) -> String {
+    let (year, month, _) = civil_from_days((seconds / SECONDS_PER_DAY) as i64);
+    format!("{:04}-{:02}", year, month)
+}
+
+/// Months since year 0 of a `YYYY-MM` month, for month arithmetic
+pub fn month_index(month: &str) -> Option<i64> {
+    let (year, month) = month.split_once('-')?;
+    let (year, month): (i64, i64) = (year.parse().ok()?, month.parse().ok()?);
+    (1..=12).contains(&month).then_some(year * 12 + month - 1)
+}
+
+/// `YYYY-MM` month of a `month_index`
+pub fn month_from_index(index: i64) -> String {
+    format!(
+        "{:04}-{:02}",
+        index.div_euclid(12),
+        index.rem_euclid(12) + 1
+    )
+}
+
+/// RFC3339 UTC timestamp (`YYYY-MM-DDTHH:MM:SSZ`) for Unix seconds
+pub fn rfc3339(seconds: u64) -> String {
+    let time = seconds % SECONDS_PER_DAY;
+    format!(
+        "{}T{:02}:{:02}:{:02}Z",
+        utc_date(seconds),
+        time / 3_600,
+        time % 3_600 / 60,
+        time % 60
+    )
+}
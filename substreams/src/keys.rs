@@ -0,0 +1,685 @@
+//! Store key schemes
+//!
+//! Every store key is built here so each store's key space is documented in
+//! one place and no two schemes of the same store can overlap. Keys are
+//! `:`-separated segments behind a fixed prefix per scheme. Segments are
+//! Antelope names (`a-z`, `1-5`, `.`), hex hashes, symbol codes, dates or
+//! integers, none of which can contain `:`, so a key is never ambiguous.
+//!
+//! Tags are the exception: they are submitted content, and a block source that
+//! does not enforce the ABI's `name` type could hand over anything. Tag
+//! segments therefore go through `escape_segment`, which keeps keys
+//! well-formed whatever the tag holds.
+
+use std::borrow::Cow;
+
+/// Percent-encode `%`, the `:` key and `;` append-list delimiters, and
+/// control characters (newlines, tabs, C1 codes) byte by byte
+///
+/// Other characters, including non-ASCII letters, are kept. The encoding is
+/// reversible, so distinct tags always give distinct segments.
+pub fn escape_segment(segment: &str) -> Cow<'_, str> {
+    let needs_escape = |c: char| matches!(c, '%' | ':' | ';') || c.is_control();
+    if !segment.contains(needs_escape) {
+        return Cow::Borrowed(segment);
+    }
+
+    let mut escaped = String::with_capacity(segment.len() + 8);
+    for c in segment.chars() {
+        if needs_escape(c) {
+            let mut buf = [0u8; 4];
+            for byte in c.encode_utf8(&mut buf).bytes() {
+                escaped.push_str(&format!("%{:02X}", byte));
+            }
+        } else {
+            escaped.push(c);
+        }
+    }
+    Cow::Owned(escaped)
+}
+
+/// Inverse of `escape_segment`; malformed escapes are kept as they are
+pub fn unescape_segment(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).unwrap_or_else(|_| segment.to_string())
+}
+
+// ============ store_stats ============
+
+/// Event count per event type
+pub fn stats_type(event_type: &str) -> String {
+    format!("type:{}", event_type)
+}
+
+/// Net staked amount per symbol, in the token's smallest unit
+pub fn stats_staked(symbol: &str) -> String {
+    format!("staked:{}", symbol)
+}
+
+/// Stake quantities left out of `staked:{SYMBOL}`, per symbol, or under
+/// `unparsed` for quantities without a readable symbol
+pub fn stats_asset_anomalies(bucket: &str) -> String {
+    format!("anomalies:{}", bucket)
+}
+
+/// Counter bucketed by UTC day (`YYYY-MM-DD`)
+pub fn stats_day(date: &str, counter: &str) -> String {
+    format!("day:{}:{}", date, counter)
+}
+
+// ============ store_daily_active / store_daily_active_count ============
+
+/// Account active on a UTC day
+pub fn daily_active(date: &str, account: &str) -> String {
+    format!("active:{}:{}", date, account)
+}
+
+/// Date of a `daily_active` key
+pub fn parse_daily_active(key: &str) -> Option<&str> {
+    Some(key.strip_prefix("active:")?.split_once(':')?.0)
+}
+
+/// Distinct accounts active on a UTC day
+pub fn daily_active_count(date: &str) -> String {
+    format!("dailyactive:{}", date)
+}
+
+// ============ store_last_active_day / store_window_active ============
+
+/// Latest UTC day (days since the Unix epoch) an account was active
+pub fn last_active_day(account: &str) -> String {
+    format!("lastactiveday:{}", account)
+}
+
+/// Distinct accounts active in the 30 UTC days ending on a date
+pub fn window_active(date: &str) -> String {
+    format!("mau:{}", date)
+}
+
+// ============ store_daily_sentiment ============
+
+/// Vote valence of a UTC day: `up`/`down` vote counts, `stakeup`/`stakedown`
+/// the voters' stake behind them
+pub fn daily_sentiment(date: &str, side: &str) -> String {
+    format!("sentiment:{}:{}", date, side)
+}
+
+// ============ Retention cohorts ============
+
+/// Month (`YYYY-MM`) of an account's first event
+pub fn first_month(account: &str) -> String {
+    format!("firstmonth:{}", account)
+}
+
+/// Account active in a month
+pub fn active_month(account: &str, month: &str) -> String {
+    format!("activemonth:{}:{}", account, month)
+}
+
+/// (account, month) of an `active_month` key
+pub fn parse_active_month(key: &str) -> Option<(&str, &str)> {
+    key.strip_prefix("activemonth:")?.rsplit_once(':')
+}
+
+/// Accounts of the `cohort` month's cohort that were active in `month`; with
+/// `month == cohort`, the cohort's size
+pub fn cohort_active(cohort: &str, month: &str) -> String {
+    format!("retained:{}:{}", cohort, month)
+}
+
+// ============ store_last_block ============
+
+/// `BlockMeta` of the latest processed block
+pub const LAST_BLOCK: &str = "lastblock";
+
+// ============ store_lib / store_pending_events ============
+
+/// Last irreversible block
+pub const LIB: &str = "lib";
+
+/// Events of a block not yet irreversible; zero-padded so a block's key is
+/// never a prefix of another's (the buffer is pruned with `delete_prefix`)
+pub fn pending_block(block_num: u64) -> String {
+    format!("pending:{:010}", block_num)
+}
+
+/// Block of a `pending_block` key
+pub fn parse_pending_block(key: &str) -> Option<u64> {
+    key.strip_prefix("pending:")?.parse().ok()
+}
+
+// ============ store_contract_config ============
+
+/// Latest contract config
+pub const CONTRACT_CONFIG: &str = "config";
+
+// ============ store_block_time ============
+
+/// Timestamp of the latest block
+pub const BLOCK_TIME: &str = "blocktime";
+
+// ============ store_account_activity ============
+
+/// Number of events by an account
+pub fn account_events(account: &str) -> String {
+    format!("account:{}:events", account)
+}
+
+/// Latest block with an event by an account (`store_account_last_block`)
+pub fn account_last_block(account: &str) -> String {
+    format!("account:{}:last_block", account)
+}
+
+// ============ store_stake_symbols / store_stake_symbol_list ============
+
+/// Precision of a staked symbol
+pub fn stake_symbol(symbol: &str) -> String {
+    format!("symbol:{}", symbol)
+}
+
+/// Symbol of a `stake_symbol` key
+pub fn parse_stake_symbol(key: &str) -> Option<&str> {
+    key.strip_prefix("symbol:")
+}
+
+/// List of `{SYMBOL}:{precision}` entries
+pub const STAKE_SYMBOL_LIST: &str = "symbols";
+
+// ============ store_stats_snapshots ============
+
+/// Marker holding the block a snapshot was taken after
+pub fn snapshot_block(block: u64) -> String {
+    format!("snapshot:{}:block", block)
+}
+
+/// `store_stats` counter `key` as of the snapshot at `block`
+pub fn snapshot_counter(block: u64, key: &str) -> String {
+    format!("snapshot:{}:{}", block, key)
+}
+
+// ============ store_rate_checkpoints ============
+
+/// Block number held by a ring slot
+pub fn rate_slot_block(slot: u64) -> String {
+    format!("slot:{}:block", slot)
+}
+
+/// Cumulative count of an event type (or `ALL`) held by a ring slot
+pub fn rate_slot_counter(slot: u64, label: &str) -> String {
+    format!("slot:{}:{}", slot, label)
+}
+
+// ============ store_last_seen ============
+
+/// Block of the most recent event of a type
+pub fn last_seen_block(event_type: &str) -> String {
+    format!("lastseen:{}:block", event_type)
+}
+
+/// Timestamp (Unix seconds) of the most recent event of a type
+pub fn last_seen_time(event_type: &str) -> String {
+    format!("lastseen:{}:time", event_type)
+}
+
+// ============ Per-key feeds (count store + entry store) ============
+
+/// Nodes authored by an account (count)
+pub fn author_nodes(account: &str) -> String {
+    format!("authornodes:{}", account)
+}
+
+/// Node authored by an account, 0 is the oldest
+pub fn author_node(account: &str, index: u64) -> String {
+    format!("authornodes:{}:{}", account, index)
+}
+
+/// Nodes carrying a tag (count)
+pub fn tag_nodes(tag: &str) -> String {
+    format!("tagnodes:{}", escape_segment(tag))
+}
+
+/// Node carrying a tag, 0 is the oldest
+pub fn tag_node(tag: &str, index: u64) -> String {
+    format!("tagnodes:{}:{}", escape_segment(tag), index)
+}
+
+/// Replies to a node (count)
+pub fn children(parent: &str) -> String {
+    format!("children:{}", parent)
+}
+
+/// Reply to a node, 0 is the oldest
+pub fn child(parent: &str, index: u64) -> String {
+    format!("children:{}:{}", parent, index)
+}
+
+/// Events touching a node (count)
+pub fn node_events(node_id: &str) -> String {
+    format!("nodeevents:{}", node_id)
+}
+
+/// Event touching a node, 0 is the oldest
+pub fn node_event(node_id: &str, index: u64) -> String {
+    format!("nodeevents:{}:{}", node_id, index)
+}
+
+/// Events involving an account (count)
+pub fn account_event_feed(account: &str) -> String {
+    format!("accountevents:{}", account)
+}
+
+/// Event involving an account, 0 is the oldest
+pub fn account_event(account: &str, index: u64) -> String {
+    format!("accountevents:{}:{}", account, index)
+}
+
+/// Votes on a submission (count)
+pub fn submission_votes(submission_hash: &str) -> String {
+    format!("subvotes:{}", submission_hash)
+}
+
+/// Vote on a submission, 0 is the oldest
+pub fn submission_vote(submission_hash: &str, index: u64) -> String {
+    format!("subvotes:{}:{}", submission_hash, index)
+}
+
+/// Stake positions opened in a symbol (count)
+pub fn position_openings(symbol: &str) -> String {
+    format!("openings:{}", symbol)
+}
+
+/// Stake position opened in a symbol, 0 is the oldest
+pub fn position_opening(symbol: &str, index: u64) -> String {
+    format!("openings:{}:{}", symbol, index)
+}
+
+// ============ store_tag_cooccurrence / store_related_tags ============
+
+/// Puts carrying both tags (stored for both orderings)
+pub fn cooccur(tag: &str, other: &str) -> String {
+    format!("cooccur:{}:{}", escape_segment(tag), escape_segment(other))
+}
+
+/// (tag, other) of a `cooccur` key, unescaped
+pub fn parse_cooccur(key: &str) -> Option<(String, String)> {
+    let (tag, other) = key.strip_prefix("cooccur:")?.split_once(':')?;
+    Some((unescape_segment(tag), unescape_segment(other)))
+}
+
+/// Tags seen together with a tag, in first co-occurrence order (entries are
+/// escaped, as `;` separates them)
+pub fn related_tags(tag: &str) -> String {
+    format!("related:{}", escape_segment(tag))
+}
+
+/// Tags whose co-occurrence count with a tag reached `2^level`; level 0 is
+/// `related_tags`
+pub fn related_tags_level(tag: &str, level: u32) -> String {
+    match level {
+        0 => related_tags(tag),
+        _ => format!("related:{}:{}", escape_segment(tag), level),
+    }
+}
+
+// ============ store_submission_puts ============
+
+/// Put of a submission, by put hash (the `tx_hash` of attest/vote/finalize)
+pub fn submission_put(hash: &str) -> String {
+    format!("subput:{}", hash)
+}
+
+// ============ store_author_reposts ============
+
+/// Blocks of every put of a hash by an author
+pub fn author_reposts(author: &str, hash: &str) -> String {
+    format!("repost:{}:{}", author, hash)
+}
+
+// ============ store_submission_attestors / store_attestor_outcomes ============
+
+/// Attestors of a submission, by put hash
+pub fn submission_attestors(hash: &str) -> String {
+    format!("attestors:{}", hash)
+}
+
+/// Finalized submissions of an attestor that were accepted (`agree`) or
+/// rejected (`disagree`)
+pub fn attestor_outcome(attestor: &str, outcome: &str) -> String {
+    format!("attestoutcome:{}:{}", attestor, outcome)
+}
+
+// ============ store_registry_nodes / store_node_engagement ============
+
+/// Metadata of a node
+pub fn registry_node(hash: &str) -> String {
+    format!("registry:{}", hash)
+}
+
+/// Likes minus unlikes of a node
+pub fn node_likes(node_id: &str) -> String {
+    format!("likes:{}", node_id)
+}
+
+/// Net stake on a node over all accounts
+pub fn node_stake(node_id: &str, symbol: &str) -> String {
+    format!("nodestake:{}:{}", node_id, symbol)
+}
+
+// ============ store_node_attestations ============
+
+/// Attestations of a node (attest `tx_hash` is the node's put hash)
+pub fn node_attestations(node_id: &str) -> String {
+    format!("nodeattests:{}", node_id)
+}
+
+// ============ store_node_stake_flow ============
+
+/// Amount staked (`side` = `stake`) or unstaked (`unstake`) on a node during
+/// a bucket of `NODE_FLOW_BUCKET_BLOCKS` blocks
+pub fn node_flow(node_id: &str, symbol: &str, bucket: u64, side: &str) -> String {
+    format!("nodeflow:{}:{}:{}:{}", node_id, symbol, bucket, side)
+}
+
+// ============ store_stake_histogram ============
+
+/// Accounts (`accounts`) or their summed stake (`sum`) in a bucket of account
+/// stake totals
+pub fn stake_histogram(symbol: &str, bucket: u32, field: &str) -> String {
+    format!("stakehist:{}:{}:{}", symbol, bucket, field)
+}
+
+// ============ store_like_milestones ============
+
+/// First like that brought a node's like count to `milestone`
+pub fn like_milestone(node_id: &str, milestone: u64) -> String {
+    format!("milestone:{}:{}", node_id, milestone)
+}
+
+// ============ store_account_firsts ============
+
+/// First event of a type involving an account
+pub fn account_first(account: &str, event_type: &str) -> String {
+    format!("first:{}:{}", account, event_type)
+}
+
+// ============ store_like_state / store_node_likers ============
+
+/// Whether an account currently likes a node (1) or has unliked it (0)
+pub fn like_state(node_id: &str, account: &str) -> String {
+    format!("liked:{}:{}", node_id, account)
+}
+
+/// (node_id, account) of a `like_state` key
+pub fn parse_like_state(key: &str) -> Option<(&str, &str)> {
+    key.strip_prefix("liked:")?.split_once(':')
+}
+
+/// Accounts that ever liked a node, in first-like order
+pub fn node_likers(node_id: &str) -> String {
+    format!("likers:{}", node_id)
+}
+
+// ============ store_account_net_likes ============
+
+/// Likes given by an account minus its counted unlikes
+pub fn account_net_likes(account: &str) -> String {
+    format!("account:{}:netlikes", account)
+}
+
+// ============ store_last_like / store_like_session_starts / store_like_session_counts ============
+
+/// Block of an account's latest like
+pub fn last_like(account: &str) -> String {
+    format!("lastlike:{}", account)
+}
+
+/// First block of an account's current like session
+pub fn like_session_start(account: &str) -> String {
+    format!("likesession:{}", account)
+}
+
+/// Likes in the account's session starting at block `start`
+pub fn like_session_count(account: &str, start: u64) -> String {
+    format!("sessionlikes:{}:{}", account, start)
+}
+
+// ============ store_event_seq ============
+
+/// Events emitted so far over the whole stream
+pub const EVENT_SEQ: &str = "seq";
+
+// ============ store_balance_changes / store_balance_changed ============
+
+/// Account stake total changed in a snapshot period
+pub fn balance_change(period: u64, account: &str, symbol: &str) -> String {
+    format!("balchange:{}:{}:{}", period, account, symbol)
+}
+
+/// Period, account and symbol of a `balance_change` key
+pub fn parse_balance_change(key: &str) -> Option<(u64, &str, &str)> {
+    let mut parts = key.strip_prefix("balchange:")?.splitn(3, ':');
+    let period = parts.next()?.parse().ok()?;
+    Some((period, parts.next()?, parts.next()?))
+}
+
+/// List of `{account}:{SYMBOL}:{precision}` entries changed in a snapshot period
+pub fn balance_changed(period: u64) -> String {
+    format!("balchanged:{}", period)
+}
+
+// ============ store_pulse_active / store_pulse_counters ============
+
+/// Account active in a pulse period
+pub fn pulse_active(period: u64, account: &str) -> String {
+    format!("pulseactive:{}:{}", period, account)
+}
+
+/// Period of a `pulse_active` key
+pub fn parse_pulse_active(key: &str) -> Option<u64> {
+    key.strip_prefix("pulseactive:")?
+        .split_once(':')?
+        .0
+        .parse()
+        .ok()
+}
+
+/// Counter of a pulse period: `events`, `active` or `staked:{SYMBOL}`
+pub fn pulse_counter(period: u64, counter: &str) -> String {
+    format!("pulse:{}:{}", period, counter)
+}
+
+// ============ store_round_members / store_round_participation ============
+
+/// Account that received respect in an election round
+pub fn round_member(round: u64, account: &str) -> String {
+    format!("roundmember:{}:{}", round, account)
+}
+
+/// Round and account of a `round_member` key
+pub fn parse_round_member(key: &str) -> Option<(u64, &str)> {
+    let (round, account) = key.strip_prefix("roundmember:")?.split_once(':')?;
+    Some((round.parse().ok()?, account))
+}
+
+/// Distinct accounts that received respect in an election round
+pub fn round_participation(round: u64) -> String {
+    format!("round:{}", round)
+}
+
+// ============ store_round_respect / store_round_accounts ============
+
+/// Respect of an account in an election round
+pub fn round_respect(round: u64, account: &str) -> String {
+    format!("roundrespect:{}:{}", round, account)
+}
+
+/// Accounts that received respect in an election round, in arrival order
+pub fn round_accounts(round: u64) -> String {
+    format!("roundaccounts:{}", round)
+}
+
+// ============ store_account_bloom_bits / store_account_bloom ============
+
+/// Bit of the account Bloom filter, present once set
+pub fn bloom_bit(position: u32) -> String {
+    format!("bloombit:{}", position)
+}
+
+/// Position of a `bloom_bit` key
+pub fn parse_bloom_bit(key: &str) -> Option<u32> {
+    key.strip_prefix("bloombit:")?.parse().ok()
+}
+
+/// Set bit positions of the account Bloom filter
+pub const ACCOUNT_BLOOM: &str = "bloom";
+
+// ============ Lookup stores ============
+
+/// Payout record of a finalized submission
+pub fn payout(submission_hash: &str) -> String {
+    format!("payout:{}", submission_hash)
+}
+
+/// Votes for (`for`) or against (`against`) a submission
+pub fn vote_tally(submission_hash: &str, side: &str) -> String {
+    format!("tally:{}:{}", submission_hash, side)
+}
+
+/// First crossing of the approval threshold by a submission's tally
+pub fn threshold_crossing(submission_hash: &str) -> String {
+    format!("crossed:{}", submission_hash)
+}
+
+/// Summed vote weight for (`for`) or against (`against`) a submission
+pub fn vote_weight(submission_hash: &str, side: &str) -> String {
+    format!("voteweight:{}:{}", submission_hash, side)
+}
+
+/// Signed weight an account's current vote adds to a submission's tally
+pub fn vote_contribution(submission_hash: &str, voter: &str) -> String {
+    format!("votecontrib:{}:{}", submission_hash, voter)
+}
+
+/// Author of a node
+pub fn node_author(hash: &str) -> String {
+    format!("node:{}", hash)
+}
+
+/// Most recent like of an account on a node
+pub fn reversible_like(account: &str, node_id: &str) -> String {
+    format!("like:{}:{}", account, node_id)
+}
+
+/// Most recent stake of an account on a node
+pub fn reversible_stake(account: &str, node_id: &str) -> String {
+    format!("stake:{}:{}", account, node_id)
+}
+
+/// Net stake of an account on a node
+pub fn stake_position(account: &str, node_id: &str, symbol: &str) -> String {
+    format!("position:{}:{}:{}", account, node_id, symbol)
+}
+
+/// Net stake of an account over all nodes
+pub fn account_staked(account: &str, symbol: &str) -> String {
+    format!("staked:{}:{}", account, symbol)
+}
+
+/// Lockup of an account's stake on a node
+pub fn stake_lock(account: &str, node_id: &str, symbol: &str) -> String {
+    format!("lock:{}:{}:{}", account, node_id, symbol)
+}
+
+/// Latest opening of a stake position (zero to positive stake)
+pub fn open_since(account: &str, node_id: &str, symbol: &str) -> String {
+    format!("opensince:{}:{}:{}", account, node_id, symbol)
+}
+
+/// Stake positions an account has ever opened, `{node_id}:{SYMBOL}` entries
+pub fn account_positions(account: &str) -> String {
+    format!("positions:{}", account)
+}
+
+/// Oracle account in force
+pub const ORACLE: &str = "oracle";
+
+/// Latest respect score of an account
+pub fn respect(account: &str) -> String {
+    format!("respect:{}", account)
+}
+
+/// Keys created so far in a tracked store
+pub fn store_keys(store: &str) -> String {
+    format!("keys:{}", store)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADVERSARIAL: [&str; 8] = [
+        "a:b",
+        "a%3Ab",
+        "line\nbreak",
+        "tab\there",
+        "list;entry",
+        "100%",
+        "nel\u{85}",
+        "ünïcødé",
+    ];
+
+    #[test]
+    fn escaped_tags_round_trip_without_delimiters() {
+        for tag in ADVERSARIAL {
+            let escaped = escape_segment(tag);
+            assert!(
+                !escaped.contains([':', ';']) && !escaped.contains(char::is_control),
+                "{:?} -> {:?}",
+                tag,
+                escaped
+            );
+            assert_eq!(unescape_segment(&escaped), tag);
+        }
+        assert!(matches!(escape_segment("rock"), Cow::Borrowed("rock")));
+        assert_eq!(escape_segment("ünïcødé"), "ünïcødé");
+    }
+
+    #[test]
+    fn pending_blocks_are_not_prefixes_of_each_other() {
+        assert!(!pending_block(123).starts_with(&pending_block(12)));
+        assert_eq!(parse_pending_block(&pending_block(12)), Some(12));
+        assert!(pending_block(9) < pending_block(10));
+    }
+
+    #[test]
+    fn adversarial_tags_never_collide() {
+        let keys: std::collections::HashSet<String> =
+            ADVERSARIAL.iter().map(|tag| tag_nodes(tag)).collect();
+        assert_eq!(keys.len(), ADVERSARIAL.len());
+
+        // A colon in a tag cannot forge an entry key of another tag's feed
+        assert_ne!(tag_nodes("a:0"), tag_node("a", 0));
+        assert_eq!(
+            parse_cooccur(&cooccur("a:b", "c")),
+            Some(("a:b".to_string(), "c".to_string()))
+        );
+    }
+}
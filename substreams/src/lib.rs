@@ -1,17 +1,57 @@
+// The handler macros expose raw-pointer `extern "C"` entry points by design.
+#![allow(clippy::not_unsafe_ptr_arg_deref)]
+
 mod abi;
+mod asset;
+mod bloom;
+mod compact;
+mod datetime;
+mod keys;
+mod params;
 mod pb;
 
+use std::collections::HashMap;
+
 use substreams::errors::Error;
 use substreams::log;
+use substreams::pb::substreams::store_delta::Operation;
+use substreams::pb::substreams::Clock;
 use substreams::prelude::*;
-use substreams::store::{StoreAddInt64, StoreGetInt64};
+use substreams::store::{
+    DeltaArray, DeltaInt64, DeltaProto, DeltaString, Deltas, StoreAddInt64, StoreGetArray,
+    StoreGetInt64, StoreMaxInt64, StoreSetIfNotExistsInt64,
+};
 use substreams_antelope::pb::Block;
 use substreams_antelope::Action;
 
+use params::Params;
 use pb::polaris::v1::{
-    AnchoredEvent, AnchoredEvents, AttestEvent, Event, EventData, Events, FinalizeEvent,
-    LikeEvent, PutEvent, RespectUpdate, StakeEvent, Stats, UnlikeEvent, UnstakeEvent,
-    UpdateRespectEvent, VoteEvent,
+    AccountExport, AccountKnown, AccountNetLikes, AccountNetLikesList, AccountStakePosition,
+    ActionRef, ActivityPulse, AnchoredEvent, AnchoredEvents, AssetAnomalies, AssetAnomaly,
+    AttestEvent, AttestorAccuracies, AttestorAccuracy, AuthorNode, AuthorNodes, BalanceSnapshot,
+    BalanceSnapshots, BlockEvents, BlockGapEvent, BlockMeta, BrigadingSignal, BrigadingSignals,
+    CappedEvents, CompactEvents, ContractConfig, ContractPausedEvent, ContractUnpausedEvent,
+    ControversialSubmission, ControversialSubmissions, CurrentOracle, DailyAcceptance, DailyCsvRow,
+    DailySentiment, DailyStickiness, EngagedSupporter, EngagedSupporters, Event, EventData,
+    EventFrames, EventLiveness, EventRate, EventRates, Events, FiatValuedEvent, FiatValuedEvents,
+    FinalizeEvent, FinalizeLatencies, FinalizeLatency, FinalizePayout, FinalizePayouts,
+    FirstTimeEvent, FirstTimeEvents, HypotheticalFinalize, HypotheticalFinalizes, InlineActionRef,
+    InvariantViolation, InvariantViolations, LargeStakeEvent, LargeStakeEvents, LikeBreadcrumb,
+    LikeBreadcrumbs, LikeEvent, LikeMilestoneEvent, LikeMilestones, LikeSession, LikeSessions,
+    Liveness, LockedStake, LockedStakes, NodeEventRef, NodeExitSignal, NodeExitSignals,
+    NodeHistory, NodeReplyCount, NodeReplyCounts, NodeScore, NodeScores, OldestStake, OldestStakes,
+    OracleSetEvent, OrphanUnlikeEvent, OrphanUnlikeEvents, OutputView, PathBreadcrumb,
+    PositionOpening, PutEvent, RegistryNode, RegistryRow, RegistryState, RelatedTag, RelatedTags,
+    RepostSpamEvent, RepostSpamEvents, RespectDelta, RespectDeltas, RespectScore, RespectUpdate,
+    RetentionCohort, RetentionCohorts, RewardEvent, RoundParticipation, RoundParticipations,
+    ScoreWeights, SearchResults, SelfAttestationEvent, SelfAttestations, Sentiment,
+    StakeChurnEvent, StakeChurnEvents, StakeEvent, StakeFlow, StakeGini, StakeLock,
+    StakeReconciliation, StakeReconciliations, StakeRespectPair, StakeRespectPairs, Stats,
+    Stickiness, StoreKeyCount, StoreSizeEstimate, SubmissionPut, SubmissionVote, TableDelta,
+    TableDeltas, TaggedNode, ThreadEdge, ThreadNode, ThreadTree, ThresholdCrossedEvent,
+    ThresholdCrossings, TimeDrift, TokenAmount, TransactionEventGroups, TransactionEvents,
+    TransactionSummaries, TransactionSummary, UnlikeEvent, UnstakeEvent, UpdateRespectEvent,
+    VoteEvent, WindowedStats,
 };
 
 /// Map module: Extract all Polaris Music Registry events from blocks
@@ -21,24 +61,492 @@ use pb::polaris::v1::{
 /// Defers string allocations (tx_hash) until a matching action is found.
 #[substreams::handlers::map]
 fn map_events(params: String, block: Block) -> Result<Events, Error> {
-    let contract_account = if params.is_empty() {
-        "polaris"
+    block_events(&params, &block, |line| log::info!("{}", line))
+}
+
+/// `map_events` output for `block`, handing each log line to `report`
+fn block_events(
+    params: &str,
+    block: &Block,
+    mut report: impl FnMut(String),
+) -> Result<Events, Error> {
+    let params = Params::parse(params);
+    let contract_account = params.contract();
+    let with_rfc3339 = params.parse_or("timestamp_rfc3339", false)?;
+    let network = params.get("network").unwrap_or_default();
+    let include_deferred = match params.get("deferred").unwrap_or("include") {
+        "include" => true,
+        "exclude" => false,
+        other => {
+            return Err(anyhow::anyhow!(
+                "invalid value for param `deferred`: {:?} (expected include or exclude)",
+                other
+            ))
+        }
+    };
+
+    let with_fingerprint = params.parse_or("fingerprint", false)?;
+    let include_inline = params.parse_or("include_inline", false)?;
+    let with_uuid = params.parse_or("uuid", false)?;
+    let with_order_key = params.parse_or("order_key", false)?;
+    if params.parse_or("strict_actions", false)? {
+        for (trx_id, name) in unknown_actions(block, contract_account) {
+            report(format!(
+                "ERROR: unhandled action {}::{} in trx {} (block {})",
+                contract_account, name, trx_id, block.number
+            ));
+        }
+    }
+
+    let mut events: Vec<Event> = extract_events(
+        block,
+        contract_account,
+        include_deferred,
+        include_inline,
+        with_uuid,
+        with_order_key,
+    )
+    .into_iter()
+    .map(|mut event| {
+        if with_rfc3339 {
+            event.timestamp_rfc3339 = datetime::rfc3339(event.timestamp);
+        }
+        event.network = network.to_string();
+        event
+    })
+    .collect();
+    if with_fingerprint {
+        set_fingerprints(&mut events);
+    }
+    // After fingerprinting, so filtered events keep their unfiltered prints
+    if let Some(node) = params.get("node") {
+        events.retain(|event| event_node(event).is_some_and(|id| id.eq_ignore_ascii_case(node)));
+    }
+
+    report(format!(
+        "Extracted {} events from block {}",
+        events.len(),
+        block.number
+    ));
+
+    Ok(Events { events })
+}
+
+/// Map module: `map_events` output with default fields dropped, for JSON sinks
+///
+/// Each event becomes a `google.protobuf.Struct` that only carries populated
+/// fields, so JSON rendering skips `expires_at: 0`, `weight: 0`, empty
+/// strings and the like. Binary consumers should keep using `map_events`.
+#[substreams::handlers::map]
+fn map_events_compact(events: Events) -> Result<CompactEvents, Error> {
+    Ok(compact_events(&events))
+}
+
+/// Compact JSON rendering of `events` (see `compact::event_json`)
+fn compact_events(events: &Events) -> CompactEvents {
+    CompactEvents {
+        events: events
+            .events
+            .iter()
+            .map(|event| compact::to_struct(compact::event_json(event, false)))
+            .collect(),
+    }
+}
+
+/// Map module: One configurable view of the package
+///
+/// Params: `view=events|stats|json` (default events), so deployment tooling
+/// can switch output shapes without rewiring the manifest. `events` is
+/// `map_events`, `stats` is `map_stats` and `json` is `map_events_compact`.
+/// `graph` and `db` are rejected: this package has no such representation,
+/// and database sinks consume `map_events` directly.
+#[substreams::handlers::map]
+fn map_output(
+    params: String,
+    events: Events,
+    stats: StoreGetInt64,
+    symbols: StoreGetArray<String>,
+) -> Result<OutputView, Error> {
+    use pb::polaris::v1::output_view::View;
+
+    let view = match Params::parse(&params).get("view").unwrap_or("events") {
+        "events" => View::Events(events),
+        "stats" => View::Stats(current_stats(&stats, &symbols)),
+        "json" => View::Json(compact_events(&events)),
+        view @ ("graph" | "db") => {
+            return Err(anyhow::anyhow!(
+                "view `{}` is not provided by this package; use events, stats or json",
+                view
+            ))
+        }
+        view => return Err(anyhow::anyhow!("unknown view: {:?}", view)),
+    };
+    Ok(OutputView { view: Some(view) })
+}
+
+/// Map module: Each `map_events` event as its own encoded `Event` frame
+///
+/// Substreams emits one output per module per block, so events cannot leave
+/// as separate messages. Each `frames` entry is one encoded `Event`, and on
+/// the wire they form a sequence of tag + length-prefixed records. A streaming
+/// sink can therefore walk and decode them one at a time without building the
+/// whole `Events` batch.
+#[substreams::handlers::map]
+fn map_event_frames(events: Events) -> Result<EventFrames, Error> {
+    Ok(EventFrames {
+        frames: events
+            .events
+            .iter()
+            .map(prost::Message::encode_to_vec)
+            .collect(),
+    })
+}
+
+/// Store module: Running count of all events emitted so far
+///
+/// Key: `seq` (see `map_sequenced_events`).
+#[substreams::handlers::store]
+fn store_event_seq(events: Events, store: StoreAddInt64) {
+    if !events.events.is_empty() {
+        store.add(0, keys::EVENT_SEQ, events.events.len() as i64);
+    }
+}
+
+/// Map module: `map_events` events with their global sequence number
+///
+/// `seq` counts events from 0 over the whole stream:
+/// `seq = events before this block + position in block`. A reorg rewinds the
+/// counter, so sequence numbers of undone blocks are handed out again.
+#[substreams::handlers::map]
+fn map_sequenced_events(events: Events, seq: StoreGetInt64) -> Result<Events, Error> {
+    let mut events = events.events;
+    // The store already counts this block's events
+    let total = seq.get_last(keys::EVENT_SEQ).unwrap_or(0);
+    let base = (total - events.len() as i64).max(0) as u64;
+    for (position, event) in events.iter_mut().enumerate() {
+        event.seq = base + position as u64;
+    }
+    Ok(Events { events })
+}
+
+/// Map module: Header summary of each block, in the usual `BlockMeta` shape
+#[substreams::handlers::map]
+fn map_block_meta(block: Block) -> Result<BlockMeta, Error> {
+    Ok(block_meta(&block))
+}
+
+/// Map module: `map_events` events together with their block's `BlockMeta`
+///
+/// For sinks wired for standard Antelope substreams, which expect block
+/// metadata next to the payload.
+#[substreams::handlers::map]
+fn map_block_events(block: Block, events: Events) -> Result<BlockEvents, Error> {
+    Ok(BlockEvents {
+        meta: Some(block_meta(&block)),
+        events: events.events,
+    })
+}
+
+/// `BlockMeta` of `block`, empty header fields when the header is missing
+fn block_meta(block: &Block) -> BlockMeta {
+    let header = block.header.as_ref();
+    BlockMeta {
+        number: u64::from(block.number),
+        id: block.id.clone(),
+        parent_id: header.map(|h| h.previous.clone()).unwrap_or_default(),
+        timestamp: header.and_then(|h| h.timestamp),
+        producer: header.map(|h| h.producer.clone()).unwrap_or_default(),
+    }
+}
+
+/// Map module: Largest drift between action trace times and the block header
+///
+/// Params: `threshold_ms=<n>` (default 0). Emits only for blocks whose
+/// largest drift exceeds the threshold, for alerting on misbehaving producers
+/// or replays. Blocks without a header timestamp emit nothing.
+#[substreams::handlers::map]
+fn map_time_drift(params: String, block: Block) -> Result<Option<TimeDrift>, Error> {
+    let threshold_ms: u64 = Params::parse(&params).parse_or("threshold_ms", 0)?;
+    Ok(time_drift(&block).filter(|drift| drift.drift_ms.unsigned_abs() > threshold_ms))
+}
+
+/// Store module: `BlockMeta` of the latest processed block
+///
+/// Key: `lastblock`. Read with `get_first`, it is the previous block's.
+#[substreams::handlers::store]
+fn store_last_block(block: Block, store: StoreSetProto<BlockMeta>) {
+    store.set(0, keys::LAST_BLOCK, &block_meta(&block));
+}
+
+/// Map module: Blocks that do not directly follow the previous one
+///
+/// Emits a `BlockGapEvent` when the block number is not the previous block's
+/// plus one, or is but names another parent. Independent of event
+/// extraction: only the block header is read.
+#[substreams::handlers::map]
+fn map_block_continuity(
+    block: Block,
+    last_block: StoreGetProto<BlockMeta>,
+) -> Result<Option<BlockGapEvent>, Error> {
+    Ok(last_block
+        .get_first(keys::LAST_BLOCK)
+        .and_then(|previous| block_gap(&previous, &block_meta(&block))))
+}
+
+/// Discontinuity between consecutive processed blocks `previous` and `current`
+fn block_gap(previous: &BlockMeta, current: &BlockMeta) -> Option<BlockGapEvent> {
+    let expected = previous.number.saturating_add(1);
+    let kind = if current.number > expected {
+        "gap"
+    } else if current.number < expected {
+        "rewind"
+    } else if !previous.id.is_empty()
+        && !current.parent_id.is_empty()
+        && current.parent_id != previous.id
+    {
+        "fork"
     } else {
-        &params
+        return None;
     };
+    Some(BlockGapEvent {
+        expected,
+        got: current.number,
+        kind: kind.to_string(),
+        previous_id: previous.id.clone(),
+        parent_id: current.parent_id.clone(),
+    })
+}
+
+/// Executed action of `block` whose `block_time` is furthest from the header's
+fn time_drift(block: &Block) -> Option<TimeDrift> {
+    let header_ms = timestamp_ms(block.header.as_ref()?.timestamp.as_ref()?);
+    block
+        .transaction_traces()
+        .flat_map(|trx| &trx.action_traces)
+        .filter_map(|trace| Some((trace, timestamp_ms(trace.block_time.as_ref()?) - header_ms)))
+        .max_by_key(|(_, drift)| drift.unsigned_abs())
+        .map(|(trace, drift_ms)| TimeDrift {
+            block_num: u64::from(block.number),
+            drift_ms,
+            transaction_id: trace.transaction_id.clone(),
+            action_ordinal: trace.action_ordinal,
+        })
+}
+
+fn timestamp_ms(timestamp: &prost_types::Timestamp) -> i64 {
+    timestamp.seconds * 1000 + i64::from(timestamp.nanos) / 1_000_000
+}
+
+/// Store value of a block number
+///
+/// Block numbers are `u64` everywhere: proto fields, keys and event data.
+/// Antelope heights are `u32`, so int64 store values hold them losslessly;
+/// the conversion saturates instead of wrapping should that ever change.
+fn block_value(block_num: u64) -> i64 {
+    i64::try_from(block_num).unwrap_or(i64::MAX)
+}
+
+/// Block number of a store value written by `block_value`
+fn value_block(value: i64) -> u64 {
+    u64::try_from(value).unwrap_or(0)
+}
+
+/// Last irreversible block as seen by `block`: Savanna's `finality_lib` once
+/// the chain runs it, the legacy DPoS value before
+fn block_lib(block: &Block) -> u64 {
+    match block.finality_lib {
+        0 => u64::from(block.dpos_irreversible_blocknum),
+        lib => u64::from(lib),
+    }
+}
+
+/// Store module: Last irreversible block, for the finality buffer
+///
+/// Key: `lib`. Readers take the previous block's value with `get_first`.
+#[substreams::handlers::store]
+fn store_lib(block: Block, store: StoreSetInt64) {
+    store.set(0, keys::LIB, &block_value(block_lib(&block)));
+}
+
+/// Store module: Events waiting for their block to become irreversible
+///
+/// Key: `pending:{block_num}` (zero-padded) holding the block's events.
+/// Blocks without events are not written. Each block deletes the blocks its
+/// LIB advance made final, and `map_final_events` releases them from those
+/// deletions, so the buffer only ever holds reversible blocks.
+#[substreams::handlers::store]
+fn store_pending_events(
+    block: Block,
+    events: Events,
+    lib: StoreGetInt64,
+    store: StoreSetProto<Events>,
+) {
+    let current_lib = block_lib(&block);
+    // On the first block nothing is buffered below its LIB yet
+    let previous_lib = lib.get_first(keys::LIB).map_or(current_lib, value_block);
+
+    for final_block in previous_lib + 1..=current_lib {
+        store.delete_prefix(0, &keys::pending_block(final_block));
+    }
+    if !events.events.is_empty() {
+        store.set(1, keys::pending_block(u64::from(block.number)), &events);
+    }
+}
+
+/// Map module: `map_events` output held back until its block is irreversible
+///
+/// Emits, in block order, the events of every block that became final with
+/// this block. See the README for the latency this adds and the fork
+/// behaviour of the carrying block.
+#[substreams::handlers::map]
+fn map_final_events(pending: Deltas<DeltaProto<Events>>) -> Result<Events, Error> {
+    let mut released: Vec<(u64, Vec<Event>)> = pending
+        .deltas
+        .into_iter()
+        .filter(|delta| delta.operation == Operation::Delete)
+        .filter_map(|delta| {
+            let block_num = keys::parse_pending_block(&delta.key)?;
+            Some((block_num, delta.old_value.events))
+        })
+        .collect();
+    released.sort_by_key(|(block_num, _)| *block_num);
+
+    Ok(Events {
+        events: released
+            .into_iter()
+            .flat_map(|(_, events)| events)
+            .collect(),
+    })
+}
+
+/// Map module: `map_events` with high-frequency event types downsampled
+///
+/// Params: `sample=<type>:<n>,...` (e.g. `sample=like:10,vote:4`) keeps about
+/// 1 in `n` events of each listed type; other types pass through untouched.
+/// Sampling lives outside `map_events` so the stores built from it stay exact.
+#[substreams::handlers::map]
+fn map_sampled_events(params: String, events: Events) -> Result<Events, Error> {
+    let rates = sample_rates(Params::parse(&params).get("sample").unwrap_or_default())?;
+    Ok(Events {
+        events: events
+            .events
+            .into_iter()
+            .filter(|event| match rates.get(&event.event_type) {
+                Some(&rate) => sample_keep(&event.tx_hash, rate),
+                None => true,
+            })
+            .collect(),
+    })
+}
+
+/// Map module: `map_events` with at most `max_events_per_block` events
+///
+/// Params: `max_events_per_block=<n>` (default: unlimited). A larger block
+/// keeps its first `n` events, in block order, and reports the rest in
+/// `dropped`. A safety valve for sinks, separate from `map_events` so the
+/// stores built from it never lose events.
+#[substreams::handlers::map]
+fn map_capped_events(params: String, events: Events) -> Result<CappedEvents, Error> {
+    let params = Params::parse(&params);
+    let cap = params.parse_or("max_events_per_block", usize::MAX)?;
+
+    let mut events = events.events;
+    let dropped = events.len().saturating_sub(cap);
+    if dropped > 0 {
+        log::info!(
+            "Truncating block {} to {} events, dropping {}",
+            events[0].block_num,
+            cap,
+            dropped
+        );
+        events.truncate(cap);
+    }
+
+    Ok(CappedEvents {
+        events,
+        truncated: dropped > 0,
+        dropped: dropped as u64,
+    })
+}
+
+/// Parse a `sample` param into per-event-type rates, keyed by `event_type`
+fn sample_rates(raw: &str) -> Result<HashMap<String, u64>, Error> {
+    raw.split(',')
+        .filter(|entry| !entry.trim().is_empty())
+        .map(|entry| {
+            let invalid = || anyhow::anyhow!("invalid `sample` entry: {:?}", entry);
+            let (event_type, rate) = entry.split_once(':').ok_or_else(invalid)?;
+            let rate: u64 = rate.trim().parse().map_err(|_| invalid())?;
+            if rate == 0 {
+                return Err(invalid());
+            }
+            Ok((event_type.trim().to_ascii_uppercase(), rate))
+        })
+        .collect()
+}
+
+/// Whether a sampled event is kept: 1 in `rate` transactions by hash
+///
+/// Depends on the transaction id alone, so the choice is the same on every run
+/// and all events of a kept transaction are kept together.
+fn sample_keep(tx_hash: &str, rate: u64) -> bool {
+    use sha2::{Digest, Sha256};
+
+    let digest = Sha256::digest(tx_hash.as_bytes());
+    let bucket = u64::from_le_bytes(digest[..8].try_into().expect("digest is 32 bytes"));
+    bucket.is_multiple_of(rate)
+}
 
-    // PERF-05: Use block.action_traces() iterator (already filters for executed transactions)
-    // instead of manual nested loops with Vec::push
-    let events: Vec<Event> = block
+/// Events for all contract actions in `block`, before per-event params apply
+///
+/// PERF-05: Use block.action_traces() iterator (already filters for executed transactions)
+/// instead of manual nested loops with Vec::push
+///
+/// Deferred transactions appear as their own `scheduled` trace in the block
+/// they execute in, with ordinary action traces, so they are extracted like
+/// any other and flagged `deferred`. Their scheduling trace (status
+/// `delayed`) and failed runs (`soft_fail`/`hard_fail`, where only `onerror`
+/// executes) are not executed transactions and never yield events.
+fn extract_events(
+    block: &Block,
+    contract_account: &str,
+    include_deferred: bool,
+    include_inline: bool,
+    with_uuid: bool,
+    with_order_key: bool,
+) -> Vec<Event> {
+    block
         .action_traces()
-        .filter_map(|(action_trace, trx)| {
+        .flat_map(|(action_trace, trx)| {
             // Only process actions received by our contract
             if action_trace.receiver != contract_account {
-                return None;
+                return Vec::new();
+            }
+            if trx.scheduled && !include_deferred {
+                return Vec::new();
             }
 
-            let action = action_trace.action.as_ref()?;
-            let block_num = block.number as u64;
+            let Some(action) = action_trace.action.as_ref() else {
+                return Vec::new();
+            };
+            // Never valid on chain: points at a broken decoder upstream
+            if action.name.is_empty() {
+                log::info!(
+                    "WARN: action with empty name received by {} in trx {} (block {})",
+                    action_trace.receiver,
+                    trx.id,
+                    block.number
+                );
+                return Vec::new();
+            }
+            // A notification (`require_recipient`) of another account's action
+            // repeats an action already counted where it executed. Only token
+            // transfers are consumed as notifications.
+            if action.account != contract_account && action.name != "transfer" {
+                return Vec::new();
+            }
+
+            let block_num = u64::from(block.number);
             let timestamp = block
                 .header
                 .as_ref()
@@ -46,30 +554,114 @@ fn map_events(params: String, block: Block) -> Result<Events, Error> {
                 .map(|t| t.seconds as u64)
                 .unwrap_or(0);
 
-            match action.name.as_str() {
-                "put" => extract_put_event(&trx.id, block_num, timestamp, action_trace),
+            let event = match action.name.as_str() {
+                "put" => {
+                    extract_put_event(trx, block_num, timestamp, action_trace, contract_account)
+                }
                 "attest" => extract_attest_event(&trx.id, block_num, timestamp, action_trace),
                 "vote" => extract_vote_event(&trx.id, block_num, timestamp, action_trace),
-                "finalize" => extract_finalize_event(&trx.id, block_num, timestamp, action_trace),
+                "finalize" => extract_finalize_event(
+                    trx,
+                    block_num,
+                    timestamp,
+                    action_trace,
+                    contract_account,
+                ),
                 "stake" => extract_stake_event(&trx.id, block_num, timestamp, action_trace),
                 "unstake" => extract_unstake_event(&trx.id, block_num, timestamp, action_trace),
                 "like" => extract_like_event(&trx.id, block_num, timestamp, action_trace),
                 "unlike" => extract_unlike_event(&trx.id, block_num, timestamp, action_trace),
+                // May split into several events, see MAX_RESPECT_UPDATES
                 "updrespect" => {
-                    extract_update_respect_event(&trx.id, block_num, timestamp, action_trace)
+                    let mut events =
+                        extract_update_respect_events(&trx.id, block_num, timestamp, action_trace);
+                    let split = events.len() > 1;
+                    for (part, event) in events.iter_mut().enumerate() {
+                        event.deferred = trx.scheduled;
+                        if include_inline {
+                            event.inline_actions = inline_actions(trx, action_trace);
+                        }
+                        if with_order_key {
+                            event.order_key = order_key(
+                                block.number,
+                                trx.index,
+                                action_trace.action_ordinal,
+                                part as u32,
+                            );
+                        }
+                        if with_uuid {
+                            let part = split.then_some(part);
+                            event.uuid = event_uuid(event, action_trace.action_ordinal, part);
+                        }
+                    }
+                    return events;
                 }
-                _ => None, // Ignore other actions (setoracle, init, etc.)
-            }
+                "pause" => extract_pause_event(&trx.id, block_num, timestamp, action_trace),
+                "unpause" => extract_unpause_event(&trx.id, block_num, timestamp, action_trace),
+                "setoracle" => {
+                    extract_set_oracle_event(&trx.id, block_num, timestamp, action_trace)
+                }
+                // Token transfer notifications (contract is `from` on payouts)
+                "transfer" => {
+                    extract_reward_event(trx, block_num, timestamp, action_trace, contract_account)
+                }
+                _ => None, // init and unknown actions (reported with strict_actions)
+            };
+            event
+                .map(|mut event| {
+                    event.deferred = trx.scheduled;
+                    if include_inline {
+                        event.inline_actions = inline_actions(trx, action_trace);
+                    }
+                    if with_order_key {
+                        event.order_key =
+                            order_key(block.number, trx.index, action_trace.action_ordinal, 0);
+                    }
+                    if with_uuid {
+                        event.uuid = event_uuid(&event, action_trace.action_ordinal, None);
+                    }
+                    event
+                })
+                .into_iter()
+                .collect()
         })
-        .collect();
+        .collect()
+}
 
-    log::info!(
-        "Extracted {} events from block {}",
-        events.len(),
-        block.number
-    );
+/// Contract actions `extract_events` handles or deliberately skips
+///
+/// Keep in sync with the match in `extract_events`; `init` is known but emits
+/// no event.
+const KNOWN_ACTIONS: [&str; 13] = [
+    "put",
+    "attest",
+    "vote",
+    "finalize",
+    "stake",
+    "unstake",
+    "like",
+    "unlike",
+    "updrespect",
+    "pause",
+    "unpause",
+    "setoracle",
+    "init",
+];
 
-    Ok(Events { events })
+/// (trx id, action name) of contract actions not in `KNOWN_ACTIONS`
+///
+/// Backs the `strict_actions` param of `map_events`, which logs actions a new
+/// contract version added before extractors exist for them.
+fn unknown_actions<'a>(block: &'a Block, contract_account: &str) -> Vec<(&'a str, &'a str)> {
+    block
+        .action_traces()
+        .filter(|(trace, _)| trace.receiver == contract_account)
+        .filter_map(|(trace, trx)| {
+            let action = trace.action.as_ref()?;
+            (action.account == contract_account && !KNOWN_ACTIONS.contains(&action.name.as_str()))
+                .then_some((trx.id.as_str(), action.name.as_str()))
+        })
+        .collect()
 }
 
 /// Map module: Extract anchored events with full blockchain provenance (T5/T6)
@@ -82,8 +674,6 @@ fn map_events(params: String, block: Block) -> Result<Events, Error> {
 /// Pre-computes block-level values once, defers per-trx allocations.
 #[substreams::handlers::map]
 fn map_anchored_events(params: String, block: Block) -> Result<AnchoredEvents, Error> {
-    use sha2::{Digest, Sha256};
-
     let contract_account = if params.is_empty() {
         "polaris"
     } else {
@@ -92,7 +682,7 @@ fn map_anchored_events(params: String, block: Block) -> Result<AnchoredEvents, E
 
     // Pre-compute block-level values once (PERF-05: avoid recomputing per action)
     let block_id = &block.id;
-    let block_num = block.number as u64;
+    let block_num = u64::from(block.number);
     let block_timestamp = block
         .header
         .as_ref()
@@ -110,6 +700,10 @@ fn map_anchored_events(params: String, block: Block) -> Result<AnchoredEvents, E
             }
 
             let action = action_trace.action.as_ref()?;
+            // Skip notifications of other accounts' actions (see extract_events)
+            if action.account != contract_account {
+                return None;
+            }
 
             // Filter actions we care about for ingestion
             match action.name.as_str() {
@@ -117,56 +711,41 @@ fn map_anchored_events(params: String, block: Block) -> Result<AnchoredEvents, E
                 _ => return None,
             }
 
-            // Extract JSON payload and content hash
+            // Extract JSON payload and content hash (None: same as the payload hash)
             let (json_data, content_hash) = if !action.json_data.is_empty() {
                 // Path 1: json_data available (preferred path from Firehose)
                 let json_str = &action.json_data;
 
                 let content_hash = if action.name == "put" {
-                    // In 0.6, Checksum256 fields are already hex strings
-                    match serde_json::from_str::<serde_json::Value>(json_str) {
-                        Ok(val) => val
-                            .get("hash")
-                            .and_then(|h| h.as_str())
-                            .map(|s| s.to_string())
-                            .unwrap_or_else(|| {
-                                let mut hasher = Sha256::new();
-                                hasher.update(json_str.as_bytes());
-                                hex::encode(hasher.finalize())
-                            }),
+                    // In 0.6, Checksum256 fields are already hex strings. Only the
+                    // hash field is deserialized; the rest of the payload is skipped.
+                    match serde_json::from_str::<PutHash>(json_str) {
+                        Ok(put) => Some(put.hash),
                         Err(_) => {
                             log::info!("Failed to parse put action JSON for content_hash");
-                            let mut hasher = Sha256::new();
-                            hasher.update(json_str.as_bytes());
-                            hex::encode(hasher.finalize())
+                            None
                         }
                     }
                 } else {
                     // For non-put actions, hash the JSON payload
-                    let mut hasher = Sha256::new();
-                    hasher.update(json_str.as_bytes());
-                    hex::encode(hasher.finalize())
+                    None
                 };
 
                 (json_str.clone(), content_hash)
-            } else {
-                // Path 2: json_data missing - use raw_data with embedded ABI
-                if action.name != "put" {
-                    log::info!(
-                        "Skipping non-put action without json_data: {}",
-                        action.name
-                    );
+            } else if action.name != "put" {
+                // Path 2: json_data missing - decode raw_data with the bundled ABI
+                let Some(value) = action_data(action_trace) else {
+                    log::info!("Failed to decode {} action from raw_data", action.name);
                     return None;
-                }
+                };
 
-                // Decode Put action from raw bytes using ABI bindings
-                let put_action = match abi::polaris_music::actions::Put::decode(action_trace) {
-                    Ok(put) => put,
-                    Err(e) => {
-                        log::info!(
-                            "Failed to decode put action: {:?}",
-                            e
-                        );
+                (value.to_string(), None)
+            } else {
+                // Path 3: put without json_data - decode raw_data with embedded ABI
+                let put_action = match decode_put(action_trace) {
+                    Some(put) => put,
+                    None => {
+                        log::info!("Failed to decode put action");
                         return None;
                     }
                 };
@@ -180,7 +759,7 @@ fn map_anchored_events(params: String, block: Block) -> Result<AnchoredEvents, E
                     "type": put_action.type_,
                     "hash": &put_action.hash,
                     "event_cid": put_action.event_cid,
-                    "parent": put_action.parent,
+                    "parent": normalize_parent(put_action.parent),
                     "ts": put_action.ts,
                     "tags": put_action.tags,
                 });
@@ -193,13 +772,13 @@ fn map_anchored_events(params: String, block: Block) -> Result<AnchoredEvents, E
                     put_action.author
                 );
 
-                (json_str, content_hash)
+                (json_str, Some(content_hash))
             };
 
-            // Compute event hash from action payload (for debugging/trace identity)
-            let mut hasher = Sha256::new();
-            hasher.update(json_data.as_bytes());
-            let event_hash = hex::encode(hasher.finalize());
+            // Compute event hash from action payload (for debugging/trace identity).
+            // Hashed once and reused as the content hash of non-put actions.
+            let event_hash = sha256_hex(json_data.as_bytes());
+            let content_hash = content_hash.unwrap_or_else(|| event_hash.clone());
 
             Some(AnchoredEvent {
                 content_hash,
@@ -208,7 +787,7 @@ fn map_anchored_events(params: String, block: Block) -> Result<AnchoredEvents, E
                 block_num,
                 block_id: block_id.clone(),
                 trx_id: trx.id.clone(),
-                action_ordinal: action_trace.execution_index as u32,
+                action_ordinal: action_trace.execution_index,
                 timestamp: block_timestamp,
                 source: "substreams-eos".to_string(),
                 contract_account: contract_account.to_string(),
@@ -228,99 +807,5954 @@ fn map_anchored_events(params: String, block: Block) -> Result<AnchoredEvents, E
     })
 }
 
+/// Bytes of SHA-256 kept in `Event.fingerprint`
+const FINGERPRINT_LEN: usize = 16;
+
+/// Fill `fingerprint` on each of a block's events
+///
+/// The hash input is the protobuf encoding of the event with
+/// `timestamp_rfc3339`, `fingerprint`, `inline_actions`, `uuid` and
+/// `order_key` cleared, followed by the number of earlier events in the block
+/// with the same encoding (u64 little-endian). Prost encodes fields in tag
+/// order and skips defaults, so the input depends only on field values, and
+/// two identical actions in one transaction still get distinct fingerprints.
+fn set_fingerprints(events: &mut [Event]) {
+    use prost::Message;
+    use sha2::{Digest, Sha256};
+
+    let mut seen: HashMap<Vec<u8>, u64> = HashMap::new();
+    for event in events {
+        let mut input = Event {
+            timestamp_rfc3339: String::new(),
+            fingerprint: Vec::new(),
+            inline_actions: Vec::new(),
+            uuid: String::new(),
+            order_key: String::new(),
+            ..event.clone()
+        }
+        .encode_to_vec();
+        let occurrence = seen.entry(input.clone()).or_default();
+        input.extend_from_slice(&occurrence.to_le_bytes());
+        *occurrence += 1;
+        event.fingerprint = Sha256::digest(&input)[..FINGERPRINT_LEN].to_vec();
+    }
+}
+
+/// UUIDv5 namespace of `Event.uuid`: UUIDv5 of `events.polaris.music` in the
+/// DNS namespace, `38c15361-273d-54b7-8072-8241cc57a0c4`
+const EVENT_UUID_NAMESPACE: [u8; 16] = [
+    0x38, 0xc1, 0x53, 0x61, 0x27, 0x3d, 0x54, 0xb7, 0x80, 0x72, 0x82, 0x41, 0xcc, 0x57, 0xa0, 0xc4,
+];
+
+/// Deterministic `uuid` of an event: UUIDv5 of
+/// `{tx_hash}:{action_ordinal}:{event_type}` in `EVENT_UUID_NAMESPACE`, with
+/// `:{part}` appended for the parts of a split UPDATE_RESPECT
+fn event_uuid(event: &Event, action_ordinal: u32, part: Option<usize>) -> String {
+    let mut name = format!("{}:{}:{}", event.tx_hash, action_ordinal, event.event_type);
+    if let Some(part) = part {
+        name.push_str(&format!(":{}", part));
+    }
+    uuid_v5(&EVENT_UUID_NAMESPACE, &name)
+}
+
+/// Sortable `order_key` of an event:
+/// `{block_num:010}-{trx_index:020}-{action_ordinal:010}-{part:010}`
+///
+/// Every number is zero-padded to the width of its type's maximum (10 digits
+/// for u32, 20 for the u64 `trx_index`), so string order is numeric order of
+/// the tuple. `block_num` is the u32 `Block.number`. `part` is 0 except for
+/// the later parts of a split UPDATE_RESPECT.
+fn order_key(block_num: u32, trx_index: u64, action_ordinal: u32, part: u32) -> String {
+    format!(
+        "{:010}-{:020}-{:010}-{:010}",
+        block_num, trx_index, action_ordinal, part
+    )
+}
+
+/// RFC 4122 version 5 (SHA-1, name-based) UUID, lowercase hyphenated
+fn uuid_v5(namespace: &[u8; 16], name: &str) -> String {
+    use sha1::{Digest, Sha1};
+
+    let digest = Sha1::new()
+        .chain_update(namespace)
+        .chain_update(name.as_bytes())
+        .finalize();
+    let mut bytes = [0u8; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    bytes[6] = (bytes[6] & 0x0f) | 0x50;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// `hash` field of a put payload, deserialized without the rest of the action
+#[derive(serde::Deserialize)]
+struct PutHash {
+    hash: String,
+}
+
+/// Lowercase hex SHA-256 of `data`.
+fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+
+    hex::encode(Sha256::digest(data))
+}
+
 /// Store module: Aggregate statistics from events
 ///
 /// PERF-05: Uses if-let chains instead of nested match for cleaner, branchless flow.
+/// Events come from `map_finalize_results`, so `finalizes_accepted` and
+/// `finalizes_rejected` follow the vote-derived outcome. Put-to-finalize
+/// latencies come from `map_finalize_latencies`, which has already joined each
+/// finalize to its put. Stake quantities that are
+/// malformed or off their symbol's precision in `store_stake_symbols` are
+/// counted under `anomalies:{SYMBOL}` instead of `staked:{SYMBOL}` (see
+/// `map_asset_anomalies`).
 #[substreams::handlers::store]
-fn store_stats(events: Events, store: StoreAddInt64) {
+fn store_stats(
+    events: Events,
+    latencies: FinalizeLatencies,
+    symbols: StoreGetString,
+    store: StoreAddInt64,
+) {
+    let precision = |symbol: &str| stake_symbol_precision(&symbols, symbol);
     for event in &events.events {
-        store.add(0, "total_events", 1);
+        add_event_stats(event, precision, |key, value| store.add(0, key, value));
+    }
+    for latency in &latencies.latencies {
+        store.add(0, "finalize_latency_seconds", latency.seconds as i64);
+        store.add(0, "finalize_latency_count", 1);
+    }
+}
 
-        if let Some(EventData {
-            event: Some(ref data),
-        }) = event.data
-        {
-            match data {
-                pb::polaris::v1::event_data::Event::Put(_) => {
-                    store.add(0, "total_puts", 1);
-                }
-                pb::polaris::v1::event_data::Event::Vote(_) => {
-                    store.add(0, "total_votes", 1);
-                }
-                pb::polaris::v1::event_data::Event::Stake(_) => {
-                    store.add(0, "total_stakes", 1);
+/// `store_stats` increments for one event, passed to `add` as (key, value);
+/// `precision` gives each symbol's established precision, if known
+fn add_event_stats(
+    event: &Event,
+    precision: impl Fn(&str) -> Option<u8>,
+    mut add: impl FnMut(&str, i64),
+) {
+    // Cumulative counters, plus their UTC-day buckets for daily reports
+    let day = datetime::utc_date(event.timestamp);
+    let mut add_daily = |key: &str, value: i64| {
+        add(key, value);
+        add(&keys::stats_day(&day, key), value);
+    };
+
+    add_daily("total_events", 1);
+    add_daily(&keys::stats_type(&event.event_type), 1);
+
+    if let Some(EventData {
+        event: Some(ref data),
+    }) = event.data
+    {
+        match data {
+            pb::polaris::v1::event_data::Event::Put(_) => {
+                add_daily("total_puts", 1);
+            }
+            pb::polaris::v1::event_data::Event::Vote(_) => {
+                add_daily("total_votes", 1);
+            }
+            pb::polaris::v1::event_data::Event::Stake(e) => {
+                add_daily("total_stakes", 1);
+                match checked_asset(&e.quantity, &precision) {
+                    Ok(quantity) => {
+                        add_daily(&keys::stats_staked(&quantity.symbol), quantity.amount)
+                    }
+                    Err(bucket) => add(&keys::stats_asset_anomalies(&bucket), 1),
                 }
-                pb::polaris::v1::event_data::Event::Like(_) => {
-                    store.add(0, "total_likes", 1);
+            }
+            pb::polaris::v1::event_data::Event::Unstake(e) => {
+                match checked_asset(&e.quantity, &precision) {
+                    Ok(quantity) => {
+                        add_daily(&keys::stats_staked(&quantity.symbol), -quantity.amount)
+                    }
+                    Err(bucket) => add(&keys::stats_asset_anomalies(&bucket), 1),
                 }
-                _ => {}
             }
+            pb::polaris::v1::event_data::Event::Like(_) => {
+                add_daily("total_likes", 1);
+            }
+            pb::polaris::v1::event_data::Event::Finalize(f) => {
+                // Outcome counters feed the acceptance trend
+                let outcome = if f.accepted {
+                    "finalizes_accepted"
+                } else {
+                    "finalizes_rejected"
+                };
+                add_daily(outcome, 1);
+            }
+            _ => {}
         }
     }
 }
 
-/// Store module: Track per-account activity
+/// Store module: Signed vote valence per UTC day
 ///
-/// PERF-05: Uses into_iter() for ownership transfer, avoids temporary String allocations
-/// where possible. Fixed from StoreAdd trait to concrete StoreAddInt64.
+/// Params: `symbol=<SYMBOL>` for the stake weighting (default MUS). Keys:
+/// `sentiment:{YYYY-MM-DD}:up|down` count votes with `val` above/below 0,
+/// and `sentiment:{YYYY-MM-DD}:stakeup|stakedown` add the voter's stake in
+/// the token's smallest unit right before each vote. Neutral votes count in
+/// neither.
 #[substreams::handlers::store]
-fn store_account_activity(events: Events, store: StoreAddInt64) {
-    for event in &events.events {
-        if let Some(EventData {
-            event: Some(ref data),
-        }) = event.data
-        {
-            let account_key = match data {
-                pb::polaris::v1::event_data::Event::Put(e) => Some(&e.author),
-                pb::polaris::v1::event_data::Event::Vote(e) => Some(&e.voter),
-                pb::polaris::v1::event_data::Event::Stake(e) => Some(&e.account),
-                pb::polaris::v1::event_data::Event::Like(e) => Some(&e.account),
-                _ => None,
-            };
+fn store_daily_sentiment(
+    params: String,
+    events: Events,
+    positions: StoreGetInt64,
+    store: StoreAddInt64,
+) {
+    let params = Params::parse(&params);
+    let symbol = params.get("symbol").unwrap_or("MUS");
 
-            if let Some(account) = account_key {
-                store.add(0, format!("account:{}:events", account), 1);
-                store.add(
-                    0,
-                    format!("account:{}:last_block", account),
-                    event.block_num as i64,
-                );
-            }
-        }
+    for (index, event) in events.events.iter().enumerate() {
+        let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Vote(vote)),
+        }) = &event.data
+        else {
+            continue;
+        };
+        let (side, stake_side) = match vote.val.signum() {
+            1 => ("up", "stakeup"),
+            -1 => ("down", "stakedown"),
+            _ => continue,
+        };
+        let day = datetime::utc_date(event.timestamp);
+        let stake = positions
+            .get_at(index as u64, keys::account_staked(&vote.voter, symbol))
+            .unwrap_or(0)
+            .max(0);
+        store.add(0, keys::daily_sentiment(&day, side), 1);
+        store.add(0, keys::daily_sentiment(&day, stake_side), stake);
     }
 }
 
-/// Map module: Output aggregated statistics
+/// Map module: Net vote sentiment of the current and preceding UTC days
+///
+/// Params: `weighting=raw|stake` (default raw), `symbol=<SYMBOL>` for stake
+/// precision (default MUS) and `days=<n>` (default 1, max 31), newest first.
+/// Raw sentiment counts votes (+1/-1); stake-weighted sentiment sums the
+/// voters' stake in whole tokens.
 #[substreams::handlers::map]
-fn map_stats(store: StoreGetInt64) -> Result<Stats, Error> {
-    Ok(Stats {
-        total_events: store.get_last("total_events").unwrap_or(0) as u64,
-        total_puts: store.get_last("total_puts").unwrap_or(0) as u64,
-        total_votes: store.get_last("total_votes").unwrap_or(0) as u64,
-        total_stakes: store.get_last("total_stakes").unwrap_or(0) as u64,
-        total_likes: store.get_last("total_likes").unwrap_or(0) as u64,
-        unique_contributors: 0,
-        total_staked_amount: "0.0000 MUS".to_string(),
-    })
-}
-
-// ============ EVENT EXTRACTION FUNCTIONS ============
-// PERF-05: Use Action::decode() trait method for type-safe deserialization.
-// Pre-computed block_num/timestamp passed as args to avoid redundant lookups.
+fn map_sentiment(
+    params: String,
+    clock: Clock,
+    store: StoreGetInt64,
+    symbols: StoreGetArray<String>,
+) -> Result<Sentiment, Error> {
+    const MAX_DAYS: u64 = 31;
+
+    let params = Params::parse(&params);
+    let weighting = params.get("weighting").unwrap_or("raw");
+    let (up, down, unit) = match weighting {
+        "raw" => ("up", "down", 1.0),
+        "stake" => {
+            let precision = symbol_precision(&symbols, params.get("symbol").unwrap_or("MUS"));
+            ("stakeup", "stakedown", 10f64.powi(precision.into()))
+        }
+        other => {
+            return Err(anyhow::anyhow!(
+                "invalid value for param `weighting`: {:?} (expected raw or stake)",
+                other
+            ))
+        }
+    };
+    let days = params.parse_or("days", 1u64)?.clamp(1, MAX_DAYS);
+    let now = clock.timestamp.map_or(0, |t| t.seconds.max(0) as u64);
+
+    let days = (0..days)
+        .filter_map(|i| now.checked_sub(i * datetime::SECONDS_PER_DAY))
+        .map(|seconds| {
+            let date = datetime::utc_date(seconds);
+            let value = |side| {
+                store
+                    .get_last(keys::daily_sentiment(&date, side))
+                    .unwrap_or(0)
+            };
+            let (positive, negative) = (value(up) as f64 / unit, value(down) as f64 / unit);
+            DailySentiment {
+                date,
+                positive,
+                negative,
+                net: positive - negative,
+            }
+        })
+        .collect();
+
+    Ok(Sentiment {
+        weighting: weighting.to_string(),
+        days,
+    })
+}
+
+/// Store module: Accounts active on each UTC day
+///
+/// Key: `active:{YYYY-MM-DD}:{account}` for every account of `event_accounts`,
+/// written once per day. Its Create deltas feed `store_daily_active_count`.
+#[substreams::handlers::store]
+fn store_daily_active(events: Events, store: StoreSetIfNotExistsInt64) {
+    for event in &events.events {
+        let day = datetime::utc_date(event.timestamp);
+        for account in event_accounts(event) {
+            store.set_if_not_exists(0, keys::daily_active(&day, account), &1);
+        }
+    }
+}
+
+/// Store module: Number of distinct active accounts per UTC day
+///
+/// Key: `dailyactive:{YYYY-MM-DD}`.
+#[substreams::handlers::store]
+fn store_daily_active_count(active: Deltas<DeltaInt64>, store: StoreAddInt64) {
+    for delta in active.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        if let Some(day) = keys::parse_daily_active(&delta.key) {
+            store.add(0, keys::daily_active_count(day), 1);
+        }
+    }
+}
+
+/// Days in the rolling window of monthly active accounts
+const MAU_WINDOW_DAYS: u64 = 30;
+
+/// Store module: Latest UTC day each account was active
+///
+/// Key: `lastactiveday:{account}`, days since the Unix epoch, for the
+/// accounts of `event_accounts`.
+#[substreams::handlers::store]
+fn store_last_active_day(events: Events, store: StoreSetInt64) {
+    for event in &events.events {
+        let day = event.timestamp / datetime::SECONDS_PER_DAY;
+        for account in event_accounts(event) {
+            store.set(0, keys::last_active_day(account), &(day as i64));
+        }
+    }
+}
+
+/// Store module: Distinct accounts active in each 30-day window
+///
+/// Key: `mau:{YYYY-MM-DD}`, the accounts active in the `MAU_WINDOW_DAYS` UTC
+/// days ending on the date. An account active on day D falls in the windows
+/// ending on D to D + 29; those already counting its previous active day P
+/// (ending before P + 30) are skipped, so each window counts it once and the
+/// sliding window is exact.
+#[substreams::handlers::store]
+fn store_window_active(events: Events, last_day: StoreGetInt64, store: StoreAddInt64) {
+    let mut accounts: Vec<(&str, u64)> = events
+        .events
+        .iter()
+        .flat_map(|event| {
+            let day = event.timestamp / datetime::SECONDS_PER_DAY;
+            event_accounts(event).into_iter().map(move |a| (a, day))
+        })
+        .collect();
+    accounts.sort_unstable();
+    accounts.dedup();
+
+    for (account, day) in accounts {
+        // Value as of the end of the previous block
+        let previous = last_day
+            .get_first(keys::last_active_day(account))
+            .map(|previous| previous.max(0) as u64);
+        if previous.is_some_and(|previous| previous >= day) {
+            continue;
+        }
+        let first = previous.map_or(day, |previous| day.max(previous + MAU_WINDOW_DAYS));
+        for window_end in first..day + MAU_WINDOW_DAYS {
+            let date = datetime::utc_date(window_end * datetime::SECONDS_PER_DAY);
+            store.add(0, keys::window_active(&date), 1);
+        }
+    }
+}
+
+/// Map module: DAU/MAU stickiness of the current and preceding UTC days
+///
+/// Params: `days=<n>` (default 1, max 31), newest first. The block's own day
+/// is still in progress, so its ratio only settles once the day is over.
+#[substreams::handlers::map]
+fn map_stickiness(
+    params: String,
+    clock: Clock,
+    daily: StoreGetInt64,
+    window: StoreGetInt64,
+) -> Result<Stickiness, Error> {
+    const MAX_DAYS: u64 = 31;
+
+    let days = Params::parse(&params)
+        .parse_or("days", 1u64)?
+        .clamp(1, MAX_DAYS);
+    let now = clock.timestamp.map_or(0, |t| t.seconds.max(0) as u64);
+
+    let days = (0..days)
+        .filter_map(|i| now.checked_sub(i * datetime::SECONDS_PER_DAY))
+        .map(|seconds| {
+            let date = datetime::utc_date(seconds);
+            let dau = daily
+                .get_last(keys::daily_active_count(&date))
+                .unwrap_or(0)
+                .max(0) as u64;
+            let mau = window
+                .get_last(keys::window_active(&date))
+                .unwrap_or(0)
+                .max(0) as u64;
+            DailyStickiness {
+                date,
+                dau,
+                mau,
+                ratio: if mau == 0 {
+                    0.0
+                } else {
+                    dau as f64 / mau as f64
+                },
+            }
+        })
+        .collect();
+
+    Ok(Stickiness { days })
+}
+
+/// Store module: UTC month of each account's first event
+///
+/// Key: `firstmonth:{account}` (`YYYY-MM`); accounts are those of
+/// `event_accounts`. Its Create deltas size the cohorts.
+#[substreams::handlers::store]
+fn store_account_first_month(events: Events, store: StoreSetIfNotExistsString) {
+    for event in &events.events {
+        let month = datetime::utc_month(event.timestamp);
+        for account in event_accounts(event) {
+            store.set_if_not_exists(0, keys::first_month(account), &month);
+        }
+    }
+}
+
+/// Store module: UTC months in which each account was active
+///
+/// Key: `activemonth:{account}:{YYYY-MM}`, written on the account's first
+/// event of the month.
+#[substreams::handlers::store]
+fn store_account_months(events: Events, store: StoreSetIfNotExistsInt64) {
+    for event in &events.events {
+        let month = datetime::utc_month(event.timestamp);
+        for account in event_accounts(event) {
+            store.set_if_not_exists(0, keys::active_month(account, &month), &1);
+        }
+    }
+}
+
+/// Store module: Retained accounts per first-activity cohort
+///
+/// Key: `retained:{M}:{N}`, the accounts first active in month M that were
+/// active in month N. `retained:{M}:{M}` is the size of cohort M.
+#[substreams::handlers::store]
+fn store_retention_cohorts(
+    active_months: Deltas<DeltaInt64>,
+    first_months: StoreGetString,
+    store: StoreAddInt64,
+) {
+    for delta in active_months.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        let Some((account, month)) = keys::parse_active_month(&delta.key) else {
+            continue;
+        };
+        if let Some(cohort) = first_months.get_last(keys::first_month(account)) {
+            store.add(0, keys::cohort_active(&cohort, month), 1);
+        }
+    }
+}
+
+/// Map module: Retention matrix of the latest monthly cohorts
+///
+/// Params: `months=<n>` (default 12, max 60): cohorts from the current UTC
+/// month back. Row M lists, for each month from M to the current one, how many
+/// of the accounts first seen in M were active then, as a count and a rate.
+#[substreams::handlers::map]
+fn map_retention_cohorts(
+    params: String,
+    clock: Clock,
+    counters: StoreGetInt64,
+) -> Result<RetentionCohorts, Error> {
+    const MAX_MONTHS: i64 = 60;
+
+    let params = Params::parse(&params);
+    let months = params.parse_or("months", 12i64)?.clamp(1, MAX_MONTHS);
+    let now = clock
+        .timestamp
+        .map(|t| t.seconds.max(0) as u64)
+        .unwrap_or(0);
+    let current = datetime::utc_month(now);
+    let current_index = datetime::month_index(&current).unwrap_or(0);
+
+    let count = |key: String| counters.get_last(key).unwrap_or(0).max(0) as u64;
+    let cohorts = (current_index - months + 1..=current_index)
+        .map(datetime::month_from_index)
+        .filter_map(|cohort| {
+            let size = count(keys::cohort_active(&cohort, &cohort));
+            if size == 0 {
+                return None;
+            }
+            let start = datetime::month_index(&cohort)?;
+            let active: Vec<u64> = (start..=current_index)
+                .map(|month| {
+                    count(keys::cohort_active(
+                        &cohort,
+                        &datetime::month_from_index(month),
+                    ))
+                })
+                .collect();
+            Some(RetentionCohort {
+                retention: active.iter().map(|&n| n as f64 / size as f64).collect(),
+                month: cohort,
+                size,
+                active,
+            })
+        })
+        .collect();
+
+    Ok(RetentionCohorts {
+        month: current,
+        block_num: clock.number,
+        cohorts,
+    })
+}
+
+/// Store module: Timestamp of the latest block, for day boundary detection
+///
+/// Key: `blocktime` (Unix seconds). Read with `get_first`, it is the previous
+/// block's time.
+#[substreams::handlers::store]
+fn store_block_time(clock: Clock, store: StoreSetInt64) {
+    let seconds = clock.timestamp.map(|t| t.seconds).unwrap_or(0);
+    store.set(0, keys::BLOCK_TIME, &seconds);
+}
+
+/// Columns of `map_daily_csv` rows
+const DAILY_CSV_HEADER: &str =
+    "date,total_events,puts,votes,stakes,likes,finalizes,unique_active_accounts,net_staked";
+
+/// Map module: One CSV row per UTC day, emitted on the first block after it
+///
+/// Params: `symbol=<SYMBOL>` (default MUS) for `net_staked`, the day's stakes
+/// minus unstakes as a decimal. No output on other blocks. The first row only
+/// covers its day from the start block on.
+#[substreams::handlers::map]
+fn map_daily_csv(
+    params: String,
+    clock: Clock,
+    block_time: StoreGetInt64,
+    stats: StoreGetInt64,
+    active: StoreGetInt64,
+    symbols: StoreGetString,
+) -> Result<Option<DailyCsvRow>, Error> {
+    let params = Params::parse(&params);
+    let symbol = params.get("symbol").unwrap_or("MUS");
+
+    let Some(previous) = block_time.get_first(keys::BLOCK_TIME) else {
+        return Ok(None);
+    };
+    let now = clock.timestamp.map(|t| t.seconds).unwrap_or(0);
+    let day = datetime::utc_date(previous.max(0) as u64);
+    if day == datetime::utc_date(now.max(0) as u64) {
+        return Ok(None);
+    }
+
+    let count = |key: &str| stats.get_last(keys::stats_day(&day, key)).unwrap_or(0);
+    let precision = symbols
+        .get_last(keys::stake_symbol(symbol))
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(MUS_PRECISION);
+    let net_staked = asset::format_asset(count(&keys::stats_staked(symbol)), precision, "");
+
+    let row = [
+        day.clone(),
+        count("total_events").to_string(),
+        count("total_puts").to_string(),
+        count("total_votes").to_string(),
+        count("total_stakes").to_string(),
+        count("total_likes").to_string(),
+        (count("finalizes_accepted") + count("finalizes_rejected")).to_string(),
+        active
+            .get_last(keys::daily_active_count(&day))
+            .unwrap_or(0)
+            .to_string(),
+        net_staked.trim_end().to_string(),
+    ]
+    .join(",");
+
+    Ok(Some(DailyCsvRow {
+        date: day,
+        header: DAILY_CSV_HEADER.to_string(),
+        row,
+    }))
+}
+
+/// Store module: Track per-account activity
+///
+/// PERF-05: Uses into_iter() for ownership transfer, avoids temporary String allocations
+/// where possible. Fixed from StoreAdd trait to concrete StoreAddInt64.
+#[substreams::handlers::store]
+fn store_account_activity(events: Events, store: StoreAddInt64) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(ref data),
+        }) = event.data
+        {
+            let account_key = match data {
+                pb::polaris::v1::event_data::Event::Put(e) => Some(&e.author),
+                pb::polaris::v1::event_data::Event::Vote(e) => Some(&e.voter),
+                pb::polaris::v1::event_data::Event::Stake(e) => Some(&e.account),
+                pb::polaris::v1::event_data::Event::Like(e) => Some(&e.account),
+                _ => None,
+            };
+
+            if let Some(account) = account_key {
+                store.add(0, keys::account_events(account), 1);
+            }
+        }
+    }
+}
+
+/// Store module: Latest block with activity per account
+///
+/// Key: `account:{name}:last_block`, for the same accounts and events as
+/// `store_account_activity`. A max store, as summing block numbers in the
+/// add store would not yield the latest one.
+#[substreams::handlers::store]
+fn store_account_last_block(events: Events, store: StoreMaxInt64) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(ref data),
+        }) = event.data
+        {
+            let account = match data {
+                pb::polaris::v1::event_data::Event::Put(e) => &e.author,
+                pb::polaris::v1::event_data::Event::Vote(e) => &e.voter,
+                pb::polaris::v1::event_data::Event::Stake(e) => &e.account,
+                pb::polaris::v1::event_data::Event::Like(e) => &e.account,
+                _ => continue,
+            };
+            store.max(
+                0,
+                keys::account_last_block(account),
+                block_value(event.block_num),
+            );
+        }
+    }
+}
+
+/// Store module: Precision of every token symbol seen in a stake
+///
+/// Key: `symbol:{SYMBOL}`, value is the precision. Set-if-not-exists so each
+/// symbol produces exactly one `Create` delta for `store_stake_symbol_list`.
+#[substreams::handlers::store]
+fn store_stake_symbols(events: Events, store: StoreSetIfNotExistsString) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Stake(ref stake)),
+        }) = event.data
+        {
+            if let Some(quantity) = asset::parse(&stake.quantity) {
+                store.set_if_not_exists(
+                    0,
+                    keys::stake_symbol(&quantity.symbol),
+                    &quantity.precision.to_string(),
+                );
+            }
+        }
+    }
+}
+
+/// Store module: List of staked symbols, so `map_stats` can enumerate them
+///
+/// Key: `symbols`, a list of `{SYMBOL}:{precision}` entries appended once per
+/// symbol on its first stake.
+#[substreams::handlers::store]
+fn store_stake_symbol_list(symbols: Deltas<DeltaString>, store: StoreAppend<String>) {
+    for delta in symbols.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        if let Some(symbol) = keys::parse_stake_symbol(&delta.key) {
+            store.append(
+                0,
+                keys::STAKE_SYMBOL_LIST,
+                format!("{}:{}", symbol, delta.new_value),
+            );
+        }
+    }
+}
+
+/// Precision of `symbol` per `store_stake_symbols` (its first stake)
+fn stake_symbol_precision(symbols: &StoreGetString, symbol: &str) -> Option<u8> {
+    symbols.get_last(keys::stake_symbol(symbol))?.parse().ok()
+}
+
+/// `quantity` if it can be added to its symbol's total; otherwise the
+/// anomaly bucket: its symbol when off `precision`, `unparsed` when malformed
+fn checked_asset(
+    quantity: &str,
+    precision: impl Fn(&str) -> Option<u8>,
+) -> Result<asset::Asset, String> {
+    let asset = asset::parse(quantity).ok_or_else(|| "unparsed".to_string())?;
+    match precision(&asset.symbol) {
+        Some(expected) if expected != asset.precision => Err(asset.symbol),
+        _ => Ok(asset),
+    }
+}
+
+/// Map module: Stake, unstake and reward quantities unfit for the totals
+///
+/// Reasons: `malformed` (no valid asset), `unexpected_symbol` (not the
+/// contract's `token_symbol` once `store_contract_config` knows it) and
+/// `precision_mismatch` (off the contract's precision or, before the config
+/// is known, off the symbol's first stake in `store_stake_symbols`). Zero
+/// precision is valid in itself and only reported when another is expected.
+#[substreams::handlers::map]
+fn map_asset_anomalies(
+    events: Events,
+    symbols: StoreGetString,
+    config: StoreGetProto<ContractConfig>,
+) -> Result<AssetAnomalies, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    // "4,MUS" -> (4, "MUS")
+    let token = config.get_last(keys::CONTRACT_CONFIG).and_then(|c| {
+        let (precision, symbol) = c.token_symbol.split_once(',')?;
+        Some((precision.parse::<u8>().ok()?, symbol.to_string()))
+    });
+
+    let anomalies = events
+        .events
+        .iter()
+        .filter_map(|event| {
+            let quantity = match event.data.as_ref()?.event.as_ref()? {
+                Data::Stake(e) => &e.quantity,
+                Data::Unstake(e) => &e.quantity,
+                Data::Reward(e) => &e.quantity,
+                _ => return None,
+            };
+            let (reason, expected) = match (asset::parse(quantity), &token) {
+                (None, _) => ("malformed", String::new()),
+                (Some(asset), Some((precision, symbol))) => {
+                    let expected = format!("{},{}", precision, symbol);
+                    if asset.symbol != *symbol {
+                        ("unexpected_symbol", expected)
+                    } else if asset.precision != *precision {
+                        ("precision_mismatch", expected)
+                    } else {
+                        return None;
+                    }
+                }
+                (Some(asset), None) => match stake_symbol_precision(&symbols, &asset.symbol) {
+                    Some(precision) if precision != asset.precision => (
+                        "precision_mismatch",
+                        format!("{},{}", precision, asset.symbol),
+                    ),
+                    _ => return None,
+                },
+            };
+            Some(AssetAnomaly {
+                tx_hash: event.tx_hash.clone(),
+                block_num: event.block_num,
+                event_type: event.event_type.clone(),
+                quantity: quantity.clone(),
+                reason: reason.to_string(),
+                expected,
+            })
+        })
+        .collect();
+
+    Ok(AssetAnomalies { anomalies })
+}
+
+/// Map module: Stake, unstake and reward events valued at a constant price
+///
+/// Params: `price=<decimal>` (required, fiat per whole token, e.g. `0.0125`),
+/// `symbol` (default `MUS`), `currency` (label, default `USD`) and
+/// `decimals` (digits of `usd_value`, default 2). The price is one constant
+/// for the whole run, not a time series: every block is valued the same.
+#[substreams::handlers::map]
+fn map_fiat_values(params: String, events: Events) -> Result<FiatValuedEvents, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let params = Params::parse(&params);
+    let raw = params
+        .get("price")
+        .ok_or_else(|| anyhow::anyhow!("map_fiat_values requires a `price` param"))?;
+    let price = asset::parse_decimal(raw)
+        .filter(|(amount, _)| *amount >= 0)
+        .ok_or_else(|| anyhow::anyhow!("invalid `price` {:?}: expected a decimal", raw))?;
+    let symbol = params.get("symbol").unwrap_or("MUS");
+    let decimals: u8 = params.parse_or("decimals", 2)?;
+    if decimals > 18 {
+        return Err(anyhow::anyhow!(
+            "invalid `decimals` {}: at most 18",
+            decimals
+        ));
+    }
+
+    let events = events
+        .events
+        .into_iter()
+        .filter_map(|event| {
+            let quantity = match event.data.as_ref()?.event.as_ref()? {
+                Data::Stake(e) => &e.quantity,
+                Data::Unstake(e) => &e.quantity,
+                Data::Reward(e) => &e.quantity,
+                _ => return None,
+            };
+            let usd_value = asset::parse(quantity)
+                .filter(|asset| asset.symbol == symbol)
+                .and_then(|asset| asset::fiat_value(&asset, price, decimals))?;
+            Some(FiatValuedEvent {
+                event: Some(event),
+                usd_value,
+            })
+        })
+        .collect();
+
+    Ok(FiatValuedEvents {
+        price: raw.to_string(),
+        currency: params.get("currency").unwrap_or("USD").to_string(),
+        symbol: symbol.to_string(),
+        events,
+    })
+}
+
+/// Map module: Output aggregated statistics
+#[substreams::handlers::map]
+fn map_stats(store: StoreGetInt64, symbols: StoreGetArray<String>) -> Result<Stats, Error> {
+    Ok(current_stats(&store, &symbols))
+}
+
+/// `Stats` as of the end of the block
+fn current_stats(store: &StoreGetInt64, symbols: &StoreGetArray<String>) -> Stats {
+    let symbols = symbols
+        .get_last(keys::STAKE_SYMBOL_LIST)
+        .unwrap_or_default();
+    stats_from(|key| store.get_last(key).unwrap_or(0), &symbols)
+}
+
+/// `store_stats` counters that make up `Stats` (`staked:{SYMBOL}` comes on top)
+const STATS_KEYS: [&str; 9] = [
+    "total_events",
+    "total_puts",
+    "total_votes",
+    "total_stakes",
+    "total_likes",
+    "finalizes_accepted",
+    "finalizes_rejected",
+    "finalize_latency_seconds",
+    "finalize_latency_count",
+];
+
+/// Build `Stats` from counter values; `symbols` are `store_stake_symbol_list` entries
+fn stats_from(value: impl Fn(&str) -> i64, symbols: &[String]) -> Stats {
+    let finalizes_accepted = value("finalizes_accepted") as u64;
+    let finalizes_rejected = value("finalizes_rejected") as u64;
+    let finalize_latency_samples = value("finalize_latency_count").max(0) as u64;
+
+    // Net staked (stakes minus unstakes) per symbol, in first-staked order
+    let staked_amounts: Vec<TokenAmount> = symbols
+        .iter()
+        .filter_map(|entry| {
+            let (symbol, precision) = entry.split_once(':')?;
+            let amount = value(&keys::stats_staked(symbol));
+            Some(TokenAmount {
+                symbol: symbol.to_string(),
+                amount: asset::format_asset(amount, precision.parse().ok()?, symbol),
+            })
+        })
+        .collect();
+    let total_staked_amount = staked_amounts
+        .iter()
+        .find(|t| t.symbol == "MUS")
+        .map(|t| t.amount.clone())
+        .unwrap_or_else(|| "0.0000 MUS".to_string());
+
+    Stats {
+        total_events: value("total_events") as u64,
+        total_puts: value("total_puts") as u64,
+        total_votes: value("total_votes") as u64,
+        total_stakes: value("total_stakes") as u64,
+        total_likes: value("total_likes") as u64,
+        unique_contributors: 0,
+        total_staked_amount,
+        finalizes_accepted,
+        finalizes_rejected,
+        acceptance_rate: acceptance_rate(finalizes_accepted, finalizes_rejected),
+        staked_amounts,
+        avg_finalize_latency_seconds: if finalize_latency_samples == 0 {
+            0.0
+        } else {
+            value("finalize_latency_seconds") as f64 / finalize_latency_samples as f64
+        },
+        finalize_latency_samples,
+    }
+}
+
+/// Blocks between `store_stats_snapshots` snapshots
+const STATS_SNAPSHOT_INTERVAL: u64 = 1_000;
+
+/// Store module: `store_stats` counters as of every `STATS_SNAPSHOT_INTERVAL`th block
+///
+/// Keys: `snapshot:{block}:block` marks a snapshot taken after `block`, and
+/// `snapshot:{block}:{key}` holds each `Stats` counter, including
+/// `staked:{SYMBOL}`, at that point. One snapshot per interval keeps the store
+/// small enough to retain for the whole chain.
+#[substreams::handlers::store]
+fn store_stats_snapshots(
+    clock: Clock,
+    stats: StoreGetInt64,
+    symbols: StoreGetArray<String>,
+    store: StoreSetInt64,
+) {
+    if !clock.number.is_multiple_of(STATS_SNAPSHOT_INTERVAL) {
+        return;
+    }
+
+    let block = clock.number;
+    store.set(0, keys::snapshot_block(block), &block_value(block));
+    let staked = symbols
+        .get_last(keys::STAKE_SYMBOL_LIST)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|entry| Some(keys::stats_staked(entry.split_once(':')?.0)));
+    for key in STATS_KEYS.iter().map(|k| k.to_string()).chain(staked) {
+        let total = stats.get_last(&key).unwrap_or(0);
+        store.set(0, keys::snapshot_counter(block, &key), &total);
+    }
+}
+
+/// Map module: `Stats` over a block range instead of from genesis
+///
+/// Params: `start_block=<A>` (required). Current totals minus the snapshot at
+/// the nearest boundary below A, so the window starts at `boundary + 1`, up to
+/// `STATS_SNAPSHOT_INTERVAL - 1` blocks before A, and ends at the current
+/// block. Run with `--stop-block B` to read the A..B window. No output until
+/// the snapshot has been taken, i.e. the stores were not started after it.
+#[substreams::handlers::map]
+fn map_windowed_stats(
+    params: String,
+    clock: Clock,
+    stats: StoreGetInt64,
+    snapshots: StoreGetInt64,
+    symbols: StoreGetArray<String>,
+) -> Result<Option<WindowedStats>, Error> {
+    let params = Params::parse(&params);
+    let requested: u64 = params
+        .get("start_block")
+        .ok_or_else(|| anyhow::anyhow!("map_windowed_stats requires a `start_block` param"))?
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid value for param `start_block`"))?;
+    if requested == 0 || clock.number < requested {
+        return Ok(None);
+    }
+
+    let boundary = (requested - 1) / STATS_SNAPSHOT_INTERVAL * STATS_SNAPSHOT_INTERVAL;
+    if snapshots.get_last(keys::snapshot_block(boundary)) != Some(block_value(boundary)) {
+        return Ok(None);
+    }
+
+    let symbols = symbols
+        .get_last(keys::STAKE_SYMBOL_LIST)
+        .unwrap_or_default();
+    let stats = stats_from(
+        |key| {
+            let current = stats.get_last(key).unwrap_or(0);
+            let base = snapshots
+                .get_last(keys::snapshot_counter(boundary, key))
+                .unwrap_or(0);
+            current - base
+        },
+        &symbols,
+    );
+
+    Ok(Some(WindowedStats {
+        requested_start_block: requested,
+        start_block: boundary + 1,
+        end_block: clock.number,
+        stats: Some(stats),
+    }))
+}
+
+/// Map module: Acceptance counters for the UTC day of the current block
+///
+/// Emitted every block so a consumer can sample the series and chart the
+/// daily acceptance trend without replaying finalize events.
+#[substreams::handlers::map]
+fn map_daily_acceptance(clock: Clock, store: StoreGetInt64) -> Result<DailyAcceptance, Error> {
+    let date = datetime::utc_date(clock.timestamp.map(|t| t.seconds as u64).unwrap_or(0));
+    let accepted = store
+        .get_last(keys::stats_day(&date, "finalizes_accepted"))
+        .unwrap_or(0) as u64;
+    let rejected = store
+        .get_last(keys::stats_day(&date, "finalizes_rejected"))
+        .unwrap_or(0) as u64;
+
+    Ok(DailyAcceptance {
+        date,
+        accepted,
+        rejected,
+        acceptance_rate: acceptance_rate(accepted, rejected),
+    })
+}
+
+/// Fraction of finalized submissions that were accepted (0.0 when none finalized)
+fn acceptance_rate(accepted: u64, rejected: u64) -> f64 {
+    let total = accepted + rejected;
+    if total == 0 {
+        0.0
+    } else {
+        accepted as f64 / total as f64
+    }
+}
+
+/// Store module: Number of nodes put by each account
+///
+/// Key: `authornodes:{account}`. Companion to `store_author_nodes`, which uses
+/// the running count to assign each node a stable per-author index.
+#[substreams::handlers::store]
+fn store_author_node_count(events: Events, store: StoreAddInt64) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Put(ref put)),
+        }) = event.data
+        {
+            store.add(0, keys::author_nodes(&put.author), 1);
+        }
+    }
+}
+
+/// Store module: Nodes put by each account, one entry per index
+///
+/// Key: `authornodes:{account}:{index}` where index 0 is the account's oldest
+/// node. Entries are written under separate keys rather than appended to one
+/// value so prolific authors never produce an unbounded store value.
+#[substreams::handlers::store]
+fn store_author_nodes(events: Events, counts: StoreGetInt64, store: StoreSetProto<AuthorNode>) {
+    let puts: Vec<(&Event, &PutEvent)> = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data {
+            Some(EventData {
+                event: Some(pb::polaris::v1::event_data::Event::Put(ref put)),
+            }) => Some((event, put)),
+            _ => None,
+        })
+        .collect();
+
+    let authors: Vec<&str> = puts.iter().map(|(_, put)| put.author.as_str()).collect();
+    let indices = feed_indices(&counts, keys::author_nodes, &authors);
+
+    for ((event, put), index) in puts.into_iter().zip(indices) {
+        store.set(
+            0,
+            keys::author_node(&put.author, index),
+            &AuthorNode {
+                hash: put.hash.clone(),
+                type_: put.type_,
+                ts: put.ts,
+                block_num: event.block_num,
+                tx_hash: event.tx_hash.clone(),
+            },
+        );
+    }
+}
+
+/// Indices for entries appended this block to per-key feeds.
+///
+/// `counts` (key `count_key(key)`) already include this block's entries, so
+/// indices are numbered back from the running total. Index 0 is the oldest
+/// entry of a feed; `keys` lists one key per new entry, in block order.
+fn feed_indices(counts: &StoreGetInt64, count_key: fn(&str) -> String, keys: &[&str]) -> Vec<u64> {
+    let mut in_block: HashMap<&str, i64> = HashMap::new();
+    for key in keys {
+        *in_block.entry(key).or_default() += 1;
+    }
+
+    let mut next: HashMap<&str, u64> = HashMap::new();
+    keys.iter()
+        .map(|key| {
+            let index = next.entry(key).or_insert_with(|| {
+                let total = counts.get_last(count_key(key)).unwrap_or(0);
+                (total - in_block[key]).max(0) as u64
+            });
+            *index += 1;
+            *index - 1
+        })
+        .collect()
+}
+
+/// Map module: Page of nodes authored by an account, newest first
+///
+/// Params: `account=<name>&offset=<n>&limit=<n>` (offset defaults to 0,
+/// limit to 50 and is capped at 500 to keep responses bounded).
+#[substreams::handlers::map]
+fn map_author_nodes(
+    params: String,
+    counts: StoreGetInt64,
+    nodes: StoreGetProto<AuthorNode>,
+) -> Result<AuthorNodes, Error> {
+    const DEFAULT_LIMIT: u64 = 50;
+    const MAX_LIMIT: u64 = 500;
+
+    let params = Params::parse(&params);
+    let account = params
+        .get("account")
+        .ok_or_else(|| anyhow::anyhow!("map_author_nodes requires an `account` param"))?
+        .to_string();
+    let offset = params.parse_or("offset", 0u64)?;
+    let limit = params.parse_or("limit", DEFAULT_LIMIT)?.min(MAX_LIMIT);
+
+    let total = counts
+        .get_last(keys::author_nodes(&account))
+        .unwrap_or(0)
+        .max(0) as u64;
+
+    // Walk indices from newest (total - 1) towards oldest (0)
+    let nodes = (0..total.saturating_sub(offset))
+        .rev()
+        .take(limit as usize)
+        .filter_map(|index| nodes.get_last(keys::author_node(&account, index)))
+        .collect();
+
+    Ok(AuthorNodes {
+        account,
+        total,
+        offset,
+        nodes,
+    })
+}
+
+/// Distinct tags of a put, sorted (the canonical tag set of a node)
+fn sorted_tags(put: &PutEvent) -> Vec<String> {
+    let mut tags = put.tags.clone();
+    tags.sort();
+    tags.dedup();
+    tags
+}
+
+/// Store module: Number of nodes carrying each tag
+///
+/// Key: `tagnodes:{tag}`. Companion to `store_tag_nodes`, numbered the same way
+/// as the per-author index.
+#[substreams::handlers::store]
+fn store_tag_node_count(events: Events, store: StoreAddInt64) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Put(ref put)),
+        }) = event.data
+        {
+            for tag in sorted_tags(put) {
+                store.add(0, keys::tag_nodes(&tag), 1);
+            }
+        }
+    }
+}
+
+/// Store module: Per-tag feed of nodes, each entry carrying the node's full tag set
+///
+/// Key: `tagnodes:{tag}:{index}` where index 0 is the oldest node with that
+/// tag. The sorted tag set on every entry lets `map_search` test a multi-tag
+/// match without a second lookup.
+#[substreams::handlers::store]
+fn store_tag_nodes(events: Events, counts: StoreGetInt64, store: StoreSetProto<TaggedNode>) {
+    let puts: Vec<(&Event, &PutEvent, Vec<String>)> = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data {
+            Some(EventData {
+                event: Some(pb::polaris::v1::event_data::Event::Put(ref put)),
+            }) => Some((event, put, sorted_tags(put))),
+            _ => None,
+        })
+        .collect();
+
+    let entries: Vec<(usize, &str)> = puts
+        .iter()
+        .enumerate()
+        .flat_map(|(i, (_, _, tags))| tags.iter().map(move |tag| (i, tag.as_str())))
+        .collect();
+    let tags: Vec<&str> = entries.iter().map(|(_, tag)| *tag).collect();
+    let indices = feed_indices(&counts, keys::tag_nodes, &tags);
+
+    let nodes: Vec<TaggedNode> = puts
+        .iter()
+        .map(|(event, put, tags)| TaggedNode {
+            hash: put.hash.clone(),
+            tags: tags.clone(),
+            block_num: event.block_num,
+            tx_hash: event.tx_hash.clone(),
+        })
+        .collect();
+    for ((i, tag), index) in entries.into_iter().zip(indices) {
+        store.set(0, keys::tag_node(tag, index), &nodes[i]);
+    }
+}
+
+/// Map module: Nodes tagged with every tag in the query (AND), newest first
+///
+/// Params: `tags=<a,b,...>&limit=<n>&scan=<n>`. Bounded search: the feed of the
+/// rarest query tag is scanned newest first, up to `scan` entries (default
+/// 1000, max 5000), and entries whose tag set is a superset of the query are
+/// kept, up to `limit` results (default 50, max 500). `exhaustive` is false
+/// when the scan stopped before reaching the feed's oldest entry.
+#[substreams::handlers::map]
+fn map_search(
+    params: String,
+    counts: StoreGetInt64,
+    nodes: StoreGetProto<TaggedNode>,
+) -> Result<SearchResults, Error> {
+    const MAX_QUERY_TAGS: usize = 10;
+    const DEFAULT_LIMIT: u64 = 50;
+    const MAX_LIMIT: u64 = 500;
+    const DEFAULT_SCAN: u64 = 1000;
+    const MAX_SCAN: u64 = 5000;
+
+    let params = Params::parse(&params);
+    let mut tags: Vec<String> = params
+        .get("tags")
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(str::to_string)
+        .collect();
+    tags.sort();
+    tags.dedup();
+    if tags.is_empty() || tags.len() > MAX_QUERY_TAGS {
+        return Err(anyhow::anyhow!(
+            "map_search requires 1 to {} comma-separated `tags`",
+            MAX_QUERY_TAGS
+        ));
+    }
+    let limit = params.parse_or("limit", DEFAULT_LIMIT)?.min(MAX_LIMIT) as usize;
+    let scan = params.parse_or("scan", DEFAULT_SCAN)?.min(MAX_SCAN);
+
+    // Drive the scan from the smallest per-tag feed
+    let (rarest, total) = tags
+        .iter()
+        .map(|tag| {
+            let total = counts.get_last(keys::tag_nodes(tag)).unwrap_or(0).max(0) as u64;
+            (tag, total)
+        })
+        .min_by_key(|(_, total)| *total)
+        .expect("tags is non-empty");
+
+    let mut matches = Vec::new();
+    let mut scanned = 0;
+    for index in (0..total).rev().take(scan as usize) {
+        if matches.len() == limit {
+            break;
+        }
+        scanned += 1;
+        if let Some(node) = nodes.get_last(keys::tag_node(rarest, index)) {
+            // Both tag lists are sorted and deduplicated
+            if tags.iter().all(|t| node.tags.binary_search(t).is_ok()) {
+                matches.push(node);
+            }
+        }
+    }
+
+    Ok(SearchResults {
+        exhaustive: scanned == total,
+        tags,
+        nodes: matches,
+        scanned,
+    })
+}
+
+/// Tags of a put considered for co-occurrence; caps pairs at 45 per put
+const MAX_COOCCUR_TAGS: usize = 10;
+
+/// Store module: Number of puts carrying each pair of tags
+///
+/// Key: `cooccur:{tag}:{other}`, written for both orderings so either tag can
+/// be looked up directly. Only the first `MAX_COOCCUR_TAGS` distinct tags of a
+/// put, in submitted order, are paired.
+#[substreams::handlers::store]
+fn store_tag_cooccurrence(events: Events, store: StoreAddInt64) {
+    for event in &events.events {
+        let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Put(ref put)),
+        }) = event.data
+        else {
+            continue;
+        };
+
+        let mut tags: Vec<&str> = Vec::new();
+        for tag in &put.tags {
+            if tags.len() == MAX_COOCCUR_TAGS {
+                break;
+            }
+            if !tags.contains(&tag.as_str()) {
+                tags.push(tag);
+            }
+        }
+
+        for (i, tag) in tags.iter().enumerate() {
+            for other in &tags[i + 1..] {
+                store.add(0, keys::cooccur(tag, other), 1);
+                store.add(0, keys::cooccur(other, tag), 1);
+            }
+        }
+    }
+}
+
+/// Highest co-occurrence level kept by `store_related_tags` (`2^62` puts)
+const MAX_RELATED_LEVEL: u32 = 62;
+
+/// Store module: Tags seen with each tag, so `map_related_tags` can list them
+///
+/// Key: `related:{tag}`, a list of tags appended on the pair's first
+/// co-occurrence, and `related:{tag}:{level}`, appended when the pair's count
+/// reaches `2^level`. Counts only grow, so each level holds exactly the tags
+/// seen at least `2^level` times and the top of the ranking can be read from
+/// a short list instead of every related tag.
+#[substreams::handlers::store]
+fn store_related_tags(pairs: Deltas<DeltaInt64>, store: StoreAppend<String>) {
+    for delta in pairs.deltas {
+        let Some((tag, other)) = keys::parse_cooccur(&delta.key) else {
+            continue;
+        };
+        let old = match delta.operation {
+            Operation::Create => 0,
+            _ => delta.old_value,
+        };
+        for level in (0..=MAX_RELATED_LEVEL).filter(|&l| old < 1 << l && delta.new_value >= 1 << l)
+        {
+            store.append(
+                0,
+                keys::related_tags_level(&tag, level),
+                keys::escape_segment(&other).into_owned(),
+            );
+        }
+    }
+}
+
+/// Map module: Tags most often put together with a tag
+///
+/// Params: `tag=<name>&limit=<n>` (limit defaults to 20, max 200). Ties are
+/// broken alphabetically. Only the highest level of `store_related_tags`
+/// holding `limit` tags is ranked: every tag left out has a lower count than
+/// every tag in it, so the result matches ranking all related tags.
+#[substreams::handlers::map]
+fn map_related_tags(
+    params: String,
+    related: StoreGetArray<String>,
+    pairs: StoreGetInt64,
+) -> Result<RelatedTags, Error> {
+    const DEFAULT_LIMIT: u64 = 20;
+    const MAX_LIMIT: u64 = 200;
+
+    let params = Params::parse(&params);
+    let tag = params
+        .get("tag")
+        .ok_or_else(|| anyhow::anyhow!("map_related_tags requires a `tag` param"))?
+        .to_string();
+    let limit = params.parse_or("limit", DEFAULT_LIMIT)?.min(MAX_LIMIT) as usize;
+
+    let candidates = (1..=MAX_RELATED_LEVEL)
+        .rev()
+        .filter_map(|level| related.get_last(keys::related_tags_level(&tag, level)))
+        .find(|level| level.len() >= limit)
+        .or_else(|| related.get_last(keys::related_tags(&tag)))
+        .unwrap_or_default();
+
+    let mut tags: Vec<RelatedTag> = candidates
+        .into_iter()
+        .map(|entry| {
+            let other = keys::unescape_segment(&entry);
+            RelatedTag {
+                count: pairs.get_last(keys::cooccur(&tag, &other)).unwrap_or(0) as u64,
+                tag: other,
+            }
+        })
+        .collect();
+    tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    tags.truncate(limit);
+
+    Ok(RelatedTags { tag, related: tags })
+}
+
+/// Store module: Number of direct replies to each node
+///
+/// Key: `children:{parent}`. Companion to `store_children`.
+#[substreams::handlers::store]
+fn store_child_count(events: Events, store: StoreAddInt64) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Put(ref put)),
+        }) = event.data
+        {
+            if !put.is_root {
+                store.add(0, keys::children(&put.parent), 1);
+            }
+        }
+    }
+}
+
+/// Map module: Reply counts of the nodes replied to in this block
+///
+/// One entry per parent of a reply put in the block, in first-reply order,
+/// with its direct reply count from `store_child_count` after the block.
+#[substreams::handlers::map]
+fn map_node_reply_counts(events: Events, counts: StoreGetInt64) -> Result<NodeReplyCounts, Error> {
+    let mut seen = std::collections::HashSet::new();
+    let nodes = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+            pb::polaris::v1::event_data::Event::Put(put) if !put.is_root => Some(&put.parent),
+            _ => None,
+        })
+        .filter(|parent| seen.insert(*parent))
+        .map(|parent| NodeReplyCount {
+            node_id: parent.clone(),
+            reply_count: counts.get_last(keys::children(parent)).unwrap_or(0).max(0) as u64,
+        })
+        .collect();
+
+    Ok(NodeReplyCounts { nodes })
+}
+
+/// Store module: Parent -> children adjacency of the reply tree
+///
+/// Key: `children:{parent}:{index}` holding the child hash, index 0 being the
+/// oldest reply.
+#[substreams::handlers::store]
+fn store_children(events: Events, counts: StoreGetInt64, store: StoreSetString) {
+    let replies: Vec<&PutEvent> = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data {
+            Some(EventData {
+                event: Some(pb::polaris::v1::event_data::Event::Put(ref put)),
+            }) if !put.is_root => Some(put),
+            _ => None,
+        })
+        .collect();
+
+    let parents: Vec<&str> = replies.iter().map(|put| put.parent.as_str()).collect();
+    let indices = feed_indices(&counts, keys::children, &parents);
+
+    for (put, index) in replies.into_iter().zip(indices) {
+        store.set(0, keys::child(&put.parent, index), &put.hash);
+    }
+}
+
+/// Map module: Reply tree rooted at a node, for thread export
+///
+/// Params: `root=<hash>&max_nodes=<n>` (default 1000, max 5000). The tree is
+/// walked breadth first. Parent hashes are caller-supplied on chain, so a node
+/// can claim a parent inside its own subtree; any edge to an already visited
+/// node is dropped with a warning and counted in `cycles_broken`.
+#[substreams::handlers::map]
+fn map_thread_tree(
+    params: String,
+    counts: StoreGetInt64,
+    children: StoreGetString,
+    authors: StoreGetString,
+) -> Result<ThreadTree, Error> {
+    const DEFAULT_MAX_NODES: u64 = 1000;
+    const MAX_NODES: u64 = 5000;
+
+    let params = Params::parse(&params);
+    let root = params
+        .get("root")
+        .ok_or_else(|| anyhow::anyhow!("map_thread_tree requires a `root` param"))?
+        .to_string();
+    let max_nodes = params
+        .parse_or("max_nodes", DEFAULT_MAX_NODES)?
+        .clamp(1, MAX_NODES) as usize;
+
+    let node = |hash: &str, depth: u32| ThreadNode {
+        hash: hash.to_string(),
+        author: authors
+            .get_last(keys::node_author(hash))
+            .unwrap_or_default(),
+        depth,
+    };
+
+    let mut visited: std::collections::HashSet<String> = std::collections::HashSet::new();
+    let mut queue = std::collections::VecDeque::new();
+    let mut tree = ThreadTree {
+        root: root.clone(),
+        nodes: vec![node(&root, 0)],
+        ..Default::default()
+    };
+    visited.insert(root.clone());
+    queue.push_back((root, 0u32));
+
+    'walk: while let Some((parent, depth)) = queue.pop_front() {
+        let count = counts.get_last(keys::children(&parent)).unwrap_or(0).max(0) as u64;
+        for index in 0..count {
+            let Some(child) = children.get_last(keys::child(&parent, index)) else {
+                continue;
+            };
+            if !visited.insert(child.clone()) {
+                log::info!(
+                    "Breaking cycle in thread {}: {} -> {} is already in the tree",
+                    tree.root,
+                    parent,
+                    child
+                );
+                tree.cycles_broken += 1;
+                continue;
+            }
+            if tree.nodes.len() == max_nodes {
+                tree.truncated = true;
+                break 'walk;
+            }
+
+            tree.nodes.push(node(&child, depth + 1));
+            tree.edges.push(ThreadEdge {
+                parent: parent.clone(),
+                child: child.clone(),
+            });
+            queue.push_back((child, depth + 1));
+        }
+    }
+
+    Ok(tree)
+}
+
+/// Node an event concerns, for the per-node history
+///
+/// Attest, vote and finalize `tx_hash` fields are the submission's put hash.
+fn event_node(event: &Event) -> Option<&str> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    Some(match event.data.as_ref()?.event.as_ref()? {
+        Data::Put(e) => &e.hash,
+        Data::Attest(e) => &e.tx_hash,
+        Data::Vote(e) => &e.tx_hash,
+        Data::Finalize(e) => &e.tx_hash,
+        Data::Stake(e) => &e.node_id,
+        Data::Unstake(e) => &e.node_id,
+        Data::Like(e) => &e.node_id,
+        Data::Unlike(e) => &e.node_id,
+        _ => return None,
+    })
+}
+
+/// Store module: Count events touching each node
+///
+/// Key: `nodeevents:{node_id}`; numbers the entries of `store_node_events`.
+#[substreams::handlers::store]
+fn store_node_event_count(events: Events, store: StoreAddInt64) {
+    for node in events.events.iter().filter_map(event_node) {
+        store.add(0, keys::node_events(node), 1);
+    }
+}
+
+/// Store module: Events touching each node, one entry per index
+///
+/// Key: `nodeevents:{node_id}:{index}` where index 0 is the oldest event.
+/// One key per entry keeps popular nodes from growing a single store value.
+#[substreams::handlers::store]
+fn store_node_events(events: Events, counts: StoreGetInt64, store: StoreSetProto<NodeEventRef>) {
+    let touched: Vec<(&Event, &str)> = events
+        .events
+        .iter()
+        .filter_map(|event| Some((event, event_node(event)?)))
+        .collect();
+
+    let nodes: Vec<&str> = touched.iter().map(|(_, node)| *node).collect();
+    let indices = feed_indices(&counts, keys::node_events, &nodes);
+
+    for ((event, node), index) in touched.into_iter().zip(indices) {
+        store.set(
+            0,
+            keys::node_event(node, index),
+            &NodeEventRef {
+                tx_hash: event.tx_hash.clone(),
+                event_type: event.event_type.clone(),
+                block_num: event.block_num,
+            },
+        );
+    }
+}
+
+/// Map module: Page of a node's history, oldest first
+///
+/// Params: `node=<hash>&offset=<n>&limit=<n>` (offset defaults to 0, limit to
+/// 50 and is capped at 500 to keep responses bounded).
+#[substreams::handlers::map]
+fn map_node_history(
+    params: String,
+    counts: StoreGetInt64,
+    entries: StoreGetProto<NodeEventRef>,
+) -> Result<NodeHistory, Error> {
+    const DEFAULT_LIMIT: u64 = 50;
+    const MAX_LIMIT: u64 = 500;
+
+    let params = Params::parse(&params);
+    let node_id = params
+        .get("node")
+        .ok_or_else(|| anyhow::anyhow!("map_node_history requires a `node` param"))?
+        .to_string();
+    let offset = params.parse_or("offset", 0u64)?;
+    let limit = params.parse_or("limit", DEFAULT_LIMIT)?.min(MAX_LIMIT);
+
+    let total = counts
+        .get_last(keys::node_events(&node_id))
+        .unwrap_or(0)
+        .max(0) as u64;
+
+    let events = (offset..total)
+        .take(limit as usize)
+        .filter_map(|index| entries.get_last(keys::node_event(&node_id, index)))
+        .collect();
+
+    Ok(NodeHistory {
+        node_id,
+        total,
+        offset,
+        events,
+    })
+}
+
+/// Accounts an event involves, for the per-account history: the acting
+/// account, plus respect and reward recipients and a newly set oracle
+fn event_accounts(event: &Event) -> Vec<&str> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let Some(data) = event.data.as_ref().and_then(|d| d.event.as_ref()) else {
+        return Vec::new();
+    };
+    let mut accounts: Vec<&str> = match data {
+        Data::Put(e) => vec![&e.author],
+        Data::Attest(e) => vec![&e.attestor],
+        Data::Vote(e) => vec![&e.voter],
+        Data::Finalize(_) => Vec::new(),
+        Data::Stake(e) => vec![&e.account],
+        Data::Unstake(e) => vec![&e.account],
+        Data::Like(e) => vec![&e.account],
+        Data::Unlike(e) => vec![&e.account],
+        Data::Updrespect(e) => e.updates.iter().map(|u| u.account.as_str()).collect(),
+        Data::Reward(e) => vec![&e.to],
+        Data::Paused(e) => vec![&e.admin],
+        Data::Unpaused(e) => vec![&e.admin],
+        Data::OracleSet(e) => vec![&e.admin, &e.oracle],
+    };
+    accounts.sort_unstable();
+    accounts.dedup();
+    accounts
+}
+
+/// Store module: Count events involving each account
+///
+/// Key: `accountevents:{account}`; numbers the entries of `store_account_events`.
+#[substreams::handlers::store]
+fn store_account_event_count(events: Events, store: StoreAddInt64) {
+    for account in events.events.iter().flat_map(event_accounts) {
+        store.add(0, keys::account_event_feed(account), 1);
+    }
+}
+
+/// Store module: Events involving each account, one entry per index
+///
+/// Key: `accountevents:{account}:{index}` where index 0 is the oldest event.
+#[substreams::handlers::store]
+fn store_account_events(events: Events, counts: StoreGetInt64, store: StoreSetProto<NodeEventRef>) {
+    let involved: Vec<(&Event, &str)> = events
+        .events
+        .iter()
+        .flat_map(|event| event_accounts(event).into_iter().map(move |a| (event, a)))
+        .collect();
+
+    let accounts: Vec<&str> = involved.iter().map(|(_, account)| *account).collect();
+    let indices = feed_indices(&counts, keys::account_event_feed, &accounts);
+
+    for ((event, account), index) in involved.into_iter().zip(indices) {
+        store.set(
+            0,
+            keys::account_event(account, index),
+            &NodeEventRef {
+                tx_hash: event.tx_hash.clone(),
+                event_type: event.event_type.clone(),
+                block_num: event.block_num,
+            },
+        );
+    }
+}
+
+/// Map module: Everything recorded about an account, for data exports
+///
+/// Params: `account=<name>&offset=<n>&limit=<n>` (offset defaults to 0, limit
+/// to 500 and is capped there). Pages through the account's event history,
+/// oldest first, and adds its current state: authored node count, latest
+/// respect and every stake position it opened.
+#[substreams::handlers::map]
+fn map_account_export(
+    params: String,
+    clock: Clock,
+    counts: StoreGetInt64,
+    entries: StoreGetProto<NodeEventRef>,
+    author_counts: StoreGetInt64,
+    respect: StoreGetProto<RespectScore>,
+    positions: StoreGetArray<String>,
+    locks: StoreGetProto<StakeLock>,
+    amounts: StoreGetInt64,
+) -> Result<AccountExport, Error> {
+    const MAX_LIMIT: u64 = 500;
+
+    let params = Params::parse(&params);
+    let account = params
+        .get("account")
+        .ok_or_else(|| anyhow::anyhow!("map_account_export requires an `account` param"))?
+        .to_string();
+    let offset = params.parse_or("offset", 0u64)?;
+    let limit = params.parse_or("limit", MAX_LIMIT)?.min(MAX_LIMIT);
+
+    let total_events = counts
+        .get_last(keys::account_event_feed(&account))
+        .unwrap_or(0)
+        .max(0) as u64;
+    let events = (offset..total_events)
+        .take(limit as usize)
+        .filter_map(|index| entries.get_last(keys::account_event(&account, index)))
+        .collect();
+
+    let stakes = positions
+        .get_last(keys::account_positions(&account))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            let (node_id, symbol) = entry.rsplit_once(':')?;
+            let lock = locks.get_last(keys::stake_lock(&account, node_id, symbol))?;
+            let amount = amounts
+                .get_last(keys::stake_position(&account, node_id, symbol))
+                .unwrap_or(0);
+            Some(AccountStakePosition {
+                node_id: lock.node_id,
+                quantity: asset::format_asset(amount, lock.precision as u8, &lock.symbol),
+            })
+        })
+        .collect();
+
+    Ok(AccountExport {
+        block_num: clock.number,
+        total_events,
+        offset,
+        events,
+        authored_nodes: author_counts
+            .get_last(keys::author_nodes(&account))
+            .unwrap_or(0)
+            .max(0) as u64,
+        respect: respect.get_last(keys::respect(&account)),
+        positions: stakes,
+        account,
+    })
+}
+
+/// Store module: Bloom filter bits set by the accounts seen so far
+///
+/// Key: `bloombit:{position}`, written once per bit position; accounts are
+/// those of `event_accounts`. Its Create deltas feed `store_account_bloom`.
+#[substreams::handlers::store]
+fn store_account_bloom_bits(events: Events, store: StoreSetIfNotExistsInt64) {
+    for account in events.events.iter().flat_map(event_accounts) {
+        for position in bloom::positions(account) {
+            store.set_if_not_exists(0, keys::bloom_bit(position), &1);
+        }
+    }
+}
+
+/// Store module: The account Bloom filter in a single key
+///
+/// Key: `bloom`, the list of set bit positions in the order they were first
+/// set; at most `bloom::BITS` entries.
+#[substreams::handlers::store]
+fn store_account_bloom(bits: Deltas<DeltaInt64>, store: StoreAppend<String>) {
+    for delta in bits.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        if let Some(position) = keys::parse_bloom_bit(&delta.key) {
+            store.append(0, keys::ACCOUNT_BLOOM, position.to_string());
+        }
+    }
+}
+
+/// Map module: Whether an account may have appeared in any event
+///
+/// Params: `account=<name>` (required). `known` is never false for an account
+/// that has appeared since the start block, but may be true for one that has
+/// not (see `false_positive_rate`).
+#[substreams::handlers::map]
+fn map_account_known(params: String, bloom: StoreGetArray<String>) -> Result<AccountKnown, Error> {
+    let params = Params::parse(&params);
+    let account = params
+        .get("account")
+        .ok_or_else(|| anyhow::anyhow!("map_account_known requires an `account` param"))?
+        .to_string();
+
+    let filter = bloom::Filter::from_positions(
+        bloom
+            .get_last(keys::ACCOUNT_BLOOM)
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|position| position.parse().ok()),
+    );
+
+    Ok(AccountKnown {
+        known: filter.contains(&account),
+        set_bits: filter.set_bits(),
+        false_positive_rate: filter.false_positive_rate(),
+        account,
+    })
+}
+
+/// Whether `node` is still live at block time `now`: its `expires_at`, the
+/// close of its voting window from the `anchors` row, is 0 (unknown) or later
+fn node_live(node: &RegistryNode, now: u64) -> bool {
+    node.expires_at == 0 || node.expires_at > now
+}
+
+/// Store module: Metadata of every node, for `map_registry_state`
+///
+/// Key: `registry:{hash}`. A repeated put of the same hash replaces the row, as
+/// the contract would.
+#[substreams::handlers::store]
+fn store_registry_nodes(events: Events, store: StoreSetProto<RegistryNode>) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Put(put)),
+        }) = &event.data
+        {
+            store.set(
+                0,
+                keys::registry_node(&put.hash),
+                &RegistryNode {
+                    hash: put.hash.clone(),
+                    author: put.author.clone(),
+                    type_: put.type_,
+                    parent: put.parent.clone(),
+                    tags: put.tags.clone(),
+                    ts: put.ts,
+                    expires_at: put.expires_at,
+                    block_num: event.block_num,
+                    tx_hash: event.tx_hash.clone(),
+                },
+            );
+        }
+    }
+}
+
+/// Store module: Where each submission was put, for `map_finalize_latencies`
+///
+/// Key: `subput:{hash}`, keyed by the put's content hash: that is what
+/// attest, vote and finalize actions call `tx_hash`, not the put's
+/// transaction id (kept in the value). A repeated put of the same hash keeps
+/// the first, so latency runs from the original submission.
+#[substreams::handlers::store]
+fn store_submission_puts(events: Events, store: StoreSetIfNotExistsProto<SubmissionPut>) {
+    for (hash, put) in events.events.iter().filter_map(submission_put) {
+        store.set_if_not_exists(0, keys::submission_put(hash), &put);
+    }
+}
+
+/// (put hash, `SubmissionPut`) of a PUT event
+fn submission_put(event: &Event) -> Option<(&str, SubmissionPut)> {
+    match event.data.as_ref()?.event.as_ref()? {
+        pb::polaris::v1::event_data::Event::Put(put) => Some((
+            &put.hash,
+            SubmissionPut {
+                transaction_id: event.tx_hash.clone(),
+                block_num: event.block_num,
+                timestamp: event.timestamp,
+            },
+        )),
+        _ => None,
+    }
+}
+
+/// Map module: Time from put to finalize of each submission finalized in the block
+///
+/// A finalize's `tx_hash` is the submission's put hash, which is looked up in
+/// `store_submission_puts`. Submissions put before the start block are
+/// unknown there and left out.
+#[substreams::handlers::map]
+fn map_finalize_latencies(
+    events: Events,
+    puts: StoreGetProto<SubmissionPut>,
+) -> Result<FinalizeLatencies, Error> {
+    Ok(FinalizeLatencies {
+        latencies: finalize_latencies(&events.events, |hash| {
+            puts.get_last(keys::submission_put(hash))
+        }),
+    })
+}
+
+/// Store module: Blocks of every put of a hash by the same author
+///
+/// Key: `repost:{author}:{hash}`, a list with one block number per put, so
+/// its length is the number of puts and `map_repost_spam` can count the
+/// recent ones.
+#[substreams::handlers::store]
+fn store_author_reposts(events: Events, store: StoreAppend<String>) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Put(ref put)),
+        }) = event.data
+        {
+            store.append(
+                0,
+                keys::author_reposts(&put.author, &put.hash),
+                event.block_num.to_string(),
+            );
+        }
+    }
+}
+
+/// Map module: Authors putting the same hash more than `max_reposts` times
+///
+/// Params: `max_reposts=<K>` (default 3) and `window=<W>` blocks (default
+/// 1000). For every author and hash put in this block, the puts in the last
+/// `W` blocks are counted; beyond the first they are reposts, and more than
+/// `K` of them is reported. A stuck client is therefore reported on every
+/// further repost until it stops.
+#[substreams::handlers::map]
+fn map_repost_spam(
+    params: String,
+    clock: Clock,
+    events: Events,
+    reposts: StoreGetArray<String>,
+) -> Result<RepostSpamEvents, Error> {
+    const DEFAULT_MAX_REPOSTS: u64 = 3;
+    const DEFAULT_WINDOW: u64 = 1000;
+
+    let params = Params::parse(&params);
+    let max_reposts = params.parse_or("max_reposts", DEFAULT_MAX_REPOSTS)?;
+    let window = params.parse_or("window", DEFAULT_WINDOW)?;
+    if window == 0 {
+        return Err(anyhow::anyhow!(
+            "map_repost_spam `window` must be at least 1"
+        ));
+    }
+    let window_start_block = clock.number.saturating_sub(window - 1);
+
+    let mut puts: Vec<(&str, &str)> = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+            pb::polaris::v1::event_data::Event::Put(put) => {
+                Some((put.author.as_str(), put.hash.as_str()))
+            }
+            _ => None,
+        })
+        .collect();
+    puts.sort_unstable();
+    puts.dedup();
+
+    let spam = puts
+        .into_iter()
+        .filter_map(|(author, hash)| {
+            let recent = reposts
+                .get_last(keys::author_reposts(author, hash))
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|block| block.parse::<u64>().ok())
+                .filter(|&block| block >= window_start_block)
+                .count() as u64;
+            let count = recent.saturating_sub(1);
+            (count > max_reposts).then(|| RepostSpamEvent {
+                author: author.to_string(),
+                hash: hash.to_string(),
+                count,
+            })
+        })
+        .collect();
+
+    Ok(RepostSpamEvents {
+        block_num: clock.number,
+        window_start_block,
+        events: spam,
+    })
+}
+
+/// Latencies of a block's finalizes, with `stored` looking up earlier puts
+///
+/// Puts earlier in the block are matched directly when `stored` does not
+/// know them, so a put finalized in the same block or transaction
+/// (auto-approved content) gets latency 0.
+fn finalize_latencies(
+    events: &[Event],
+    stored: impl Fn(&str) -> Option<SubmissionPut>,
+) -> Vec<FinalizeLatency> {
+    let mut in_block: HashMap<&str, SubmissionPut> = HashMap::new();
+    let mut latencies = Vec::new();
+    for event in events {
+        if let Some((hash, put)) = submission_put(event) {
+            in_block.entry(hash).or_insert(put);
+            continue;
+        }
+        let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Finalize(finalize)),
+        }) = &event.data
+        else {
+            continue;
+        };
+        let Some(put) =
+            stored(&finalize.tx_hash).or_else(|| in_block.get(finalize.tx_hash.as_str()).cloned())
+        else {
+            continue;
+        };
+        latencies.push(FinalizeLatency {
+            tx_hash: finalize.tx_hash.clone(),
+            put_block: put.block_num,
+            finalize_block: event.block_num,
+            seconds: event.timestamp.saturating_sub(put.timestamp),
+        });
+    }
+    latencies
+}
+
+/// Store module: Like count and net stake of every node
+///
+/// Keys: `likes:{node_id}` (likes minus unlikes) and
+/// `nodestake:{node_id}:{SYMBOL}` (stakes minus unstakes, in the token's
+/// smallest unit). Writes use the event's index in the block as ordinal, so
+/// readers can see the count right after each event. An unlike only counts
+/// when `store_like_state` knows the account liked the node, so the like
+/// count never goes negative (see `map_orphan_unlikes`).
+#[substreams::handlers::store]
+fn store_node_engagement(events: Events, like_state: StoreGetInt64, store: StoreAddInt64) {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    for (index, event) in events.events.iter().enumerate() {
+        let ordinal = index as u64;
+        match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Like(e)) => store.add(ordinal, keys::node_likes(&e.node_id), 1),
+            Some(Data::Unlike(e)) if liked_before(&like_state, index, &e.node_id, &e.account) => {
+                store.add(ordinal, keys::node_likes(&e.node_id), -1)
+            }
+            Some(Data::Stake(e)) => {
+                if let Some(quantity) = asset::parse(&e.quantity) {
+                    let key = keys::node_stake(&e.node_id, &quantity.symbol);
+                    store.add(ordinal, key, quantity.amount);
+                }
+            }
+            Some(Data::Unstake(e)) => {
+                if let Some(quantity) = asset::parse(&e.quantity) {
+                    let key = keys::node_stake(&e.node_id, &quantity.symbol);
+                    store.add(ordinal, key, -quantity.amount);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Like counts that trigger a `LikeMilestoneEvent` unless params override them
+const LIKE_MILESTONES: [u64; 4] = [1, 10, 100, 1_000];
+
+/// Store module: Like milestones reached by each node
+///
+/// Params: `milestones=<n>,<n>,...` (default 1,10,100,1000).
+/// Key: `milestone:{node_id}:{milestone}`, set by the like that first brings
+/// the node's like count to the milestone. Set-if-not-exists keeps that first
+/// like, so a count dropping below a milestone and climbing back never
+/// triggers it again.
+#[substreams::handlers::store]
+fn store_like_milestones(
+    params: String,
+    events: Events,
+    engagement: StoreGetInt64,
+    store: StoreSetIfNotExistsProto<LikeMilestoneEvent>,
+) {
+    let milestones = match Params::parse(&params).get("milestones") {
+        None => LIKE_MILESTONES.to_vec(),
+        Some(list) => match list.split(',').map(|m| m.trim().parse()).collect() {
+            Ok(milestones) => milestones,
+            Err(_) => panic!("store_like_milestones: invalid `milestones`: {:?}", list),
+        },
+    };
+
+    for (index, event) in events.events.iter().enumerate() {
+        let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Like(like)),
+        }) = &event.data
+        else {
+            continue;
+        };
+        // Likes move the count one at a time, so each milestone is hit exactly
+        let count = engagement
+            .get_at(index as u64, keys::node_likes(&like.node_id))
+            .unwrap_or(0);
+        if count > 0 && milestones.contains(&(count as u64)) {
+            store.set_if_not_exists(
+                index as u64,
+                keys::like_milestone(&like.node_id, count as u64),
+                &LikeMilestoneEvent {
+                    node_id: like.node_id.clone(),
+                    milestone: count as u64,
+                    account: like.account.clone(),
+                    block_num: event.block_num,
+                },
+            );
+        }
+    }
+}
+
+/// Map module: Like milestones reached in this block
+///
+/// Emits the `Create` deltas of `store_like_milestones`, i.e. each milestone
+/// on its first occurrence per node.
+#[substreams::handlers::map]
+fn map_like_milestones(
+    milestones: Deltas<DeltaProto<LikeMilestoneEvent>>,
+) -> Result<LikeMilestones, Error> {
+    Ok(LikeMilestones {
+        milestones: milestones
+            .deltas
+            .into_iter()
+            .filter(|delta| delta.operation == Operation::Create)
+            .map(|delta| delta.new_value)
+            .collect(),
+    })
+}
+
+/// Store module: First event of each type involving each account
+///
+/// Key: `first:{account}:{event_type}` for every account of `event_accounts`.
+/// Set-if-not-exists at the event's index keeps the earliest occurrence, also
+/// within a block.
+#[substreams::handlers::store]
+fn store_account_firsts(events: Events, store: StoreSetIfNotExistsProto<FirstTimeEvent>) {
+    for (index, event) in events.events.iter().enumerate() {
+        for account in event_accounts(event) {
+            store.set_if_not_exists(
+                index as u64,
+                keys::account_first(account, &event.event_type),
+                &FirstTimeEvent {
+                    account: account.to_string(),
+                    event_type: event.event_type.clone(),
+                    tx_hash: event.tx_hash.clone(),
+                    block_num: event.block_num,
+                },
+            );
+        }
+    }
+}
+
+/// Map module: Accounts' first events of a type in this block
+///
+/// Emits the `Create` deltas of `store_account_firsts`, so each
+/// `(account, event_type)` fires once on a given chain.
+#[substreams::handlers::map]
+fn map_first_time_events(
+    firsts: Deltas<DeltaProto<FirstTimeEvent>>,
+) -> Result<FirstTimeEvents, Error> {
+    Ok(FirstTimeEvents {
+        events: firsts
+            .deltas
+            .into_iter()
+            .filter(|delta| delta.operation == Operation::Create)
+            .map(|delta| delta.new_value)
+            .collect(),
+    })
+}
+
+/// Whether `account` liked `node_id` just before the block's event `index`,
+/// per `store_like_state` (written at event indices)
+fn liked_before(like_state: &StoreGetInt64, index: usize, node_id: &str, account: &str) -> bool {
+    get_before(like_state, index, &keys::like_state(node_id, account)) == Some(1)
+}
+
+/// Value of `key` just before the block's event `index`, for stores written
+/// at event indices
+fn get_before(store: &StoreGetInt64, index: usize, key: &str) -> Option<i64> {
+    match index {
+        0 => store.get_first(key),
+        _ => store.get_at(index as u64 - 1, key),
+    }
+}
+
+/// (value just before, value at) the block's event `index` of `key`, 0 when
+/// unset, for stores written at event indices
+fn values_around(store: &StoreGetInt64, index: usize, key: &str) -> (i64, i64) {
+    (
+        get_before(store, index, key).unwrap_or(0),
+        store.get_at(index as u64, key).unwrap_or(0),
+    )
+}
+
+/// Map module: Unlikes of nodes the account is not known to like
+///
+/// Either the like predates the start block or the unlike is an anomaly;
+/// `store_node_engagement` does not count these. Nodes liked, unliked and
+/// unliked again show up on the second unlike. Off unless params set
+/// `enabled=true`.
+#[substreams::handlers::map]
+fn map_orphan_unlikes(
+    params: String,
+    events: Events,
+    like_state: StoreGetInt64,
+) -> Result<OrphanUnlikeEvents, Error> {
+    if !Params::parse(&params).parse_or("enabled", false)? {
+        return Ok(OrphanUnlikeEvents::default());
+    }
+
+    let orphans = events
+        .events
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(index, event)| match event.data.as_ref()?.event.as_ref()? {
+                pb::polaris::v1::event_data::Event::Unlike(unlike)
+                    if !liked_before(&like_state, index, &unlike.node_id, &unlike.account) =>
+                {
+                    Some(OrphanUnlikeEvent {
+                        account: unlike.account.clone(),
+                        node_id: unlike.node_id.clone(),
+                        tx_hash: event.tx_hash.clone(),
+                        block_num: event.block_num,
+                    })
+                }
+                _ => None,
+            },
+        )
+        .collect();
+
+    Ok(OrphanUnlikeEvents { events: orphans })
+}
+
+/// Store module: Likes given by each account minus its unlikes
+///
+/// Key: `account:{account}:netlikes`. As for `likes:{node_id}` in
+/// `store_node_engagement`, an unlike only counts when `store_like_state`
+/// knows the account liked the node, so the value never goes negative.
+#[substreams::handlers::store]
+fn store_account_net_likes(events: Events, like_state: StoreGetInt64, store: StoreAddInt64) {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    for (index, event) in events.events.iter().enumerate() {
+        let ordinal = index as u64;
+        match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Like(e)) => store.add(ordinal, keys::account_net_likes(&e.account), 1),
+            Some(Data::Unlike(e)) if liked_before(&like_state, index, &e.node_id, &e.account) => {
+                store.add(ordinal, keys::account_net_likes(&e.account), -1)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Map module: Net likes of accounts, most generous first
+///
+/// Params: `account=<name>` for one account, or `accounts=<name>,<name>,...`
+/// and `limit=<n>` (default 10, max 100) for a leaderboard. Without either,
+/// the accounts that liked or unliked in this block are ranked, as stores
+/// cannot be enumerated. Equal counts are ordered by account.
+#[substreams::handlers::map]
+fn map_account_net_likes(
+    params: String,
+    events: Events,
+    net_likes: StoreGetInt64,
+) -> Result<AccountNetLikesList, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    const DEFAULT_LIMIT: u64 = 10;
+    const MAX_LIMIT: u64 = 100;
+
+    let params = Params::parse(&params);
+    let limit = params.parse_or("limit", DEFAULT_LIMIT)?.min(MAX_LIMIT) as usize;
+    let mut candidates: Vec<&str> = match (params.get("account"), params.get("accounts")) {
+        (Some(account), _) => vec![account],
+        (None, Some(accounts)) => accounts.split(',').map(str::trim).collect(),
+        (None, None) => events
+            .events
+            .iter()
+            .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+                Data::Like(e) => Some(e.account.as_str()),
+                Data::Unlike(e) => Some(e.account.as_str()),
+                _ => None,
+            })
+            .collect(),
+    };
+    candidates.retain(|account| !account.is_empty());
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    let mut accounts: Vec<AccountNetLikes> = candidates
+        .into_iter()
+        .map(|account| AccountNetLikes {
+            account: account.to_string(),
+            net_likes: net_likes
+                .get_last(keys::account_net_likes(account))
+                .unwrap_or(0)
+                .max(0) as u64,
+        })
+        .collect();
+    accounts.sort_by(|a, b| {
+        b.net_likes
+            .cmp(&a.net_likes)
+            .then(a.account.cmp(&b.account))
+    });
+    accounts.truncate(limit);
+
+    Ok(AccountNetLikesList { accounts })
+}
+
+/// Blocks without likes after which an account's next like starts a new session
+const DEFAULT_SESSION_GAP: u64 = 100;
+
+/// (event index, account, block) of every LIKE in `events`
+fn like_blocks(events: &Events) -> impl Iterator<Item = (usize, &str, u64)> {
+    events
+        .events
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(index, event)| match event.data.as_ref()?.event.as_ref()? {
+                pb::polaris::v1::event_data::Event::Like(like) => {
+                    Some((index, like.account.as_str(), event.block_num))
+                }
+                _ => None,
+            },
+        )
+}
+
+/// Session gap from params `gap=<blocks>`
+fn session_gap(params: &str) -> Result<u64, Error> {
+    Params::parse(params).parse_or("gap", DEFAULT_SESSION_GAP)
+}
+
+/// Store module: Block of each account's latest like
+///
+/// Key: `lastlike:{account}`, written at the like's event index.
+#[substreams::handlers::store]
+fn store_last_like(events: Events, store: StoreSetInt64) {
+    for (index, account, block) in like_blocks(&events) {
+        store.set(index as u64, keys::last_like(account), &block_value(block));
+    }
+}
+
+/// Store module: First block of each account's current like session
+///
+/// Params: `gap=<blocks>` (default 100), which must match
+/// `map_like_sessions`. Key: `likesession:{account}`, set by a like more
+/// than `gap` blocks after the account's previous one, or by its first like.
+#[substreams::handlers::store]
+fn store_like_session_starts(
+    params: String,
+    events: Events,
+    last_like: StoreGetInt64,
+    store: StoreSetInt64,
+) {
+    let gap = match session_gap(&params) {
+        Ok(gap) => gap,
+        Err(err) => panic!("store_like_session_starts: {}", err),
+    };
+    for (index, account, block) in like_blocks(&events) {
+        let previous = get_before(&last_like, index, &keys::last_like(account)).map(value_block);
+        if previous.is_none_or(|previous| block.saturating_sub(previous) > gap) {
+            store.set(
+                index as u64,
+                keys::like_session_start(account),
+                &block_value(block),
+            );
+        }
+    }
+}
+
+/// Store module: Likes per account and session
+///
+/// Key: `sessionlikes:{account}:{start}`, counting the likes of the session
+/// that `store_like_session_starts` started at block `start`.
+#[substreams::handlers::store]
+fn store_like_session_counts(events: Events, starts: StoreGetInt64, store: StoreAddInt64) {
+    for (index, account, _) in like_blocks(&events) {
+        if let Some(start) = starts.get_at(index as u64, keys::like_session_start(account)) {
+            store.add(0, keys::like_session_count(account, value_block(start)), 1);
+        }
+    }
+}
+
+/// Map module: Like sessions closed by a like in this block
+///
+/// Params: `gap=<blocks>` (default 100), the same as for
+/// `store_like_session_starts`. A session closes when the account likes again
+/// more than `gap` blocks after its last like; that like opens the next
+/// session. Idle accounts cannot be listed, so a session is only emitted
+/// once the account's next session starts.
+#[substreams::handlers::map]
+fn map_like_sessions(
+    params: String,
+    events: Events,
+    last_like: StoreGetInt64,
+    starts: StoreGetInt64,
+    counts: StoreGetInt64,
+) -> Result<LikeSessions, Error> {
+    let gap = session_gap(&params)?;
+
+    let sessions = like_blocks(&events)
+        .filter_map(|(index, account, block)| {
+            let end = get_before(&last_like, index, &keys::last_like(account)).map(value_block)?;
+            if block.saturating_sub(end) <= gap {
+                return None;
+            }
+            let start =
+                get_before(&starts, index, &keys::like_session_start(account)).map(value_block)?;
+            Some(LikeSession {
+                account: account.to_string(),
+                start_block: start,
+                end_block: end,
+                like_count: counts
+                    .get_last(keys::like_session_count(account, start))
+                    .unwrap_or(0)
+                    .max(0) as u64,
+            })
+        })
+        .collect();
+
+    Ok(LikeSessions { sessions })
+}
+
+/// Store module: Current like state of each account on each node
+///
+/// Key: `liked:{node_id}:{account}`, 1 after a like and 0 after an unlike.
+#[substreams::handlers::store]
+fn store_like_state(events: Events, store: StoreSetInt64) {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    for (index, event) in events.events.iter().enumerate() {
+        let (node_id, account, liked) = match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Like(e)) => (&e.node_id, &e.account, 1),
+            Some(Data::Unlike(e)) => (&e.node_id, &e.account, 0),
+            _ => continue,
+        };
+        store.set(index as u64, keys::like_state(node_id, account), &liked);
+    }
+}
+
+/// Store module: Accounts that liked each node, so they can be listed
+///
+/// Key: `likers:{node_id}`, appended on an account's first like of the node.
+#[substreams::handlers::store]
+fn store_node_likers(states: Deltas<DeltaInt64>, store: StoreAppend<String>) {
+    for delta in states.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        if let Some((node_id, account)) = keys::parse_like_state(&delta.key) {
+            store.append(0, keys::node_likers(node_id), account.to_string());
+        }
+    }
+}
+
+/// Map module: Accounts that both like and stake a node
+///
+/// Params: `node=<hash>` (required). Joins the node's likers with the stake
+/// positions: an account is an engaged supporter while its like stands and it
+/// has a positive stake in any symbol. Supporters come in first-like order.
+#[substreams::handlers::map]
+fn map_engaged_supporters(
+    params: String,
+    likers: StoreGetArray<String>,
+    like_state: StoreGetInt64,
+    positions: StoreGetInt64,
+    symbols: StoreGetArray<String>,
+) -> Result<EngagedSupporters, Error> {
+    let params = Params::parse(&params);
+    let node_id = params
+        .get("node")
+        .ok_or_else(|| anyhow::anyhow!("map_engaged_supporters requires a `node` param"))?
+        .to_string();
+    let symbols = symbols
+        .get_last(keys::STAKE_SYMBOL_LIST)
+        .unwrap_or_default();
+
+    let supporters: Vec<EngagedSupporter> = likers
+        .get_last(keys::node_likers(&node_id))
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|account| like_state.get_last(keys::like_state(&node_id, account)) == Some(1))
+        .filter(|account| {
+            symbols.iter().any(|entry| {
+                let symbol = entry.split_once(':').map_or(entry.as_str(), |(s, _)| s);
+                positions
+                    .get_last(keys::stake_position(account, &node_id, symbol))
+                    .unwrap_or(0)
+                    > 0
+            })
+        })
+        .map(|account| EngagedSupporter {
+            node_id: node_id.clone(),
+            account,
+        })
+        .collect();
+
+    Ok(EngagedSupporters {
+        count: supporters.len() as u64,
+        node_id,
+        supporters,
+    })
+}
+
+/// Map module: Current registry rows of live nodes
+///
+/// Params: `node=<hash>` looks up a single node; without it, every node touched
+/// by this block's events is emitted, in first-touched order. Stores cannot be
+/// enumerated, so a full snapshot is not offered. Expired nodes (see
+/// `node_live`) are left out.
+#[substreams::handlers::map]
+fn map_registry_state(
+    params: String,
+    clock: Clock,
+    events: Events,
+    nodes: StoreGetProto<RegistryNode>,
+    engagement: StoreGetInt64,
+    symbols: StoreGetArray<String>,
+    replies: StoreGetInt64,
+) -> Result<RegistryState, Error> {
+    let params = Params::parse(&params);
+    let now = clock
+        .timestamp
+        .as_ref()
+        .map_or(0, |t| t.seconds.max(0) as u64);
+    let symbols = symbols
+        .get_last(keys::STAKE_SYMBOL_LIST)
+        .unwrap_or_default();
+
+    let node_ids: Vec<&str> = match params.get("node") {
+        Some(node) => vec![node],
+        None => {
+            let mut seen = std::collections::HashSet::new();
+            events
+                .events
+                .iter()
+                .filter_map(event_node)
+                .filter(|node| seen.insert(*node))
+                .collect()
+        }
+    };
+
+    let rows = node_ids
+        .into_iter()
+        .filter_map(|node_id| nodes.get_last(keys::registry_node(node_id)))
+        .filter(|node| node_live(node, now))
+        .map(|node| {
+            let staked = symbols
+                .iter()
+                .filter_map(|entry| {
+                    let (symbol, precision) = entry.split_once(':')?;
+                    let amount = engagement
+                        .get_last(keys::node_stake(&node.hash, symbol))
+                        .unwrap_or(0);
+                    if amount == 0 {
+                        return None;
+                    }
+                    Some(TokenAmount {
+                        symbol: symbol.to_string(),
+                        amount: asset::format_asset(amount, precision.parse().ok()?, symbol),
+                    })
+                })
+                .collect();
+            RegistryRow {
+                like_count: engagement
+                    .get_last(keys::node_likes(&node.hash))
+                    .unwrap_or(0)
+                    .max(0) as u64,
+                staked,
+                reply_count: replies
+                    .get_last(keys::children(&node.hash))
+                    .unwrap_or(0)
+                    .max(0) as u64,
+                node: Some(node),
+            }
+        })
+        .collect();
+
+    Ok(RegistryState {
+        block_num: clock.number,
+        rows,
+    })
+}
+
+/// Store module: Attestation count of every node
+///
+/// Key: `nodeattests:{node_id}`. Every attest counts, including repeated
+/// attests of the same node by one attestor.
+#[substreams::handlers::store]
+fn store_node_attestations(events: Events, store: StoreAddInt64) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Attest(attest)),
+        }) = &event.data
+        {
+            store.add(0, keys::node_attestations(&attest.tx_hash), 1);
+        }
+    }
+}
+
+/// Map module: Composite engagement score of nodes, highest first
+///
+/// Params: `w_like`, `w_stake` and `w_attest` weights (default 1.0 each),
+/// `symbol=<SYMBOL>` for the stake (default MUS), `limit=<n>` (default 10,
+/// max 100) and `nodes=<hash>,<hash>,...` to score. Without `nodes`, the
+/// nodes touched by this block's events are scored, as stores cannot be
+/// enumerated. Score is `w_like * likes + w_stake * staked + w_attest *
+/// attestations`, with `staked` in whole tokens. Equal scores are ordered by
+/// node id. `min_stake=<asset>` (default none) leaves nodes staked below it
+/// out of the ranking, counted in `below_min_stake`.
+#[substreams::handlers::map]
+fn map_node_score(
+    params: String,
+    events: Events,
+    engagement: StoreGetInt64,
+    attestations: StoreGetInt64,
+    symbols: StoreGetArray<String>,
+) -> Result<NodeScores, Error> {
+    const DEFAULT_LIMIT: u64 = 10;
+    const MAX_LIMIT: u64 = 100;
+
+    let params = Params::parse(&params);
+    let weights = ScoreWeights {
+        like: params.parse_or("w_like", 1.0)?,
+        stake: params.parse_or("w_stake", 1.0)?,
+        attest: params.parse_or("w_attest", 1.0)?,
+    };
+    let symbol = params.get("symbol").unwrap_or("MUS");
+    let limit = params.parse_or("limit", DEFAULT_LIMIT)?.min(MAX_LIMIT) as usize;
+    let min_stake = min_stake_param(&params, symbol)?;
+    let precision = symbol_precision(&symbols, symbol);
+
+    let mut seen = std::collections::HashSet::new();
+    let node_ids: Vec<&str> = match params.get("nodes") {
+        Some(nodes) => nodes.split(',').map(str::trim).collect(),
+        None => events.events.iter().filter_map(event_node).collect(),
+    };
+
+    let mut below_min_stake = 0;
+    let scores = node_ids
+        .into_iter()
+        .filter(|node| !node.is_empty() && seen.insert(*node))
+        .filter_map(|node_id| {
+            let likes = engagement
+                .get_last(keys::node_likes(node_id))
+                .unwrap_or(0)
+                .max(0);
+            let staked = engagement
+                .get_last(keys::node_stake(node_id, symbol))
+                .unwrap_or(0)
+                .max(0);
+            if min_stake
+                .as_ref()
+                .is_some_and(|m| asset::is_below(staked, precision, m))
+            {
+                below_min_stake += 1;
+                return None;
+            }
+            let attests = attestations
+                .get_last(keys::node_attestations(node_id))
+                .unwrap_or(0);
+            Some(NodeScore {
+                node_id: node_id.to_string(),
+                score: weights.score(likes, staked as f64 / 10f64.powi(precision.into()), attests),
+                likes: likes as u64,
+                staked: asset::format_asset(staked, precision, symbol),
+                attestations: attests as u64,
+            })
+        })
+        .collect();
+
+    Ok(NodeScores {
+        scores: top_scores(scores, limit),
+        weights: Some(weights),
+        below_min_stake,
+    })
+}
+
+/// Precision of a staked symbol per `store_stake_symbol_list`, or MUS's
+fn symbol_precision(symbols: &StoreGetArray<String>, symbol: &str) -> u8 {
+    symbols
+        .get_last(keys::STAKE_SYMBOL_LIST)
+        .unwrap_or_default()
+        .iter()
+        .find_map(|entry| match entry.split_once(':') {
+            Some((s, precision)) if s == symbol => precision.parse().ok(),
+            _ => None,
+        })
+        .unwrap_or(MUS_PRECISION)
+}
+
+impl ScoreWeights {
+    fn score(&self, likes: i64, staked: f64, attestations: i64) -> f64 {
+        self.like * likes as f64 + self.stake * staked + self.attest * attestations as f64
+    }
+}
+
+/// The `limit` highest scores; equal scores are ordered by node id
+fn top_scores(mut scores: Vec<NodeScore>, limit: usize) -> Vec<NodeScore> {
+    scores.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| a.node_id.cmp(&b.node_id))
+    });
+    scores.truncate(limit);
+    scores
+}
+
+/// Store module: Authoritative payout record per finalized submission
+///
+/// Links each `FinalizeEvent` to the `RewardEvent`s it triggered: both share the
+/// transaction id, and a reward's `source_action_ordinal` is the finalize's
+/// `action_ordinal`. Key: `payout:{submission_hash}` (the finalize `tx_hash`).
+/// Events come from `map_finalize_results`, so `accepted` is the vote-derived
+/// outcome.
+#[substreams::handlers::store]
+fn store_finalize_payouts(events: Events, store: StoreSetProto<FinalizePayout>) {
+    for payout in collect_finalize_payouts(&events) {
+        store.set(0, keys::payout(&payout.submission_hash), &payout);
+    }
+}
+
+/// Map module: Submissions finalized in this block with their actual payout
+#[substreams::handlers::map]
+fn map_finalize_payouts(
+    payouts: Deltas<DeltaProto<FinalizePayout>>,
+) -> Result<FinalizePayouts, Error> {
+    Ok(FinalizePayouts {
+        payouts: payouts
+            .into_iter()
+            .filter(|delta| delta.operation != Operation::Delete)
+            .map(|delta| delta.new_value)
+            .collect(),
+    })
+}
+
+/// Group a block's finalize events with the reward transfers they caused.
+fn collect_finalize_payouts(events: &Events) -> Vec<FinalizePayout> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let mut payouts: Vec<(u32, FinalizePayout)> = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data {
+            Some(EventData {
+                event: Some(Data::Finalize(ref finalize)),
+            }) => Some((
+                finalize.action_ordinal,
+                FinalizePayout {
+                    submission_hash: finalize.tx_hash.clone(),
+                    trx_id: event.tx_hash.clone(),
+                    block_num: event.block_num,
+                    accepted: finalize.accepted,
+                    ..Default::default()
+                },
+            )),
+            _ => None,
+        })
+        .collect();
+
+    for event in &events.events {
+        let Some(EventData {
+            event: Some(Data::Reward(ref reward)),
+        }) = event.data
+        else {
+            continue;
+        };
+        if reward.source_action != "finalize" {
+            continue;
+        }
+
+        let Some((_, payout)) = payouts.iter_mut().find(|(ordinal, payout)| {
+            payout.trx_id == event.tx_hash && *ordinal == reward.source_action_ordinal
+        }) else {
+            continue;
+        };
+
+        match asset::parse(&reward.quantity) {
+            Some(quantity) => {
+                payout.total_amount += quantity.amount;
+                payout.total_quantity =
+                    asset::format_asset(payout.total_amount, quantity.precision, &quantity.symbol);
+            }
+            None => log::info!(
+                "Unparseable reward quantity {:?} in trx {}",
+                reward.quantity,
+                event.tx_hash
+            ),
+        }
+        payout.rewards.push(reward.clone());
+    }
+
+    payouts.into_iter().map(|(_, payout)| payout).collect()
+}
+
+/// Store module: Vote counts per submission
+///
+/// Keys: `tally:{submission_hash}:for` (val > 0) and `tally:{submission_hash}:against`
+/// (val < 0), counting each account's current vote once, whatever the voter's
+/// respect. As in the contract's tally, a re-vote first takes the voter's
+/// previous vote back out (read from `store_vote_contributions`) and a cleared
+/// vote (val 0) only does that. Writes use the event's index in the block as
+/// ordinal, so a finalize sees exactly the votes cast before it.
+#[substreams::handlers::store]
+fn store_vote_tallies(events: Events, contributions: StoreGetInt64, store: StoreAddInt64) {
+    let contribution = |index, key: &str| {
+        let (before, after) = values_around(&contributions, index, key);
+        (before.signum(), after.signum())
+    };
+    for (index, key, delta) in tally_changes(&events, keys::vote_tally, contribution) {
+        store.add(index as u64, key, delta);
+    }
+}
+
+/// Map module: Finalizes re-judged against a hypothetical approval threshold
+///
+/// Params: `threshold=<percent>` (required, 0-100, e.g. `threshold=60`).
+/// Acceptance is recomputed from `store_vote_tallies`, each account's current
+/// vote counted once, as votes for / (for + against) >= threshold. Analytical
+/// only: the contract weights votes by respect, so the weighted outcome of `map_finalize_results`, which feeds
+/// this module, is reported alongside.
+#[substreams::handlers::map]
+fn map_hypothetical_finalizes(
+    params: String,
+    events: Events,
+    tallies: StoreGetInt64,
+) -> Result<HypotheticalFinalizes, Error> {
+    let threshold = threshold_percent(&params, "map_hypothetical_finalizes")?;
+
+    let finalizes = events
+        .events
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(index, event)| match event.data.as_ref()?.event.as_ref()? {
+                pb::polaris::v1::event_data::Event::Finalize(f) => Some((index, f)),
+                _ => None,
+            },
+        )
+        .map(|(index, f)| {
+            let count = |side| {
+                tallies
+                    .get_at(index as u64, keys::vote_tally(&f.tx_hash, side))
+                    .unwrap_or(0)
+                    .max(0) as u64
+            };
+            let (votes_for, votes_against) = (count("for"), count("against"));
+            let tally_approval_percent = if votes_for + votes_against == 0 {
+                0.0
+            } else {
+                100.0 * votes_for as f64 / (votes_for + votes_against) as f64
+            };
+            HypotheticalFinalize {
+                tx_hash: f.tx_hash.clone(),
+                actual_accepted: f.accepted,
+                hypothetical_accepted: votes_for + votes_against > 0
+                    && tally_approval_percent >= threshold,
+                approval_percent: f.approval_percent,
+                tally_approval_percent,
+                votes_for,
+                votes_against,
+            }
+        })
+        .collect();
+
+    Ok(HypotheticalFinalizes {
+        threshold_percent: threshold,
+        finalizes,
+    })
+}
+
+/// Required `threshold=<percent>` param (0-100, `%` suffix allowed) of `module`
+fn threshold_percent(params: &str, module: &str) -> Result<f64, Error> {
+    let params = Params::parse(params);
+    let raw = params
+        .get("threshold")
+        .ok_or_else(|| anyhow::anyhow!("{} requires a `threshold` param", module))?;
+    raw.trim_end_matches('%')
+        .parse()
+        .ok()
+        .filter(|t| (0.0..=100.0).contains(t))
+        .ok_or_else(|| {
+            anyhow::anyhow!("invalid `threshold` {:?}: expected a percentage 0-100", raw)
+        })
+}
+
+/// Votes in `events` that bring their submission's tally approval (for /
+/// (for + against), as in `map_hypothetical_finalizes`) from below
+/// `threshold` to at or above it, per `tally(index, vote_tally key)`, the
+/// (before, at) values of `store_vote_tallies` around the vote's event index
+fn threshold_crossings(
+    events: &Events,
+    tally: impl Fn(usize, &str) -> (i64, i64),
+    threshold: f64,
+) -> Vec<ThresholdCrossedEvent> {
+    let approval = |votes_for: i64, votes_against: i64| {
+        let total = votes_for.max(0) + votes_against.max(0);
+        (total > 0).then(|| 100.0 * votes_for.max(0) as f64 / total as f64)
+    };
+
+    events
+        .events
+        .iter()
+        .enumerate()
+        .filter_map(|(index, event)| {
+            let pb::polaris::v1::event_data::Event::Vote(vote) =
+                event.data.as_ref()?.event.as_ref()?
+            else {
+                return None;
+            };
+            let (for_before, votes_for) = tally(index, &keys::vote_tally(&vote.tx_hash, "for"));
+            let (against_before, votes_against) =
+                tally(index, &keys::vote_tally(&vote.tx_hash, "against"));
+            let before = approval(for_before, against_before);
+            let after = approval(votes_for, votes_against)?;
+            let crossed = after >= threshold && before.is_none_or(|before| before < threshold);
+            crossed.then(|| ThresholdCrossedEvent {
+                tx_hash: vote.tx_hash.clone(),
+                crossed_at_block: event.block_num,
+                approval_percent: after,
+                votes_for: votes_for.max(0) as u64,
+                votes_against: votes_against.max(0) as u64,
+            })
+        })
+        .collect()
+}
+
+/// Store module: First time each submission's tally reached the threshold
+///
+/// Params: `threshold=<percent>` (required, 0-100), which must match
+/// `map_threshold_crossings`. Key: `crossed:{submission_hash}`, the
+/// `ThresholdCrossedEvent` of the first crossing. Set-if-not-exists, so its
+/// single Create delta is the "exactly once" signal.
+#[substreams::handlers::store]
+fn store_threshold_crossings(
+    params: String,
+    events: Events,
+    tallies: StoreGetInt64,
+    store: StoreSetIfNotExistsProto<ThresholdCrossedEvent>,
+) {
+    let threshold = match threshold_percent(&params, "store_threshold_crossings") {
+        Ok(threshold) => threshold,
+        Err(err) => panic!("store_threshold_crossings: {}", err),
+    };
+    for crossing in threshold_crossings(
+        &events,
+        |index, key| values_around(&tallies, index, key),
+        threshold,
+    ) {
+        store.set_if_not_exists(0, keys::threshold_crossing(&crossing.tx_hash), &crossing);
+    }
+}
+
+/// Map module: Submissions whose running tally reaches the approval threshold
+///
+/// Params: `threshold=<percent>` (required, 0-100, as for the store) and
+/// `recross=true` to also report crossings after a dip back below (default
+/// false: each submission at most once, from `store_threshold_crossings`).
+#[substreams::handlers::map]
+fn map_threshold_crossings(
+    params: String,
+    events: Events,
+    tallies: StoreGetInt64,
+    first_crossings: Deltas<DeltaProto<ThresholdCrossedEvent>>,
+) -> Result<ThresholdCrossings, Error> {
+    let threshold = threshold_percent(&params, "map_threshold_crossings")?;
+
+    let events = if Params::parse(&params).parse_or("recross", false)? {
+        threshold_crossings(
+            &events,
+            |index, key| values_around(&tallies, index, key),
+            threshold,
+        )
+    } else {
+        first_crossings
+            .deltas
+            .into_iter()
+            .filter(|delta| delta.operation == Operation::Create)
+            .map(|delta| delta.new_value)
+            .collect()
+    };
+
+    Ok(ThresholdCrossings {
+        threshold_percent: threshold,
+        events,
+    })
+}
+
+/// Weight the contract gives a vote: the voter's respect capped at
+/// `max_vote_weight`, or 1 for voters without respect
+///
+/// The cap only applies once `store_contract_config` has seen the globals row.
+fn contract_vote_weight(respect: Option<u32>, max_vote_weight: Option<u32>) -> u32 {
+    match (respect, max_vote_weight) {
+        (None, _) => 1,
+        (Some(respect), Some(max)) => respect.min(max),
+        (Some(respect), None) => respect,
+    }
+}
+
+/// `contract_vote_weight` of `voter` at the block's event `index`
+fn voter_weight_at(
+    respect: &StoreGetProto<RespectScore>,
+    config: &StoreGetProto<ContractConfig>,
+    index: usize,
+    voter: &str,
+) -> u32 {
+    contract_vote_weight(
+        respect
+            .get_at(index as u64, keys::respect(voter))
+            .map(|score| score.respect),
+        config
+            .get_last(keys::CONTRACT_CONFIG)
+            .map(|c| c.max_vote_weight),
+    )
+}
+
+/// (event index, `vote_contribution` key, signed weight) of every vote in
+/// `events`: `weight` for val > 0, `-weight` for val < 0 and 0 for a cleared vote
+fn vote_contributions(
+    events: &Events,
+    weight_of: impl Fn(usize, &str) -> u32,
+) -> Vec<(usize, String, i64)> {
+    events
+        .events
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(index, event)| match event.data.as_ref()?.event.as_ref()? {
+                pb::polaris::v1::event_data::Event::Vote(vote) => Some((
+                    index,
+                    keys::vote_contribution(&vote.tx_hash, &vote.voter),
+                    i64::from(vote.val.signum()) * i64::from(weight_of(index, &vote.voter)),
+                )),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+/// Store module: Signed weight of each account's current vote on a submission
+///
+/// Key: `votecontrib:{submission_hash}:{voter}`, the `contract_vote_weight` at
+/// vote time, negated for votes against and 0 once the vote is cleared
+/// (val 0). Lets `store_vote_weights` replace a re-vote's old contribution,
+/// as the contract does.
+#[substreams::handlers::store]
+fn store_vote_contributions(
+    events: Events,
+    respect: StoreGetProto<RespectScore>,
+    config: StoreGetProto<ContractConfig>,
+    store: StoreSetInt64,
+) {
+    let weight_of = |index, voter: &str| voter_weight_at(&respect, &config, index, voter);
+    for (index, key, contribution) in vote_contributions(&events, weight_of) {
+        store.set(index as u64, key, &contribution);
+    }
+}
+
+/// (event index, `key(submission_hash, side)`, delta) moving each vote's
+/// submission tally from the voter's `before` contribution to the `after` one
+fn tally_changes(
+    events: &Events,
+    key: fn(&str, &str) -> String,
+    contribution: impl Fn(usize, &str) -> (i64, i64),
+) -> Vec<(usize, String, i64)> {
+    let side = |contribution: i64| if contribution > 0 { "for" } else { "against" };
+    let mut changes = Vec::new();
+    for (index, event) in events.events.iter().enumerate() {
+        let Some(pb::polaris::v1::event_data::Event::Vote(vote)) =
+            event.data.as_ref().and_then(|d| d.event.as_ref())
+        else {
+            continue;
+        };
+        let (before, after) =
+            contribution(index, &keys::vote_contribution(&vote.tx_hash, &vote.voter));
+        if before != 0 {
+            changes.push((index, key(&vote.tx_hash, side(before)), -before.abs()));
+        }
+        if after != 0 {
+            changes.push((index, key(&vote.tx_hash, side(after)), after.abs()));
+        }
+    }
+    changes
+}
+
+/// Store module: Vote weight per submission, as tallied by the contract
+///
+/// Keys: `voteweight:{submission_hash}:for` (val > 0) and
+/// `voteweight:{submission_hash}:against` (val < 0), summing the
+/// `contract_vote_weight` of each account's current vote. A re-vote first
+/// removes the voter's previous contribution and a cleared vote (val 0) only
+/// removes it. Writes use the event index as ordinal.
+#[substreams::handlers::store]
+fn store_vote_weights(events: Events, contributions: StoreGetInt64, store: StoreAddInt64) {
+    let contribution = |index, key: &str| values_around(&contributions, index, key);
+    for (index, key, delta) in tally_changes(&events, keys::vote_weight, contribution) {
+        store.add(index as u64, key, delta);
+    }
+}
+
+/// Map module: Most divisive submissions, highest controversy first
+///
+/// Params: `by=count|weight` (default count: `store_vote_tallies`, weight:
+/// `store_vote_weights`; both hold each voter's current vote only, so a voter
+/// switching sides moves rather than doubles), `limit=<n>` (default 10, max
+/// 100) and
+/// `submissions=<hash>,<hash>,...` to rank. Without `submissions`, those voted
+/// on in this block are ranked, as stores cannot be enumerated. Controversy is
+/// `(up + down) * min(up, down) / max(up, down)`.
+#[substreams::handlers::map]
+fn map_controversial(
+    params: String,
+    events: Events,
+    tallies: StoreGetInt64,
+    weights: StoreGetInt64,
+) -> Result<ControversialSubmissions, Error> {
+    const DEFAULT_LIMIT: u64 = 10;
+    const MAX_LIMIT: u64 = 100;
+
+    let params = Params::parse(&params);
+    let basis = params.get("by").unwrap_or("count");
+    let (store, key): (_, fn(&str, &str) -> String) = match basis {
+        "count" => (&tallies, keys::vote_tally),
+        "weight" => (&weights, keys::vote_weight),
+        other => {
+            return Err(anyhow::anyhow!(
+                "invalid `by` {:?}: expected count or weight",
+                other
+            ))
+        }
+    };
+    let limit = params.parse_or("limit", DEFAULT_LIMIT)?.min(MAX_LIMIT) as usize;
+
+    let mut seen = std::collections::HashSet::new();
+    let hashes: Vec<&str> = match params.get("submissions") {
+        Some(hashes) => hashes.split(',').map(str::trim).collect(),
+        None => events
+            .events
+            .iter()
+            .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+                pb::polaris::v1::event_data::Event::Vote(vote) => Some(vote.tx_hash.as_str()),
+                _ => None,
+            })
+            .collect(),
+    };
+
+    let mut submissions: Vec<ControversialSubmission> = hashes
+        .into_iter()
+        .filter(|hash| !hash.is_empty() && seen.insert(*hash))
+        .map(|hash| {
+            let side = |side| store.get_last(key(hash, side)).unwrap_or(0).max(0) as u64;
+            let (up, down) = (side("for"), side("against"));
+            ControversialSubmission {
+                tx_hash: hash.to_string(),
+                up,
+                down,
+                controversy: controversy(up, down),
+            }
+        })
+        .collect();
+    submissions.sort_by(|a, b| {
+        b.controversy
+            .total_cmp(&a.controversy)
+            .then_with(|| a.tx_hash.cmp(&b.tx_hash))
+    });
+    submissions.truncate(limit);
+
+    Ok(ControversialSubmissions {
+        basis: basis.to_string(),
+        submissions,
+    })
+}
+
+/// `(up + down) * min(up, down) / max(up, down)`: zero for one-sided votes,
+/// the total for an even split
+fn controversy(up: u64, down: u64) -> f64 {
+    if up == 0 || down == 0 {
+        return 0.0;
+    }
+    let (up, down) = (up as f64, down as f64);
+    (up + down) * up.min(down) / up.max(down)
+}
+
+/// Map module: `map_events` with FINALIZE outcomes filled from the votes
+///
+/// Params: `threshold=<percent>` (0-100), overriding the contract's
+/// `approval_threshold_bp` from `store_contract_config` (9000 until the
+/// globals row is seen, the contract's default). Each FINALIZE gets
+/// `approval_percent` = round(for / (for + against) * 100) over the vote
+/// weight in `store_vote_weights` cast before it, in any earlier block or
+/// earlier in its own, and `accepted` when `for * 10000 >= (for + against) *
+/// threshold_bp`, as the contract's `finalize` decides it. Other events pass
+/// through unchanged. Votes carry their weight themselves only in
+/// `map_vote_respect`; here it comes from the store.
+#[substreams::handlers::map]
+fn map_finalize_results(
+    params: String,
+    events: Events,
+    weights: StoreGetInt64,
+    config: StoreGetProto<ContractConfig>,
+) -> Result<Events, Error> {
+    // globals.approval_threshold_bp as set by the contract's init
+    const DEFAULT_THRESHOLD_BP: u64 = 9000;
+
+    let threshold_bp = match Params::parse(&params).get("threshold") {
+        Some(_) => (threshold_percent(&params, "map_finalize_results")? * 100.0).round() as u64,
+        None => config
+            .get_last(keys::CONTRACT_CONFIG)
+            .map(|c| c.approval_threshold_bp)
+            .filter(|&bp| bp > 0)
+            .unwrap_or(DEFAULT_THRESHOLD_BP),
+    };
+
+    let mut events = events.events;
+    resolve_finalizes(
+        &mut events,
+        |index, key| get_before(&weights, index, key).unwrap_or(0),
+        threshold_bp,
+    );
+
+    Ok(Events { events })
+}
+
+/// Fill each FINALIZE's outcome from `weight_before(index, vote_weight key)`,
+/// the tally just before the finalize's event index
+fn resolve_finalizes(
+    events: &mut [Event],
+    weight_before: impl Fn(usize, &str) -> i64,
+    threshold_bp: u64,
+) {
+    for (index, event) in events.iter_mut().enumerate() {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Finalize(ref mut finalize)),
+        }) = event.data
+        {
+            let weight = |side| {
+                weight_before(index, &keys::vote_weight(&finalize.tx_hash, side)).max(0) as u64
+            };
+            let (approval_percent, accepted) =
+                finalize_result(weight("for"), weight("against"), threshold_bp);
+            finalize.approval_percent = approval_percent;
+            finalize.accepted = accepted;
+        }
+    }
+}
+
+/// (rounded approval percent, accepted) of a submission with `yes` and `no`
+/// vote weight against a threshold in basis points, compared in integers as
+/// the contract does; never accepted without any weight
+fn finalize_result(yes: u64, no: u64, threshold_bp: u64) -> (u64, bool) {
+    let total = yes + no;
+    if total == 0 {
+        return (0, false);
+    }
+    let approval = 100.0 * yes as f64 / total as f64;
+    (
+        approval.round() as u64,
+        u128::from(yes) * 10_000 >= u128::from(total) * u128::from(threshold_bp),
+    )
+}
+
+/// Store module: Count votes per submission
+///
+/// Key: `subvotes:{submission_hash}`; numbers the entries of `store_submission_votes`.
+#[substreams::handlers::store]
+fn store_submission_vote_count(events: Events, store: StoreAddInt64) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Vote(ref vote)),
+        }) = event.data
+        {
+            store.add(0, keys::submission_votes(&vote.tx_hash), 1);
+        }
+    }
+}
+
+/// Store module: Votes on each submission, one entry per index
+///
+/// Key: `subvotes:{submission_hash}:{index}` where index 0 is the oldest vote.
+#[substreams::handlers::store]
+fn store_submission_votes(
+    events: Events,
+    counts: StoreGetInt64,
+    store: StoreSetProto<SubmissionVote>,
+) {
+    let votes: Vec<(&VoteEvent, u64)> = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+            pb::polaris::v1::event_data::Event::Vote(vote) => Some((vote, event.block_num)),
+            _ => None,
+        })
+        .collect();
+
+    let submissions: Vec<&str> = votes
+        .iter()
+        .map(|(vote, _)| vote.tx_hash.as_str())
+        .collect();
+    let indices = feed_indices(&counts, keys::submission_votes, &submissions);
+
+    for ((vote, block_num), index) in votes.into_iter().zip(indices) {
+        store.set(
+            0,
+            keys::submission_vote(&vote.tx_hash, index),
+            &SubmissionVote {
+                voter: vote.voter.clone(),
+                val: vote.val,
+                block_num,
+            },
+        );
+    }
+}
+
+/// Map module: Submissions drawing a burst of votes from new accounts
+///
+/// Params (all optional): `min_votes=<K>` (default 10), `window=<W>` blocks
+/// (default 1200), `new_account_blocks=<N>` (default 172800) and
+/// `min_new_ratio=<r>` (default 0.5). For each submission voted on in the
+/// block, the votes of the last `W` blocks are read back from
+/// `store_submission_votes`. A voter is new when its first event in
+/// `store_account_events` is at most `N` blocks old. A signal is emitted when
+/// more than `K` votes fall in the window and at least `r` of them come from
+/// new accounts. Heuristic only, for manual review (see README).
+#[substreams::handlers::map]
+fn map_brigading_signals(
+    params: String,
+    events: Events,
+    counts: StoreGetInt64,
+    votes: StoreGetProto<SubmissionVote>,
+    account_events: StoreGetProto<NodeEventRef>,
+) -> Result<BrigadingSignals, Error> {
+    // Votes read back per submission, so a flood cannot stall the module
+    const MAX_WINDOW_VOTES: u64 = 1_000;
+
+    let params = Params::parse(&params);
+    let min_votes = params.parse_or("min_votes", 10u64)?;
+    let window = params.parse_or("window", 1_200u64)?;
+    let new_account_blocks = params.parse_or("new_account_blocks", 172_800u64)?;
+    let min_new_ratio = params.parse_or("min_new_ratio", 0.5f64)?;
+
+    let mut seen = std::collections::HashSet::new();
+    let mut signals = Vec::new();
+    for event in &events.events {
+        let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Vote(vote)),
+        }) = &event.data
+        else {
+            continue;
+        };
+        if !seen.insert(vote.tx_hash.as_str()) {
+            continue;
+        }
+
+        let since = event.block_num.saturating_sub(window);
+        let total = counts
+            .get_last(keys::submission_votes(&vote.tx_hash))
+            .unwrap_or(0)
+            .max(0) as u64;
+        let recent: Vec<SubmissionVote> = (0..total)
+            .rev()
+            .take(MAX_WINDOW_VOTES as usize)
+            .filter_map(|index| votes.get_last(keys::submission_vote(&vote.tx_hash, index)))
+            .take_while(|v| v.block_num > since)
+            .collect();
+        if (recent.len() as u64) <= min_votes {
+            continue;
+        }
+
+        let new_accounts = recent
+            .iter()
+            .filter(|v| {
+                account_events
+                    .get_last(keys::account_event(&v.voter, 0))
+                    .is_some_and(|first| {
+                        event.block_num.saturating_sub(first.block_num) <= new_account_blocks
+                    })
+            })
+            .count();
+        let new_account_ratio = new_accounts as f64 / recent.len() as f64;
+        if new_account_ratio < min_new_ratio {
+            continue;
+        }
+
+        let mut by_val: HashMap<i32, usize> = HashMap::new();
+        for v in &recent {
+            *by_val.entry(v.val).or_default() += 1;
+        }
+        let identical = by_val.values().copied().max().unwrap_or(0);
+
+        signals.push(BrigadingSignal {
+            tx_hash: vote.tx_hash.clone(),
+            vote_count: recent.len() as u64,
+            window_blocks: window,
+            new_account_ratio,
+            new_accounts: new_accounts as u64,
+            identical_ratio: identical as f64 / recent.len() as f64,
+            block_num: event.block_num,
+        });
+    }
+
+    Ok(BrigadingSignals { signals })
+}
+
+/// Map module: Per-transaction summary of the Polaris actions it contained
+///
+/// Groups `map_events` output by transaction so composite intents (e.g. a stake
+/// and a vote signed together) can be detected. Actions keep execution order.
+#[substreams::handlers::map]
+fn map_combined_actions(events: Events) -> Result<TransactionSummaries, Error> {
+    let mut summaries: Vec<TransactionSummary> = Vec::new();
+    let mut by_tx: HashMap<String, usize> = HashMap::new();
+
+    for event in events.events {
+        let index = *by_tx.entry(event.tx_hash.clone()).or_insert_with(|| {
+            summaries.push(TransactionSummary {
+                tx_hash: event.tx_hash.clone(),
+                block_num: event.block_num,
+                actions: Vec::new(),
+            });
+            summaries.len() - 1
+        });
+        summaries[index].actions.push(event.event_type);
+    }
+
+    Ok(TransactionSummaries { summaries })
+}
+
+/// Map module: `map_events` output grouped per transaction
+///
+/// One `TransactionEvents` per transaction with at least one event, in block
+/// order, so sinks can commit transaction-atomically. Events keep execution
+/// order within each transaction.
+#[substreams::handlers::map]
+fn map_events_by_tx(events: Events) -> Result<TransactionEventGroups, Error> {
+    let mut transactions: Vec<TransactionEvents> = Vec::new();
+    let mut by_tx: HashMap<String, usize> = HashMap::new();
+
+    for event in events.events {
+        let index = *by_tx.entry(event.tx_hash.clone()).or_insert_with(|| {
+            transactions.push(TransactionEvents {
+                tx_hash: event.tx_hash.clone(),
+                block_num: event.block_num,
+                events: Vec::new(),
+            });
+            transactions.len() - 1
+        });
+        transactions[index].events.push(event);
+    }
+
+    Ok(TransactionEventGroups { transactions })
+}
+
+/// Stores whose growth is tracked by `store_key_counts`, in input order
+///
+/// Every store is listed except those whose key count is bounded by design:
+/// the single-key `store_lib`, `store_block_time`, `store_contract_config`,
+/// `store_stake_symbol_list` and `store_account_bloom`;
+/// `store_account_bloom_bits` (at most `bloom::BITS` keys);
+/// `store_rate_checkpoints` (a ring of `RATE_RING_BLOCKS` slots);
+/// `store_pending_events`, which deletes blocks once final, so Create deltas
+/// would overstate it; and `store_key_counts` itself, one key per entry here.
+/// A new store that grows with nodes, accounts or time belongs in this list.
+const TRACKED_STORES: [&str; 69] = [
+    "store_stats",
+    "store_account_activity",
+    "store_author_node_count",
+    "store_author_nodes",
+    "store_finalize_payouts",
+    "store_node_authors",
+    "store_reversible_actions",
+    "store_tag_node_count",
+    "store_tag_nodes",
+    "store_stake_positions",
+    "store_child_count",
+    "store_children",
+    "store_respect",
+    "store_stats_snapshots",
+    "store_node_event_count",
+    "store_node_events",
+    "store_last_seen",
+    "store_stake_locks",
+    "store_tag_cooccurrence",
+    "store_vote_tallies",
+    "store_registry_nodes",
+    "store_node_engagement",
+    "store_like_milestones",
+    "store_oracle",
+    "store_like_state",
+    "store_position_opening_count",
+    "store_position_openings",
+    "store_account_event_count",
+    "store_account_events",
+    "store_round_members",
+    "store_daily_active",
+    "store_account_months",
+    "store_submission_vote_count",
+    "store_submission_votes",
+    "store_balance_changes",
+    "store_submission_puts",
+    "store_node_attestations",
+    "store_account_firsts",
+    "store_node_stake_flow",
+    "store_daily_sentiment",
+    "store_pulse_active",
+    "store_account_last_block",
+    "store_round_respect",
+    "store_account_net_likes",
+    "store_last_like",
+    "store_like_session_starts",
+    "store_like_session_counts",
+    "store_last_active_day",
+    "store_window_active",
+    "store_threshold_crossings",
+    "store_last_block",
+    "store_vote_weights",
+    "store_vote_contributions",
+    "store_event_seq",
+    "store_author_reposts",
+    "store_stake_symbols",
+    "store_daily_active_count",
+    "store_account_first_month",
+    "store_retention_cohorts",
+    "store_related_tags",
+    "store_node_likers",
+    "store_submission_attestors",
+    "store_attestor_outcomes",
+    "store_account_positions",
+    "store_stake_histogram",
+    "store_balance_changed",
+    "store_pulse_counters",
+    "store_round_participation",
+    "store_round_accounts",
+];
+
+/// Store module: Approximate number of keys held by each tracked store
+///
+/// Substreams cannot enumerate or prune store keys, so growth is estimated by
+/// counting `Create` deltas (the first write of a key). Key: `keys:{store}`.
+#[substreams::handlers::store]
+fn store_key_counts(
+    stats: Deltas<DeltaInt64>,
+    account_activity: Deltas<DeltaInt64>,
+    author_node_count: Deltas<DeltaInt64>,
+    author_nodes: Deltas<DeltaProto<AuthorNode>>,
+    finalize_payouts: Deltas<DeltaProto<FinalizePayout>>,
+    node_authors: Deltas<DeltaString>,
+    reversible_actions: Deltas<DeltaProto<ActionRef>>,
+    tag_node_count: Deltas<DeltaInt64>,
+    tag_nodes: Deltas<DeltaProto<TaggedNode>>,
+    stake_positions: Deltas<DeltaInt64>,
+    child_count: Deltas<DeltaInt64>,
+    children: Deltas<DeltaString>,
+    respect: Deltas<DeltaProto<RespectScore>>,
+    stats_snapshots: Deltas<DeltaInt64>,
+    node_event_count: Deltas<DeltaInt64>,
+    node_events: Deltas<DeltaProto<NodeEventRef>>,
+    last_seen: Deltas<DeltaInt64>,
+    stake_locks: Deltas<DeltaProto<StakeLock>>,
+    tag_cooccurrence: Deltas<DeltaInt64>,
+    vote_tallies: Deltas<DeltaInt64>,
+    registry_nodes: Deltas<DeltaProto<RegistryNode>>,
+    node_engagement: Deltas<DeltaInt64>,
+    like_milestones: Deltas<DeltaProto<LikeMilestoneEvent>>,
+    oracle: Deltas<DeltaProto<CurrentOracle>>,
+    like_state: Deltas<DeltaInt64>,
+    position_opening_count: Deltas<DeltaInt64>,
+    position_openings: Deltas<DeltaProto<PositionOpening>>,
+    account_event_count: Deltas<DeltaInt64>,
+    account_events: Deltas<DeltaProto<NodeEventRef>>,
+    round_members: Deltas<DeltaInt64>,
+    daily_active: Deltas<DeltaInt64>,
+    account_months: Deltas<DeltaInt64>,
+    submission_vote_count: Deltas<DeltaInt64>,
+    submission_votes: Deltas<DeltaProto<SubmissionVote>>,
+    balance_changes: Deltas<DeltaInt64>,
+    submission_puts: Deltas<DeltaProto<SubmissionPut>>,
+    node_attestations: Deltas<DeltaInt64>,
+    account_firsts: Deltas<DeltaProto<FirstTimeEvent>>,
+    node_stake_flow: Deltas<DeltaInt64>,
+    daily_sentiment: Deltas<DeltaInt64>,
+    pulse_active: Deltas<DeltaInt64>,
+    account_last_block: Deltas<DeltaInt64>,
+    round_respect: Deltas<DeltaInt64>,
+    account_net_likes: Deltas<DeltaInt64>,
+    last_like: Deltas<DeltaInt64>,
+    like_session_starts: Deltas<DeltaInt64>,
+    like_session_counts: Deltas<DeltaInt64>,
+    last_active_day: Deltas<DeltaInt64>,
+    window_active: Deltas<DeltaInt64>,
+    threshold_crossings: Deltas<DeltaProto<ThresholdCrossedEvent>>,
+    last_block: Deltas<DeltaProto<BlockMeta>>,
+    vote_weights: Deltas<DeltaInt64>,
+    vote_contributions: Deltas<DeltaInt64>,
+    event_seq: Deltas<DeltaInt64>,
+    author_reposts: Deltas<DeltaArray<String>>,
+    stake_symbols: Deltas<DeltaString>,
+    daily_active_count: Deltas<DeltaInt64>,
+    account_first_month: Deltas<DeltaString>,
+    retention_cohorts: Deltas<DeltaInt64>,
+    related_tags: Deltas<DeltaArray<String>>,
+    node_likers: Deltas<DeltaArray<String>>,
+    submission_attestors: Deltas<DeltaArray<String>>,
+    attestor_outcomes: Deltas<DeltaInt64>,
+    account_positions: Deltas<DeltaArray<String>>,
+    stake_histogram: Deltas<DeltaInt64>,
+    balance_changed: Deltas<DeltaArray<String>>,
+    pulse_counters: Deltas<DeltaInt64>,
+    round_participation: Deltas<DeltaInt64>,
+    round_accounts: Deltas<DeltaArray<String>>,
+    store: StoreAddInt64,
+) {
+    let created = [
+        created_keys(stats.deltas.iter().map(|d| d.operation)),
+        created_keys(account_activity.deltas.iter().map(|d| d.operation)),
+        created_keys(author_node_count.deltas.iter().map(|d| d.operation)),
+        created_keys(author_nodes.deltas.iter().map(|d| d.operation)),
+        created_keys(finalize_payouts.deltas.iter().map(|d| d.operation)),
+        created_keys(node_authors.deltas.iter().map(|d| d.operation)),
+        created_keys(reversible_actions.deltas.iter().map(|d| d.operation)),
+        created_keys(tag_node_count.deltas.iter().map(|d| d.operation)),
+        created_keys(tag_nodes.deltas.iter().map(|d| d.operation)),
+        created_keys(stake_positions.deltas.iter().map(|d| d.operation)),
+        created_keys(child_count.deltas.iter().map(|d| d.operation)),
+        created_keys(children.deltas.iter().map(|d| d.operation)),
+        created_keys(respect.deltas.iter().map(|d| d.operation)),
+        created_keys(stats_snapshots.deltas.iter().map(|d| d.operation)),
+        created_keys(node_event_count.deltas.iter().map(|d| d.operation)),
+        created_keys(node_events.deltas.iter().map(|d| d.operation)),
+        created_keys(last_seen.deltas.iter().map(|d| d.operation)),
+        created_keys(stake_locks.deltas.iter().map(|d| d.operation)),
+        created_keys(tag_cooccurrence.deltas.iter().map(|d| d.operation)),
+        created_keys(vote_tallies.deltas.iter().map(|d| d.operation)),
+        created_keys(registry_nodes.deltas.iter().map(|d| d.operation)),
+        created_keys(node_engagement.deltas.iter().map(|d| d.operation)),
+        created_keys(like_milestones.deltas.iter().map(|d| d.operation)),
+        created_keys(oracle.deltas.iter().map(|d| d.operation)),
+        created_keys(like_state.deltas.iter().map(|d| d.operation)),
+        created_keys(position_opening_count.deltas.iter().map(|d| d.operation)),
+        created_keys(position_openings.deltas.iter().map(|d| d.operation)),
+        created_keys(account_event_count.deltas.iter().map(|d| d.operation)),
+        created_keys(account_events.deltas.iter().map(|d| d.operation)),
+        created_keys(round_members.deltas.iter().map(|d| d.operation)),
+        created_keys(daily_active.deltas.iter().map(|d| d.operation)),
+        created_keys(account_months.deltas.iter().map(|d| d.operation)),
+        created_keys(submission_vote_count.deltas.iter().map(|d| d.operation)),
+        created_keys(submission_votes.deltas.iter().map(|d| d.operation)),
+        created_keys(balance_changes.deltas.iter().map(|d| d.operation)),
+        created_keys(submission_puts.deltas.iter().map(|d| d.operation)),
+        created_keys(node_attestations.deltas.iter().map(|d| d.operation)),
+        created_keys(account_firsts.deltas.iter().map(|d| d.operation)),
+        created_keys(node_stake_flow.deltas.iter().map(|d| d.operation)),
+        created_keys(daily_sentiment.deltas.iter().map(|d| d.operation)),
+        created_keys(pulse_active.deltas.iter().map(|d| d.operation)),
+        created_keys(account_last_block.deltas.iter().map(|d| d.operation)),
+        created_keys(round_respect.deltas.iter().map(|d| d.operation)),
+        created_keys(account_net_likes.deltas.iter().map(|d| d.operation)),
+        created_keys(last_like.deltas.iter().map(|d| d.operation)),
+        created_keys(like_session_starts.deltas.iter().map(|d| d.operation)),
+        created_keys(like_session_counts.deltas.iter().map(|d| d.operation)),
+        created_keys(last_active_day.deltas.iter().map(|d| d.operation)),
+        created_keys(window_active.deltas.iter().map(|d| d.operation)),
+        created_keys(threshold_crossings.deltas.iter().map(|d| d.operation)),
+        created_keys(last_block.deltas.iter().map(|d| d.operation)),
+        created_keys(vote_weights.deltas.iter().map(|d| d.operation)),
+        created_keys(vote_contributions.deltas.iter().map(|d| d.operation)),
+        created_keys(event_seq.deltas.iter().map(|d| d.operation)),
+        created_keys(author_reposts.deltas.iter().map(|d| d.operation)),
+        created_keys(stake_symbols.deltas.iter().map(|d| d.operation)),
+        created_keys(daily_active_count.deltas.iter().map(|d| d.operation)),
+        created_keys(account_first_month.deltas.iter().map(|d| d.operation)),
+        created_keys(retention_cohorts.deltas.iter().map(|d| d.operation)),
+        created_keys(related_tags.deltas.iter().map(|d| d.operation)),
+        created_keys(node_likers.deltas.iter().map(|d| d.operation)),
+        created_keys(submission_attestors.deltas.iter().map(|d| d.operation)),
+        created_keys(attestor_outcomes.deltas.iter().map(|d| d.operation)),
+        created_keys(account_positions.deltas.iter().map(|d| d.operation)),
+        created_keys(stake_histogram.deltas.iter().map(|d| d.operation)),
+        created_keys(balance_changed.deltas.iter().map(|d| d.operation)),
+        created_keys(pulse_counters.deltas.iter().map(|d| d.operation)),
+        created_keys(round_participation.deltas.iter().map(|d| d.operation)),
+        created_keys(round_accounts.deltas.iter().map(|d| d.operation)),
+    ];
+
+    for (name, count) in TRACKED_STORES.iter().zip(created) {
+        if count > 0 {
+            store.add(0, keys::store_keys(name), count);
+        }
+    }
+}
+
+/// Number of keys first written in a block's deltas
+fn created_keys(operations: impl Iterator<Item = Operation>) -> i64 {
+    operations.filter(|op| *op == Operation::Create).count() as i64
+}
+
+/// Map module: Periodic store size report for growth monitoring
+///
+/// Params: `interval=<blocks>` (default 1000). Only blocks whose number is a
+/// multiple of the interval produce output.
+#[substreams::handlers::map]
+fn map_store_size_estimate(
+    params: String,
+    clock: Clock,
+    counts: StoreGetInt64,
+) -> Result<Option<StoreSizeEstimate>, Error> {
+    const DEFAULT_INTERVAL: u64 = 1000;
+
+    let interval = Params::parse(&params).parse_or("interval", DEFAULT_INTERVAL)?;
+    if interval == 0 {
+        return Err(anyhow::anyhow!(
+            "map_store_size_estimate `interval` must be > 0"
+        ));
+    }
+    if !clock.number.is_multiple_of(interval) {
+        return Ok(None);
+    }
+
+    let stores: Vec<StoreKeyCount> = TRACKED_STORES
+        .iter()
+        .map(|name| StoreKeyCount {
+            store: name.to_string(),
+            keys: counts.get_last(keys::store_keys(name)).unwrap_or(0).max(0) as u64,
+        })
+        .collect();
+
+    Ok(Some(StoreSizeEstimate {
+        block_num: clock.number,
+        total_keys: stores.iter().map(|s| s.keys).sum(),
+        stores,
+    }))
+}
+
+/// Store module: Author of each node, keyed by content hash
+///
+/// Key: `node:{hash}`. Set-if-not-exists keeps the original submitter should
+/// the same hash ever be put again.
+#[substreams::handlers::store]
+fn store_node_authors(events: Events, store: StoreSetIfNotExistsString) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Put(ref put)),
+        }) = event.data
+        {
+            store.set_if_not_exists(0, keys::node_author(&put.hash), &put.author);
+        }
+    }
+}
+
+/// Map module: Like/unlike events enriched with the liked content's author
+///
+/// Feeds the notification pipeline ("X liked your track"). `content_author`
+/// stays empty when the node was put before the start block.
+#[substreams::handlers::map]
+fn map_like_events(events: Events, authors: StoreGetString) -> Result<Events, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let author_of = |node_id: &str| {
+        authors
+            .get_last(keys::node_author(node_id))
+            .unwrap_or_default()
+    };
+
+    let events = events
+        .events
+        .into_iter()
+        .filter_map(|mut event| {
+            match event.data.as_mut()?.event.as_mut()? {
+                Data::Like(like) => like.content_author = author_of(&like.node_id),
+                Data::Unlike(unlike) => unlike.content_author = author_of(&unlike.node_id),
+                _ => return None,
+            }
+            Some(event)
+        })
+        .collect();
+
+    Ok(Events { events })
+}
+
+/// Map module: Breadcrumbs of the thread path of each like in the block
+///
+/// Joins every `path` element of a LIKE against `store_registry_nodes`, in
+/// path order. Ancestors put before the start block (or never put) get a
+/// placeholder: `known` false and type name `UNKNOWN`.
+#[substreams::handlers::map]
+fn map_like_breadcrumbs(
+    events: Events,
+    nodes: StoreGetProto<RegistryNode>,
+) -> Result<LikeBreadcrumbs, Error> {
+    let likes = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+            pb::polaris::v1::event_data::Event::Like(like) => Some(LikeBreadcrumb {
+                account: like.account.clone(),
+                node_id: like.node_id.clone(),
+                tx_hash: event.tx_hash.clone(),
+                breadcrumbs: like
+                    .path
+                    .iter()
+                    .map(|node_id| {
+                        path_breadcrumb(node_id, nodes.get_last(keys::registry_node(node_id)))
+                    })
+                    .collect(),
+            }),
+            _ => None,
+        })
+        .collect();
+
+    Ok(LikeBreadcrumbs { likes })
+}
+
+/// Breadcrumb of a path element, a placeholder when `node` is unknown
+fn path_breadcrumb(node_id: &str, node: Option<RegistryNode>) -> PathBreadcrumb {
+    match node {
+        Some(node) => PathBreadcrumb {
+            node_id: node_id.to_string(),
+            type_: node.type_,
+            type_name: abi::content_types::content_type_name(node.type_).to_string(),
+            author: node.author,
+            known: true,
+        },
+        None => PathBreadcrumb {
+            node_id: node_id.to_string(),
+            type_name: abi::content_types::content_type_name(0).to_string(),
+            ..Default::default()
+        },
+    }
+}
+
+/// Map module: `map_events` with the content type of referenced nodes
+///
+/// Sets `content_type` on ATTEST, STAKE/UNSTAKE and LIKE/UNLIKE events to the
+/// put type of their node, from `store_registry_nodes`; other events pass
+/// through unchanged. Stays 0 when the node was put before the start block.
+#[substreams::handlers::map]
+fn map_content_type_events(
+    events: Events,
+    nodes: StoreGetProto<RegistryNode>,
+) -> Result<Events, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let type_of = |hash: &str| {
+        nodes
+            .get_last(keys::registry_node(hash))
+            .map_or(0, |node| node.type_)
+    };
+
+    let mut events = events.events;
+    for event in &mut events {
+        match event.data.as_mut().and_then(|d| d.event.as_mut()) {
+            Some(Data::Attest(e)) => e.content_type = type_of(&e.tx_hash),
+            Some(Data::Stake(e)) => e.content_type = type_of(&e.node_id),
+            Some(Data::Unstake(e)) => e.content_type = type_of(&e.node_id),
+            Some(Data::Like(e)) => e.content_type = type_of(&e.node_id),
+            Some(Data::Unlike(e)) => e.content_type = type_of(&e.node_id),
+            _ => {}
+        }
+    }
+
+    Ok(Events { events })
+}
+
+/// Map module: Attestations of a node by its own author
+///
+/// Attest `tx_hash` is the attested node's put hash. Moderation signal only:
+/// the contract allows self-attestation, so nothing is filtered. Nodes put
+/// before the start block have no known author and are never flagged.
+#[substreams::handlers::map]
+fn map_self_attestations(
+    events: Events,
+    authors: StoreGetString,
+) -> Result<SelfAttestations, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let attestations = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+            Data::Attest(attest)
+                if authors
+                    .get_last(keys::node_author(&attest.tx_hash))
+                    .as_deref()
+                    == Some(attest.attestor.as_str()) =>
+            {
+                Some(SelfAttestationEvent {
+                    attestor: attest.attestor.clone(),
+                    tx_hash: event.tx_hash.clone(),
+                    node_id: attest.tx_hash.clone(),
+                    block_num: event.block_num,
+                })
+            }
+            _ => None,
+        })
+        .collect();
+
+    Ok(SelfAttestations { attestations })
+}
+
+/// Store module: Attestors of each submission
+///
+/// Key: `attestors:{hash}`, one entry per ATTEST (an attestor attesting twice
+/// is listed twice; readers dedup). Attest `tx_hash` is the put hash.
+#[substreams::handlers::store]
+fn store_submission_attestors(events: Events, store: StoreAppend<String>) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Attest(ref attest)),
+        }) = event.data
+        {
+            store.append(
+                0,
+                keys::submission_attestors(&attest.tx_hash),
+                attest.attestor.clone(),
+            );
+        }
+    }
+}
+
+/// Store module: How often each attestor's submissions were accepted
+///
+/// Keys: `attestoutcome:{attestor}:agree` (the attested submission was
+/// accepted) and `attestoutcome:{attestor}:disagree` (rejected), counted
+/// once per attestor and submission when the FINALIZE lands, with the
+/// outcome from `map_finalize_results`. Attestations in the finalize's own
+/// block count as made before it.
+#[substreams::handlers::store]
+fn store_attestor_outcomes(events: Events, attestors: StoreGetArray<String>, store: StoreAddInt64) {
+    for event in &events.events {
+        let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Finalize(finalize)),
+        }) = &event.data
+        else {
+            continue;
+        };
+        let outcome = if finalize.accepted {
+            "agree"
+        } else {
+            "disagree"
+        };
+        for attestor in submission_attestors(&attestors, &finalize.tx_hash) {
+            store.add(0, keys::attestor_outcome(&attestor, outcome), 1);
+        }
+    }
+}
+
+/// Distinct attestors of a submission, sorted
+fn submission_attestors(attestors: &StoreGetArray<String>, hash: &str) -> Vec<String> {
+    let mut attested_by = attestors
+        .get_last(keys::submission_attestors(hash))
+        .unwrap_or_default();
+    attested_by.sort_unstable();
+    attested_by.dedup();
+    attested_by
+}
+
+/// Map module: Accuracy of the attestors whose counts changed in this block
+///
+/// These are the attestors of the submissions finalized in the block.
+/// Accuracy is agreements / (agreements + disagreements): the share of an
+/// attestor's finalized submissions that were accepted. Consumers keep the
+/// latest entry per attestor.
+#[substreams::handlers::map]
+fn map_attestor_accuracy(
+    events: Events,
+    attestors: StoreGetArray<String>,
+    outcomes: StoreGetInt64,
+) -> Result<AttestorAccuracies, Error> {
+    let mut changed: Vec<String> = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+            pb::polaris::v1::event_data::Event::Finalize(finalize) => Some(finalize),
+            _ => None,
+        })
+        .flat_map(|finalize| submission_attestors(&attestors, &finalize.tx_hash))
+        .collect();
+    changed.sort_unstable();
+    changed.dedup();
+
+    let attestors = changed
+        .into_iter()
+        .map(|attestor| {
+            let count = |outcome| {
+                outcomes
+                    .get_last(keys::attestor_outcome(&attestor, outcome))
+                    .unwrap_or(0)
+                    .max(0) as u64
+            };
+            let (agreements, disagreements) = (count("agree"), count("disagree"));
+            AttestorAccuracy {
+                attestor,
+                agreements,
+                disagreements,
+                accuracy: acceptance_rate(agreements, disagreements),
+            }
+        })
+        .collect();
+
+    Ok(AttestorAccuracies { attestors })
+}
+
+/// Key of the like/stake an unlike/unstake would reverse, if `event` is one of them
+fn reversible_key(event: &Event) -> Option<String> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    Some(match event.data.as_ref()?.event.as_ref()? {
+        Data::Like(e) => keys::reversible_like(&e.account, &e.node_id),
+        Data::Unlike(e) => keys::reversible_like(&e.account, &e.node_id),
+        Data::Stake(e) => keys::reversible_stake(&e.account, &e.node_id),
+        Data::Unstake(e) => keys::reversible_stake(&e.account, &e.node_id),
+        _ => return None,
+    })
+}
+
+/// Store module: Most recent like/stake per account and node
+///
+/// Keys: `like:{account}:{node_id}` and `stake:{account}:{node_id}`. Writes use
+/// the event's index in the block as ordinal so `map_reversal_events` can read
+/// the state as of each reversal, even when both land in the same block.
+#[substreams::handlers::store]
+fn store_reversible_actions(events: Events, store: StoreSetProto<ActionRef>) {
+    for (index, event) in events.events.iter().enumerate() {
+        if !matches!(event.event_type.as_str(), "LIKE" | "STAKE") {
+            continue;
+        }
+        if let Some(key) = reversible_key(event) {
+            let origin = ActionRef {
+                tx_hash: event.tx_hash.clone(),
+                block_num: event.block_num,
+            };
+            store.set(index as u64, key, &origin);
+        }
+    }
+}
+
+/// Map module: Unlike/unstake events linked to the like/stake they reverse
+///
+/// `reverses_tx_hash` stays empty when the original action predates the start
+/// block.
+#[substreams::handlers::map]
+fn map_reversal_events(
+    events: Events,
+    originals: StoreGetProto<ActionRef>,
+) -> Result<Events, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let events = events
+        .events
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, mut event)| {
+            let reverses = reversible_key(&event)
+                .and_then(|key| originals.get_at(index as u64, key))
+                .map(|origin| origin.tx_hash)
+                .unwrap_or_default();
+            match event.data.as_mut()?.event.as_mut()? {
+                Data::Unlike(unlike) => unlike.reverses_tx_hash = reverses,
+                Data::Unstake(unstake) => unstake.reverses_tx_hash = reverses,
+                _ => return None,
+            }
+            Some(event)
+        })
+        .collect();
+
+    Ok(Events { events })
+}
+
+/// Store module: Net stake of each account on each node, per token symbol
+///
+/// Keys, in the token's smallest unit: `position:{account}:{node_id}:{SYMBOL}`
+/// and the account-wide total `staked:{account}:{SYMBOL}`. Writes use the
+/// event's index in the block as ordinal, like `store_reversible_actions`, so
+/// readers can see the position at each event.
+#[substreams::handlers::store]
+fn store_stake_positions(events: Events, store: StoreAddInt64) {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    for (index, event) in events.events.iter().enumerate() {
+        let (account, node_id, quantity, sign) =
+            match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+                Some(Data::Stake(e)) => (&e.account, &e.node_id, &e.quantity, 1),
+                Some(Data::Unstake(e)) => (&e.account, &e.node_id, &e.quantity, -1),
+                _ => continue,
+            };
+        if let Some(quantity) = asset::parse(quantity) {
+            store.add(
+                index as u64,
+                keys::stake_position(account, node_id, &quantity.symbol),
+                sign * quantity.amount,
+            );
+            store.add(
+                index as u64,
+                keys::account_staked(account, &quantity.symbol),
+                sign * quantity.amount,
+            );
+        }
+    }
+}
+
+/// Store module: Lockup of each stake position
+///
+/// Params: `lockup_seconds=<n>` (default 0: stakes unlock immediately).
+/// Key: `lock:{account}:{node_id}:{SYMBOL}`. Every stake restarts the lockup of
+/// its position, so `unlock_at` is the latest stake time plus the lockup.
+#[substreams::handlers::store]
+fn store_stake_locks(params: String, events: Events, store: StoreSetProto<StakeLock>) {
+    let lockup = match Params::parse(&params).parse_or("lockup_seconds", 0u64) {
+        Ok(lockup) => lockup,
+        Err(err) => panic!("store_stake_locks: {}", err),
+    };
+
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Stake(ref stake)),
+        }) = event.data
+        {
+            let Some(quantity) = asset::parse(&stake.quantity) else {
+                continue;
+            };
+            store.set(
+                0,
+                keys::stake_lock(&stake.account, &stake.node_id, &quantity.symbol),
+                &StakeLock {
+                    account: stake.account.clone(),
+                    node_id: stake.node_id.clone(),
+                    symbol: quantity.symbol,
+                    precision: u32::from(quantity.precision),
+                    staked_at: event.timestamp,
+                    unlock_at: event.timestamp.saturating_add(lockup),
+                },
+            );
+        }
+    }
+}
+
+/// Store module: Stake positions opened by each account, so they can be listed
+///
+/// Key: `positions:{account}`, a list of `{node_id}:{SYMBOL}` entries appended
+/// on a position's first stake.
+#[substreams::handlers::store]
+fn store_account_positions(locks: Deltas<DeltaProto<StakeLock>>, store: StoreAppend<String>) {
+    for delta in locks.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        let lock = delta.new_value;
+        store.append(
+            0,
+            keys::account_positions(&lock.account),
+            format!("{}:{}", lock.node_id, lock.symbol),
+        );
+    }
+}
+
+/// Map module: An account's stake positions that are still locked
+///
+/// Params: `account=<name>` (required). Lists open positions whose
+/// `unlock_at` is after the current block time, soonest unlock first.
+#[substreams::handlers::map]
+fn map_locked_stakes(
+    params: String,
+    clock: Clock,
+    positions: StoreGetArray<String>,
+    locks: StoreGetProto<StakeLock>,
+    amounts: StoreGetInt64,
+) -> Result<LockedStakes, Error> {
+    let params = Params::parse(&params);
+    let account = params
+        .get("account")
+        .ok_or_else(|| anyhow::anyhow!("map_locked_stakes requires an `account` param"))?
+        .to_string();
+    let now = clock.timestamp.map(|t| t.seconds as u64).unwrap_or(0);
+
+    let mut locked: Vec<LockedStake> = positions
+        .get_last(keys::account_positions(&account))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            let (node_id, symbol) = entry.rsplit_once(':')?;
+            let lock = locks.get_last(keys::stake_lock(&account, node_id, symbol))?;
+            let amount = amounts
+                .get_last(keys::stake_position(&account, node_id, symbol))
+                .unwrap_or(0);
+            (amount > 0 && lock.unlock_at > now).then(|| LockedStake {
+                node_id: lock.node_id,
+                quantity: asset::format_asset(amount, lock.precision as u8, &lock.symbol),
+                staked_at: lock.staked_at,
+                unlock_at: lock.unlock_at,
+                seconds_remaining: lock.unlock_at - now,
+            })
+        })
+        .collect();
+    locked.sort_by_key(|stake| stake.unlock_at);
+
+    Ok(LockedStakes {
+        account,
+        block_num: clock.number,
+        timestamp: now,
+        stakes: locked,
+    })
+}
+
+/// Stakes of a block that open a position, i.e. take it from zero to positive
+///
+/// `positions` is `store_stake_positions`, read at each event's ordinal.
+fn position_openings<'a>(
+    events: &'a Events,
+    positions: &StoreGetInt64,
+) -> Vec<(&'a Event, &'a StakeEvent, asset::Asset)> {
+    events
+        .events
+        .iter()
+        .enumerate()
+        .filter_map(|(index, event)| {
+            let Some(EventData {
+                event: Some(pb::polaris::v1::event_data::Event::Stake(stake)),
+            }) = &event.data
+            else {
+                return None;
+            };
+            let quantity = asset::parse(&stake.quantity)?;
+            let after = positions
+                .get_at(
+                    index as u64,
+                    keys::stake_position(&stake.account, &stake.node_id, &quantity.symbol),
+                )
+                .unwrap_or(0);
+            (after > 0 && after - quantity.amount <= 0).then_some((event, stake, quantity))
+        })
+        .collect()
+}
+
+/// Store module: Number of stake positions opened per symbol
+///
+/// Key: `openings:{SYMBOL}`; numbers the entries of `store_position_openings`.
+#[substreams::handlers::store]
+fn store_position_opening_count(events: Events, positions: StoreGetInt64, store: StoreAddInt64) {
+    for (_, _, quantity) in position_openings(&events, &positions) {
+        store.add(0, keys::position_openings(&quantity.symbol), 1);
+    }
+}
+
+/// Store module: Stake position openings in chronological order
+///
+/// Keys: `openings:{SYMBOL}:{index}`, where index 0 is the oldest opening,
+/// and `opensince:{account}:{node_id}:{SYMBOL}` with the position's latest
+/// opening. A position that is closed and reopened gets a new entry; the old
+/// one no longer matches `opensince` and is stale.
+#[substreams::handlers::store]
+fn store_position_openings(
+    events: Events,
+    positions: StoreGetInt64,
+    counts: StoreGetInt64,
+    store: StoreSetProto<PositionOpening>,
+) {
+    let openings = position_openings(&events, &positions);
+    let symbols: Vec<&str> = openings.iter().map(|(_, _, q)| q.symbol.as_str()).collect();
+    let indices = feed_indices(&counts, keys::position_openings, &symbols);
+
+    for ((event, stake, quantity), index) in openings.iter().zip(indices) {
+        let opening = PositionOpening {
+            account: stake.account.clone(),
+            node_id: stake.node_id.clone(),
+            symbol: quantity.symbol.clone(),
+            precision: u32::from(quantity.precision),
+            opened_at: event.timestamp,
+            block_num: event.block_num,
+            tx_hash: event.tx_hash.clone(),
+            index,
+        };
+        store.set(0, keys::position_opening(&quantity.symbol, index), &opening);
+        store.set(
+            0,
+            keys::open_since(&stake.account, &stake.node_id, &quantity.symbol),
+            &opening,
+        );
+    }
+}
+
+/// Openings `map_oldest_stakes` inspects at most per call
+const MAX_OLDEST_SCAN: u64 = 10_000;
+
+/// Map module: Longest-running open stake positions in a symbol
+///
+/// Params: `symbol=<SYMBOL>&limit=<n>` (symbol defaults to MUS, limit to 10
+/// and is capped at 100) and `min_stake=<asset>` (default none). Walks the
+/// openings from the oldest and keeps those still open since that opening, so
+/// the result is ordered by opening time. Positions under `min_stake` are
+/// skipped and counted in `below_min_stake`. At most `MAX_OLDEST_SCAN`
+/// openings are inspected; `complete` is false when the walk stopped there
+/// before finding `limit` positions.
+#[substreams::handlers::map]
+fn map_oldest_stakes(
+    params: String,
+    clock: Clock,
+    counts: StoreGetInt64,
+    openings: StoreGetProto<PositionOpening>,
+    positions: StoreGetInt64,
+) -> Result<OldestStakes, Error> {
+    const DEFAULT_LIMIT: u64 = 10;
+    const MAX_LIMIT: u64 = 100;
+
+    let params = Params::parse(&params);
+    let symbol = params.get("symbol").unwrap_or("MUS").to_string();
+    let limit = params.parse_or("limit", DEFAULT_LIMIT)?.min(MAX_LIMIT) as usize;
+    let min_stake = min_stake_param(&params, &symbol)?;
+    let now = clock.timestamp.map(|t| t.seconds as u64).unwrap_or(0);
+
+    let total = counts
+        .get_last(keys::position_openings(&symbol))
+        .unwrap_or(0)
+        .max(0) as u64;
+    let scanned = total.min(MAX_OLDEST_SCAN);
+
+    let mut below_min_stake = 0;
+    let stakes: Vec<OldestStake> = (0..scanned)
+        .filter_map(|index| openings.get_last(keys::position_opening(&symbol, index)))
+        .filter(|opening| {
+            let key = keys::open_since(&opening.account, &opening.node_id, &opening.symbol);
+            openings.get_last(key).map(|latest| latest.index) == Some(opening.index)
+        })
+        .filter_map(|opening| {
+            let amount = positions
+                .get_last(keys::stake_position(
+                    &opening.account,
+                    &opening.node_id,
+                    &opening.symbol,
+                ))
+                .unwrap_or(0);
+            let precision = opening.precision as u8;
+            if amount > 0
+                && min_stake
+                    .as_ref()
+                    .is_some_and(|m| asset::is_below(amount, precision, m))
+            {
+                below_min_stake += 1;
+                return None;
+            }
+            (amount > 0).then(|| OldestStake {
+                quantity: asset::format_asset(amount, precision, &opening.symbol),
+                open_seconds: now.saturating_sub(opening.opened_at),
+                account: opening.account,
+                node_id: opening.node_id,
+                opened_at: opening.opened_at,
+            })
+        })
+        .take(limit)
+        .collect();
+
+    Ok(OldestStakes {
+        complete: stakes.len() == limit || scanned == total,
+        symbol,
+        block_num: clock.number,
+        timestamp: now,
+        stakes,
+        below_min_stake,
+    })
+}
+
+/// Optional `min_stake=<asset>` floor of a stake ranking over `symbol`
+fn min_stake_param(params: &Params, symbol: &str) -> Result<Option<asset::Asset>, Error> {
+    let Some(raw) = params.get("min_stake") else {
+        return Ok(None);
+    };
+    match asset::parse(raw) {
+        Some(floor) if floor.symbol == symbol => Ok(Some(floor)),
+        Some(floor) => Err(anyhow::anyhow!(
+            "invalid `min_stake` {:?}: in {}, but the ranking is in {}",
+            raw,
+            floor.symbol,
+            symbol
+        )),
+        None => Err(anyhow::anyhow!(
+            "invalid `min_stake` {:?}: expected an asset such as `1.0000 MUS`",
+            raw
+        )),
+    }
+}
+
+/// Map module: Unstakes from positions opened only recently
+///
+/// Params: `blocks=<N>` (required). Flags every UNSTAKE whose position was
+/// opened (see `store_position_openings`) fewer than N blocks earlier,
+/// including partial unstakes, with the share of the position taken out.
+/// Topping up an old position does not restart its age.
+#[substreams::handlers::map]
+fn map_stake_churn(
+    params: String,
+    events: Events,
+    openings: StoreGetProto<PositionOpening>,
+    positions: StoreGetInt64,
+) -> Result<StakeChurnEvents, Error> {
+    let params = Params::parse(&params);
+    let window: u64 = params
+        .get("blocks")
+        .ok_or_else(|| anyhow::anyhow!("map_stake_churn requires a `blocks` param"))?
+        .parse()
+        .map_err(|_| anyhow::anyhow!("invalid value for param `blocks`"))?;
+
+    let churn = events
+        .events
+        .iter()
+        .enumerate()
+        .filter_map(|(index, event)| {
+            let Some(EventData {
+                event: Some(pb::polaris::v1::event_data::Event::Unstake(unstake)),
+            }) = &event.data
+            else {
+                return None;
+            };
+            let quantity = asset::parse(&unstake.quantity)?;
+            let opening = openings.get_last(keys::open_since(
+                &unstake.account,
+                &unstake.node_id,
+                &quantity.symbol,
+            ))?;
+            let held_blocks = event.block_num.saturating_sub(opening.block_num);
+            if held_blocks >= window {
+                return None;
+            }
+
+            let after = positions
+                .get_at(
+                    index as u64,
+                    keys::stake_position(&unstake.account, &unstake.node_id, &quantity.symbol),
+                )
+                .unwrap_or(0);
+            let before = after + quantity.amount;
+            Some(StakeChurnEvent {
+                account: unstake.account.clone(),
+                node_id: unstake.node_id.clone(),
+                quantity: unstake.quantity.clone(),
+                held_blocks,
+                opened_block: opening.block_num,
+                closed: after <= 0,
+                fraction: if before > 0 {
+                    (quantity.amount as f64 / before as f64).min(1.0)
+                } else {
+                    1.0
+                },
+                tx_hash: event.tx_hash.clone(),
+                block_num: event.block_num,
+            })
+        })
+        .collect();
+
+    Ok(StakeChurnEvents { events: churn })
+}
+
+/// Highest bucket of `store_stake_histogram`: bucket 0 means no stake, bucket
+/// `b >= 1` holds account totals in `[2^(b-1), 2^b)` smallest units
+const STAKE_BUCKETS: u32 = 63;
+
+/// Histogram bucket of an account stake total (see `STAKE_BUCKETS`)
+fn stake_bucket(amount: i64) -> u32 {
+    if amount <= 0 {
+        0
+    } else {
+        64 - amount.leading_zeros()
+    }
+}
+
+/// Store module: Histogram of account stake totals per symbol
+///
+/// Keys: `stakehist:{SYMBOL}:{bucket}:accounts` and `:sum`, for buckets 1 and
+/// up (see `STAKE_BUCKETS`). Each stake or unstake moves its account from the
+/// bucket of its total before the event to the bucket after it.
+#[substreams::handlers::store]
+fn store_stake_histogram(events: Events, positions: StoreGetInt64, store: StoreAddInt64) {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    for (index, event) in events.events.iter().enumerate() {
+        let (account, quantity, sign) = match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Stake(e)) => (&e.account, &e.quantity, 1),
+            Some(Data::Unstake(e)) => (&e.account, &e.quantity, -1),
+            _ => continue,
+        };
+        let Some(quantity) = asset::parse(quantity) else {
+            continue;
+        };
+        let after = positions
+            .get_at(
+                index as u64,
+                keys::account_staked(account, &quantity.symbol),
+            )
+            .unwrap_or(0);
+        let before = after - sign * quantity.amount;
+
+        for (total, direction) in [(before, -1), (after, 1)] {
+            let bucket = stake_bucket(total);
+            if bucket != 0 {
+                let key = |field| keys::stake_histogram(&quantity.symbol, bucket, field);
+                store.add(0, key("accounts"), direction);
+                store.add(0, key("sum"), direction * total);
+            }
+        }
+    }
+}
+
+/// Gini coefficient of a distribution given as (accounts, sum) groups, every
+/// account of a group holding its group's mean, in ascending order of mean
+///
+/// Area under the Lorenz curve, which is exact for such grouped data. 0 when
+/// there is nothing to distribute.
+fn grouped_gini(groups: &[(i64, i64)]) -> f64 {
+    let accounts: i64 = groups.iter().map(|(n, _)| n).sum();
+    let total: i64 = groups.iter().map(|(_, sum)| sum).sum();
+    if accounts <= 0 || total <= 0 {
+        return 0.0;
+    }
+
+    let mut area = 0.0;
+    let mut cumulative = 0.0;
+    for (n, sum) in groups {
+        let share = *sum as f64 / total as f64;
+        area += *n as f64 / accounts as f64 * (2.0 * cumulative + share);
+        cumulative += share;
+    }
+    (1.0 - area).clamp(0.0, 1.0)
+}
+
+/// Map module: Approximate Gini coefficient of account stake totals
+///
+/// Params: `symbol=<SYMBOL>` (default MUS) and `every=<n>` (default 1): only
+/// blocks whose number is a multiple of `every` have an output. Accounts are
+/// grouped by their `store_stake_histogram` bucket and treated as holding
+/// their bucket's mean, which ignores inequality within a bucket, so the
+/// result is a lower bound of the exact Gini.
+#[substreams::handlers::map]
+fn map_stake_gini(
+    params: String,
+    clock: Clock,
+    histogram: StoreGetInt64,
+) -> Result<Option<StakeGini>, Error> {
+    let params = Params::parse(&params);
+    let symbol = params.get("symbol").unwrap_or("MUS").to_string();
+    let every = params.parse_or("every", 1u64)?.max(1);
+    if !clock.number.is_multiple_of(every) {
+        return Ok(None);
+    }
+
+    let groups: Vec<(i64, i64)> = (1..=STAKE_BUCKETS)
+        .filter_map(|bucket| {
+            let get = |field| histogram.get_last(keys::stake_histogram(&symbol, bucket, field));
+            let accounts = get("accounts").unwrap_or(0);
+            (accounts > 0).then(|| (accounts, get("sum").unwrap_or(0)))
+        })
+        .collect();
+
+    Ok(Some(StakeGini {
+        gini: grouped_gini(&groups),
+        accounts: groups.iter().map(|(n, _)| *n as u64).sum(),
+        buckets: groups.len() as u32,
+        symbol,
+        block_num: clock.number,
+    }))
+}
+
+/// Period of a block: period `k` covers blocks `(k-1)*interval + 1` to
+/// `k*interval`, so it closes (snapshot, pulse) on block `k*interval`
+fn block_period(block_num: u64, interval: u64) -> u64 {
+    block_num.div_ceil(interval)
+}
+
+/// Store module: Account stake totals changed in each snapshot period
+///
+/// Params: `interval=<n>` (default 1000), which must match
+/// `map_balance_snapshots`. Key: `balchange:{period}:{account}:{SYMBOL}`
+/// holding the token precision, written on the account's first stake or
+/// unstake in the period. Its Create deltas feed `store_balance_changed`.
+#[substreams::handlers::store]
+fn store_balance_changes(params: String, events: Events, store: StoreSetIfNotExistsInt64) {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let interval = match Params::parse(&params).parse_or("interval", 1_000u64) {
+        Ok(interval) => interval.max(1),
+        Err(err) => panic!("store_balance_changes: {}", err),
+    };
+    for event in &events.events {
+        let (account, quantity) = match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Stake(e)) => (&e.account, &e.quantity),
+            Some(Data::Unstake(e)) => (&e.account, &e.quantity),
+            _ => continue,
+        };
+        if let Some(quantity) = asset::parse(quantity) {
+            store.set_if_not_exists(
+                0,
+                keys::balance_change(
+                    block_period(event.block_num, interval),
+                    account,
+                    &quantity.symbol,
+                ),
+                &i64::from(quantity.precision),
+            );
+        }
+    }
+}
+
+/// Store module: List of the account stake totals changed per snapshot period
+///
+/// Key: `balchanged:{period}`, entries `{account}:{SYMBOL}:{precision}`, each
+/// account and symbol once.
+#[substreams::handlers::store]
+fn store_balance_changed(changes: Deltas<DeltaInt64>, store: StoreAppend<String>) {
+    for delta in changes.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        if let Some((period, account, symbol)) = keys::parse_balance_change(&delta.key) {
+            store.append(
+                0,
+                keys::balance_changed(period),
+                format!("{}:{}:{}", account, symbol, delta.new_value),
+            );
+        }
+    }
+}
+
+/// Map module: Stake totals of the accounts that changed since the last snapshot
+///
+/// Params: `interval=<n>` (default 1000), matching `store_balance_changes`.
+/// Only blocks whose number is a multiple of `interval` have an output, with
+/// one snapshot per account and symbol staked or unstaked during the period
+/// ending there, sorted by account and symbol. A total back at zero is reported as such.
+#[substreams::handlers::map]
+fn map_balance_snapshots(
+    params: String,
+    clock: Clock,
+    changed: StoreGetArray<String>,
+    positions: StoreGetInt64,
+) -> Result<Option<BalanceSnapshots>, Error> {
+    let interval = Params::parse(&params)
+        .parse_or("interval", 1_000u64)?
+        .max(1);
+    if !clock.number.is_multiple_of(interval) {
+        return Ok(None);
+    }
+
+    let mut changes: Vec<(String, String, u8)> = changed
+        .get_last(keys::balance_changed(block_period(clock.number, interval)))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            let mut parts = entry.splitn(3, ':');
+            let (account, symbol) = (parts.next()?, parts.next()?);
+            let precision = parts.next()?.parse().ok()?;
+            Some((account.to_string(), symbol.to_string(), precision))
+        })
+        .collect();
+    changes.sort_unstable();
+
+    let snapshots = changes
+        .into_iter()
+        .map(|(account, symbol, precision)| {
+            let total = positions
+                .get_last(keys::account_staked(&account, &symbol))
+                .unwrap_or(0);
+            BalanceSnapshot {
+                staked: asset::format_asset(total, precision, &symbol),
+                account,
+                block_num: clock.number,
+            }
+        })
+        .collect();
+
+    Ok(Some(BalanceSnapshots {
+        block_num: clock.number,
+        snapshots,
+    }))
+}
+
+/// Pulse period length from `interval=<n>` params (default 100 blocks)
+fn pulse_interval(params: &str) -> Result<u64, Error> {
+    Ok(Params::parse(params).parse_or("interval", 100u64)?.max(1))
+}
+
+/// Store module: Accounts active in each pulse period
+///
+/// Params: `interval=<n>`, matching `map_pulse`. Key:
+/// `pulseactive:{period}:{account}` for every account of `event_accounts`,
+/// written once per period. Its Create deltas count distinct accounts in
+/// `store_pulse_counters`.
+#[substreams::handlers::store]
+fn store_pulse_active(params: String, events: Events, store: StoreSetIfNotExistsInt64) {
+    let interval = match pulse_interval(&params) {
+        Ok(interval) => interval,
+        Err(err) => panic!("store_pulse_active: {}", err),
+    };
+    for event in &events.events {
+        let period = block_period(event.block_num, interval);
+        for account in event_accounts(event) {
+            store.set_if_not_exists(0, keys::pulse_active(period, account), &1);
+        }
+    }
+}
+
+/// Store module: Activity counters of each pulse period
+///
+/// Params: `interval=<n>`, matching `map_pulse`. Keys: `pulse:{period}:events`,
+/// `pulse:{period}:active` (distinct accounts) and
+/// `pulse:{period}:staked:{SYMBOL}` (stakes minus unstakes, in the token's
+/// smallest unit).
+#[substreams::handlers::store]
+fn store_pulse_counters(
+    params: String,
+    events: Events,
+    active: Deltas<DeltaInt64>,
+    store: StoreAddInt64,
+) {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let interval = match pulse_interval(&params) {
+        Ok(interval) => interval,
+        Err(err) => panic!("store_pulse_counters: {}", err),
+    };
+    for event in &events.events {
+        let period = block_period(event.block_num, interval);
+        store.add(0, keys::pulse_counter(period, "events"), 1);
+        let (quantity, sign) = match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Stake(e)) => (&e.quantity, 1),
+            Some(Data::Unstake(e)) => (&e.quantity, -1),
+            _ => continue,
+        };
+        if let Some(quantity) = asset::parse(quantity) {
+            let counter = format!("staked:{}", quantity.symbol);
+            store.add(
+                0,
+                keys::pulse_counter(period, &counter),
+                sign * quantity.amount,
+            );
+        }
+    }
+    for delta in active.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        if let Some(period) = keys::parse_pulse_active(&delta.key) {
+            store.add(0, keys::pulse_counter(period, "active"), 1);
+        }
+    }
+}
+
+/// Map module: Activity summary of the pulse period ending at this block
+///
+/// Params: `interval=<n>` (default 100, matching the pulse stores) and
+/// `symbol=<SYMBOL>` for the stake change (default MUS). Emits on block
+/// numbers divisible by `interval` only.
+#[substreams::handlers::map]
+fn map_pulse(
+    params: String,
+    clock: Clock,
+    counters: StoreGetInt64,
+    symbols: StoreGetArray<String>,
+) -> Result<Option<ActivityPulse>, Error> {
+    let interval = pulse_interval(&params)?;
+    if !clock.number.is_multiple_of(interval) {
+        return Ok(None);
+    }
+    let symbol = Params::parse(&params)
+        .get("symbol")
+        .unwrap_or("MUS")
+        .to_string();
+
+    let period = block_period(clock.number, interval);
+    let counter = |name: &str| {
+        counters
+            .get_last(keys::pulse_counter(period, name))
+            .unwrap_or(0)
+    };
+    Ok(Some(ActivityPulse {
+        from_block: clock.number + 1 - interval,
+        to_block: clock.number,
+        events_in_window: counter("events").max(0) as u64,
+        active_accounts: counter("active").max(0) as u64,
+        net_staked_change: asset::format_asset(
+            counter(&format!("staked:{}", symbol)),
+            symbol_precision(&symbols, &symbol),
+            &symbol,
+        ),
+    }))
+}
+
+/// Store module: Latest respect score of each account
+///
+/// Key: `respect:{account}`. Later rounds overwrite earlier ones. Writes use
+/// the event's index in the block as ordinal, so readers can see the score in
+/// force at each event.
+#[substreams::handlers::store]
+fn store_respect(events: Events, store: StoreSetProto<RespectScore>) {
+    for (index, event) in events.events.iter().enumerate() {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Updrespect(ref update)),
+        }) = event.data
+        {
+            for entry in &update.updates {
+                store.set(
+                    index as u64,
+                    keys::respect(&entry.account),
+                    &RespectScore {
+                        respect: entry.respect,
+                        election_round: update.election_round,
+                        block_num: event.block_num,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Map module: Vote events enriched with the voter's respect at vote time
+///
+/// Kept apart from `map_events` so the raw vote stream stays lean.
+/// `voter_respect` is zero when the voter has no known respect; `weight` is
+/// the `contract_vote_weight` (capped, 1 without respect).
+///
+/// Params: `min_weight=<n>` (default 0) drops votes whose `weight` is below
+/// `n`, for a feed of consequential votes only.
+#[substreams::handlers::map]
+fn map_vote_respect(
+    params: String,
+    events: Events,
+    respect: StoreGetProto<RespectScore>,
+    config: StoreGetProto<ContractConfig>,
+) -> Result<Events, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let min_weight: u32 = Params::parse(&params).parse_or("min_weight", 0)?;
+
+    let events = events
+        .events
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, mut event)| {
+            let Data::Vote(vote) = event.data.as_mut()?.event.as_mut()? else {
+                return None;
+            };
+            vote.voter_respect = respect
+                .get_at(index as u64, keys::respect(&vote.voter))
+                .map(|score| score.respect)
+                .unwrap_or(0);
+            vote.weight = voter_weight_at(&respect, &config, index, &vote.voter);
+            (vote.weight >= min_weight).then_some(event)
+        })
+        .collect();
+
+    Ok(Events { events })
+}
+
+/// Map module: Stakes and unstakes above a threshold, for risk alerts
+///
+/// Params: `threshold=<asset>` (e.g. `threshold=10000.0000 MUS`). Only
+/// movements in the threshold's symbol are compared; differing precisions are
+/// rescaled so `10000 MUS` and `10000.0000 MUS` behave the same.
+#[substreams::handlers::map]
+fn map_large_stakes(params: String, events: Events) -> Result<LargeStakeEvents, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let params = Params::parse(&params);
+    let raw = params
+        .get("threshold")
+        .ok_or_else(|| anyhow::anyhow!("map_large_stakes requires a `threshold` param"))?;
+    let threshold = asset::parse(raw).ok_or_else(|| {
+        anyhow::anyhow!(
+            "invalid `threshold` {:?}: expected an asset such as `10000.0000 MUS`",
+            raw
+        )
+    })?;
+
+    let exceeds = |quantity: &str| {
+        asset::parse(quantity).is_some_and(|q| {
+            q.symbol == threshold.symbol
+                && i128::from(q.amount) * 10i128.pow(u32::from(threshold.precision))
+                    > i128::from(threshold.amount) * 10i128.pow(u32::from(q.precision))
+        })
+    };
+
+    let events = events
+        .events
+        .iter()
+        .filter_map(|event| {
+            let ((account, node_id, quantity), direction) =
+                match event.data.as_ref()?.event.as_ref()? {
+                    Data::Stake(e) => ((&e.account, &e.node_id, &e.quantity), "STAKE"),
+                    Data::Unstake(e) => ((&e.account, &e.node_id, &e.quantity), "UNSTAKE"),
+                    _ => return None,
+                };
+            exceeds(quantity).then(|| LargeStakeEvent {
+                account: account.clone(),
+                node_id: node_id.clone(),
+                quantity: quantity.clone(),
+                direction: direction.to_string(),
+                tx_hash: event.tx_hash.clone(),
+                block_num: event.block_num,
+            })
+        })
+        .collect();
+
+    Ok(LargeStakeEvents { events })
+}
+
+/// Precision of the MUS token, for formatting blocks without MUS movements
+const MUS_PRECISION: u8 = 4;
+
+/// Blocks per bucket of `store_node_stake_flow`; windows are whole buckets
+const NODE_FLOW_BUCKET_BLOCKS: u64 = 1000;
+
+/// Most buckets `map_node_exit_signal` reads per node
+const MAX_NODE_FLOW_BUCKETS: u64 = 500;
+
+/// Store module: Stake and unstake amounts per node, in block buckets
+///
+/// Key: `nodeflow:{node_id}:{SYMBOL}:{bucket}:stake|unstake`, in the token's
+/// smallest unit, where `bucket` is the block number divided by
+/// `NODE_FLOW_BUCKET_BLOCKS`. Old buckets are kept, as a node's keys cannot
+/// be listed to prune them.
+#[substreams::handlers::store]
+fn store_node_stake_flow(events: Events, store: StoreAddInt64) {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    for event in &events.events {
+        let (node_id, quantity, side) = match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Stake(e)) => (&e.node_id, &e.quantity, "stake"),
+            Some(Data::Unstake(e)) => (&e.node_id, &e.quantity, "unstake"),
+            _ => continue,
+        };
+        if let Some(quantity) = asset::parse(quantity) {
+            let bucket = event.block_num / NODE_FLOW_BUCKET_BLOCKS;
+            store.add(
+                0,
+                keys::node_flow(node_id, &quantity.symbol, bucket, side),
+                quantity.amount,
+            );
+        }
+    }
+}
+
+/// Map module: Nodes whose recent unstakes outweigh their stakes
+///
+/// Params: `window=<blocks>` (default 100000), `threshold=<ratio>` (default
+/// 1.0), `symbol=<SYMBOL>` (default MUS) and `nodes=<hash>,...` to check.
+/// Without `nodes`, the nodes staked or unstaked in this block are checked.
+/// A node is flagged when its unstaked/staked ratio over the window exceeds
+/// the threshold, or when it was only unstaked. The window covers whole
+/// buckets, so it starts up to `NODE_FLOW_BUCKET_BLOCKS - 1` blocks early.
+#[substreams::handlers::map]
+fn map_node_exit_signal(
+    params: String,
+    clock: Clock,
+    events: Events,
+    flow: StoreGetInt64,
+    symbols: StoreGetArray<String>,
+) -> Result<NodeExitSignals, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    const DEFAULT_WINDOW: u64 = 100_000;
+
+    let params = Params::parse(&params);
+    let window = params.parse_or("window", DEFAULT_WINDOW)?;
+    let threshold = params.parse_or("threshold", 1.0)?;
+    let symbol = params.get("symbol").unwrap_or("MUS");
+    if window == 0 || window > MAX_NODE_FLOW_BUCKETS * NODE_FLOW_BUCKET_BLOCKS {
+        return Err(anyhow::anyhow!(
+            "map_node_exit_signal `window` must be between 1 and {}",
+            MAX_NODE_FLOW_BUCKETS * NODE_FLOW_BUCKET_BLOCKS
+        ));
+    }
+    let precision = symbol_precision(&symbols, symbol);
+
+    let last = clock.number / NODE_FLOW_BUCKET_BLOCKS;
+    let first = clock.number.saturating_sub(window - 1) / NODE_FLOW_BUCKET_BLOCKS;
+    let total = |node_id: &str, side: &str| -> i64 {
+        (first..=last)
+            .filter_map(|bucket| flow.get_last(keys::node_flow(node_id, symbol, bucket, side)))
+            .sum()
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    let node_ids: Vec<&str> = match params.get("nodes") {
+        Some(nodes) => nodes.split(',').map(str::trim).collect(),
+        None => events
+            .events
+            .iter()
+            .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+                Data::Stake(e) => Some(e.node_id.as_str()),
+                Data::Unstake(e) => Some(e.node_id.as_str()),
+                _ => None,
+            })
+            .collect(),
+    };
+
+    let signals = node_ids
+        .into_iter()
+        .filter(|node| !node.is_empty() && seen.insert(*node))
+        .filter_map(|node_id| {
+            let staked = total(node_id, "stake");
+            let unstaked = total(node_id, "unstake");
+            let ratio = match staked {
+                0 => 0.0,
+                _ => unstaked as f64 / staked as f64,
+            };
+            let flagged = unstaked > 0 && (staked == 0 || ratio > threshold);
+            flagged.then(|| NodeExitSignal {
+                node_id: node_id.to_string(),
+                staked: asset::format_asset(staked, precision, symbol),
+                unstaked: asset::format_asset(unstaked, precision, symbol),
+                ratio,
+            })
+        })
+        .collect();
+
+    Ok(NodeExitSignals {
+        block_num: clock.number,
+        window_start_block: first * NODE_FLOW_BUCKET_BLOCKS,
+        signals,
+    })
+}
+
+/// Map module: Net stake flow of the block
+///
+/// Params: `symbol=<SYMBOL>` (default MUS). Sums the block's STAKE (inflow)
+/// and UNSTAKE (outflow) amounts in that symbol; blocks without any report
+/// zeros, so the series has no gaps.
+#[substreams::handlers::map]
+fn map_stake_flow(params: String, clock: Clock, events: Events) -> Result<StakeFlow, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let params = Params::parse(&params);
+    let symbol = params.get("symbol").unwrap_or("MUS");
+
+    let mut precision = MUS_PRECISION;
+    let (mut inflow, mut outflow) = (0i64, 0i64);
+    for event in &events.events {
+        let (quantity, total) = match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Stake(e)) => (&e.quantity, &mut inflow),
+            Some(Data::Unstake(e)) => (&e.quantity, &mut outflow),
+            _ => continue,
+        };
+        if let Some(quantity) = asset::parse(quantity).filter(|q| q.symbol == symbol) {
+            precision = quantity.precision;
+            *total += quantity.amount;
+        }
+    }
+
+    Ok(StakeFlow {
+        block_num: clock.number,
+        inflow: asset::format_asset(inflow, precision, symbol),
+        outflow: asset::format_asset(outflow, precision, symbol),
+        net: asset::format_asset(inflow - outflow, precision, symbol),
+    })
+}
+
+/// Store module: Accounts that received respect in each election round
+///
+/// Key: `roundmember:{round}:{account}`, written on the account's first update
+/// in the round. Its Create deltas feed `store_round_participation`.
+#[substreams::handlers::store]
+fn store_round_members(events: Events, store: StoreSetIfNotExistsInt64) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Updrespect(ref update)),
+        }) = event.data
+        {
+            for entry in &update.updates {
+                store.set_if_not_exists(
+                    0,
+                    keys::round_member(update.election_round, &entry.account),
+                    &1,
+                );
+            }
+        }
+    }
+}
+
+/// Store module: Distinct accounts that received respect per election round
+///
+/// Key: `round:{round}`. A round updated over several blocks or split into
+/// parts counts each account once.
+#[substreams::handlers::store]
+fn store_round_participation(members: Deltas<DeltaInt64>, store: StoreAddInt64) {
+    for delta in members.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        if let Some((round, _)) = keys::parse_round_member(&delta.key) {
+            store.add(0, keys::round_participation(round), 1);
+        }
+    }
+}
+
+/// Map module: Participation of the election rounds updated in this block
+///
+/// For each round with respect updates in the block: its distinct accounts so
+/// far and those of the round before, for round-over-round change. A round
+/// spanning several blocks is emitted for each of them with a growing count.
+#[substreams::handlers::map]
+fn map_round_participation(
+    events: Events,
+    participation: StoreGetInt64,
+) -> Result<RoundParticipations, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let mut rounds: Vec<u64> = events
+        .events
+        .iter()
+        .filter_map(|event| match event.data.as_ref()?.event.as_ref()? {
+            Data::Updrespect(e) => Some(e.election_round),
+            _ => None,
+        })
+        .collect();
+    rounds.sort_unstable();
+    rounds.dedup();
+
+    let accounts = |round: u64| {
+        participation
+            .get_last(keys::round_participation(round))
+            .unwrap_or(0)
+            .max(0) as u64
+    };
+    let rounds = rounds
+        .into_iter()
+        .map(|round| {
+            let (current, previous) = (accounts(round), round.checked_sub(1).map(accounts));
+            RoundParticipation {
+                election_round: round,
+                accounts: current,
+                previous_accounts: previous.unwrap_or(0),
+                change: current as i64 - previous.unwrap_or(0) as i64,
+            }
+        })
+        .collect();
+
+    Ok(RoundParticipations { rounds })
+}
+
+/// Store module: Respect of each account per election round
+///
+/// Key: `roundrespect:{round}:{account}`. Unlike `store_respect`, earlier
+/// rounds are kept, so each round can be compared with the one before.
+#[substreams::handlers::store]
+fn store_round_respect(events: Events, store: StoreSetInt64) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Updrespect(ref update)),
+        }) = event.data
+        {
+            for entry in &update.updates {
+                store.set(
+                    0,
+                    keys::round_respect(update.election_round, &entry.account),
+                    &i64::from(entry.respect),
+                );
+            }
+        }
+    }
+}
+
+/// Store module: Accounts that received respect per election round
+///
+/// Key: `roundaccounts:{round}`, appended from the Create deltas of
+/// `store_round_members` so each account is listed once per round.
+#[substreams::handlers::store]
+fn store_round_accounts(members: Deltas<DeltaInt64>, store: StoreAppend<String>) {
+    for delta in members.deltas {
+        if delta.operation != Operation::Create {
+            continue;
+        }
+        if let Some((round, account)) = keys::parse_round_member(&delta.key) {
+            store.append(0, keys::round_accounts(round), account.to_string());
+        }
+    }
+}
+
+/// Map module: Round-over-round respect changes
+///
+/// Accounts updated in the block are compared with round `n - 1` right away.
+/// Drop-outs can only be known once a round is complete, which is taken to be
+/// when the next round starts: the block with the first update of round `n`
+/// also emits the accounts of round `n - 2` missing from round `n - 1`.
+#[substreams::handlers::map]
+fn map_respect_deltas(
+    events: Events,
+    respect: StoreGetInt64,
+    accounts: StoreGetArray<String>,
+) -> Result<RespectDeltas, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let score = |round: u64, account: &str| {
+        respect
+            .get_last(keys::round_respect(round, account))
+            .and_then(|r| u32::try_from(r).ok())
+    };
+    let delta = |account: &str, previous: u32, current: u32, round: u64| RespectDelta {
+        account: account.to_string(),
+        previous,
+        current,
+        change: i64::from(current) - i64::from(previous),
+        round,
+    };
+
+    let mut updated: Vec<(u64, &str)> = Vec::new();
+    for event in &events.events {
+        if let Some(Data::Updrespect(update)) = event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            updated.extend(
+                update
+                    .updates
+                    .iter()
+                    .map(|u| (update.election_round, u.account.as_str())),
+            );
+        }
+    }
+    updated.sort_unstable();
+    updated.dedup();
+
+    let mut deltas: Vec<RespectDelta> = updated
+        .iter()
+        .map(|&(round, account)| {
+            let previous = round
+                .checked_sub(1)
+                .and_then(|r| score(r, account))
+                .unwrap_or(0);
+            delta(account, previous, score(round, account).unwrap_or(0), round)
+        })
+        .collect();
+
+    let mut started: Vec<u64> = updated.iter().map(|&(round, _)| round).collect();
+    started.dedup();
+    started.retain(|&round| accounts.get_first(keys::round_accounts(round)).is_none());
+    for closed in started.iter().filter_map(|round| round.checked_sub(1)) {
+        let Some(before) = closed.checked_sub(1) else {
+            continue;
+        };
+        for account in accounts
+            .get_last(keys::round_accounts(before))
+            .unwrap_or_default()
+        {
+            if score(closed, &account).is_none() {
+                let previous = score(before, &account).unwrap_or(0);
+                deltas.push(delta(&account, previous, 0, closed));
+            }
+        }
+    }
+
+    deltas.retain(|d| d.change != 0);
+    deltas.sort_by(|a, b| {
+        (a.round, b.change.abs(), &a.account).cmp(&(b.round, a.change.abs(), &b.account))
+    });
+    Ok(RespectDeltas { deltas })
+}
+
+/// Map module: Stake vs respect pairs for accounts whose stake or respect changed
+///
+/// Stores cannot be enumerated, so a pair is emitted whenever either side of an
+/// account changes; the latest pair per account is its current value. The
+/// missing side is zero (no stake) or absent `respect` (never scored).
+#[substreams::handlers::map]
+fn map_stake_vs_respect(
+    events: Events,
+    positions: StoreGetInt64,
+    respect: StoreGetProto<RespectScore>,
+    symbols: StoreGetArray<String>,
+) -> Result<StakeRespectPairs, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let mut accounts: Vec<&str> = Vec::new();
+    for event in &events.events {
+        match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Stake(e)) => accounts.push(&e.account),
+            Some(Data::Unstake(e)) => accounts.push(&e.account),
+            Some(Data::Updrespect(e)) => {
+                accounts.extend(e.updates.iter().map(|u| u.account.as_str()))
+            }
+            _ => {}
+        }
+    }
+    accounts.sort_unstable();
+    accounts.dedup();
+
+    let symbols: Vec<(String, u8)> = symbols
+        .get_last(keys::STAKE_SYMBOL_LIST)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|entry| {
+            let (symbol, precision) = entry.split_once(':')?;
+            Some((symbol.to_string(), precision.parse().ok()?))
+        })
+        .collect();
+
+    let pairs = accounts
+        .into_iter()
+        .map(|account| StakeRespectPair {
+            account: account.to_string(),
+            staked: symbols
+                .iter()
+                .map(|(symbol, precision)| TokenAmount {
+                    symbol: symbol.clone(),
+                    amount: asset::format_asset(
+                        positions
+                            .get_last(keys::account_staked(account, symbol))
+                            .unwrap_or(0),
+                        *precision,
+                        symbol,
+                    ),
+                })
+                .collect(),
+            respect: respect.get_last(keys::respect(account)),
+        })
+        .collect();
+
+    Ok(StakeRespectPairs { pairs })
+}
+
+// Invariant checks run by `map_invariant_violations` (violation codes)
+
+/// Unlike with no prior like by the account on the node
+const UNLIKE_WITHOUT_LIKE: &str = "UNLIKE_WITHOUT_LIKE";
+/// Finalize of a submission that was never put
+const FINALIZE_UNKNOWN_SUBMISSION: &str = "FINALIZE_UNKNOWN_SUBMISSION";
+/// Vote on a submission that was never put
+const VOTE_UNKNOWN_SUBMISSION: &str = "VOTE_UNKNOWN_SUBMISSION";
+/// Unstake larger than the account's stake on the node
+const UNSTAKE_EXCEEDS_STAKE: &str = "UNSTAKE_EXCEEDS_STAKE";
+
+/// Map module: Cross-event invariant violations found in this block
+///
+/// Each violation carries one of the codes above. "Prior" and "known" mean
+/// since the start block, so a violation can also mean history before it was
+/// not indexed; on a full backfill each one is an indexing bug or a genuine
+/// contract anomaly.
+#[substreams::handlers::map]
+fn map_invariant_violations(
+    events: Events,
+    reversible: StoreGetProto<ActionRef>,
+    authors: StoreGetString,
+    positions: StoreGetInt64,
+) -> Result<InvariantViolations, Error> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    let is_known = |hash: &str| authors.has_last(keys::node_author(hash));
+
+    let mut violations = Vec::new();
+    for (index, event) in events.events.iter().enumerate() {
+        let ord = index as u64;
+        let violation = match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Unlike(e))
+                if !reversible.has_at(ord, keys::reversible_like(&e.account, &e.node_id)) =>
+            {
+                Some((
+                    UNLIKE_WITHOUT_LIKE,
+                    format!("{} unliked {}", e.account, e.node_id),
+                ))
+            }
+            Some(Data::Finalize(e)) if !is_known(&e.tx_hash) => Some((
+                FINALIZE_UNKNOWN_SUBMISSION,
+                format!("finalized {}", e.tx_hash),
+            )),
+            Some(Data::Vote(e)) if !is_known(&e.tx_hash) => Some((
+                VOTE_UNKNOWN_SUBMISSION,
+                format!("{} voted on {}", e.voter, e.tx_hash),
+            )),
+            Some(Data::Unstake(e)) => asset::parse(&e.quantity).and_then(|quantity| {
+                // Position as of this event, i.e. after the unstake was applied
+                let position = positions
+                    .get_at(
+                        ord,
+                        keys::stake_position(&e.account, &e.node_id, &quantity.symbol),
+                    )
+                    .unwrap_or(0);
+                (position < 0).then(|| {
+                    (
+                        UNSTAKE_EXCEEDS_STAKE,
+                        format!(
+                            "{} unstaked {} from {}, {} more than staked",
+                            e.account,
+                            e.quantity,
+                            e.node_id,
+                            asset::format_asset(-position, quantity.precision, &quantity.symbol)
+                        ),
+                    )
+                })
+            }),
+            _ => None,
+        };
+
+        if let Some((code, detail)) = violation {
+            violations.push(InvariantViolation {
+                code: code.to_string(),
+                tx_hash: event.tx_hash.clone(),
+                block_num: event.block_num,
+                event_type: event.event_type.clone(),
+                detail,
+            });
+        }
+    }
+
+    Ok(InvariantViolations { violations })
+}
+
+/// Contract table holding per-account stake rows (`stake_record`)
+const STAKES_TABLE: &str = "stakes";
+
+/// Store module: Oracle account currently in force
+///
+/// Key: `oracle`. Each `setoracle` overwrites it, so the store holds the
+/// latest one together with where it was set.
+#[substreams::handlers::store]
+fn store_oracle(events: Events, store: StoreSetProto<CurrentOracle>) {
+    for event in &events.events {
+        if let Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::OracleSet(e)),
+        }) = &event.data
+        {
+            store.set(
+                0,
+                keys::ORACLE,
+                &CurrentOracle {
+                    oracle: e.oracle.clone(),
+                    block_num: event.block_num,
+                    tx_hash: event.tx_hash.clone(),
+                    timestamp: event.timestamp,
+                },
+            );
+        }
+    }
+}
+
+/// Map module: Current oracle account, as of this block
+///
+/// No output until the first `setoracle` since the start block.
+#[substreams::handlers::map]
+fn map_current_oracle(
+    oracle: StoreGetProto<CurrentOracle>,
+) -> Result<Option<CurrentOracle>, Error> {
+    Ok(oracle.get_last(keys::ORACLE))
+}
+
+/// Map module: Stake totals from actions vs. from `stakes` table deltas
+///
+/// Params: contract account (as for `map_events`). For every symbol, the net
+/// STAKE/UNSTAKE amount of the block's events is compared with the net change
+/// of `amount` over the contract's `stakes` table rows. Only diverging symbols
+/// are emitted: a non-zero delta means events are missing or double counted.
+#[substreams::handlers::map]
+fn map_stake_reconciliation(
+    params: String,
+    block: Block,
+    events: Events,
+) -> Result<StakeReconciliations, Error> {
+    let params = Params::parse(&params);
+    Ok(StakeReconciliations {
+        reconciliations: stake_reconciliation(&block, params.contract(), &events.events),
+    })
+}
+
+/// Diverging per-symbol stake totals of `block`, see `map_stake_reconciliation`
+fn stake_reconciliation(
+    block: &Block,
+    contract_account: &str,
+    events: &[Event],
+) -> Vec<StakeReconciliation> {
+    use pb::polaris::v1::event_data::Event as Data;
+
+    // symbol -> (precision, action total, table total)
+    let mut totals: std::collections::BTreeMap<String, (u8, i64, i64)> = Default::default();
+    let mut add = |quantity: asset::Asset, action: i64, table: i64| {
+        let entry = totals.entry(quantity.symbol).or_default();
+        entry.0 = quantity.precision;
+        entry.1 += action * quantity.amount;
+        entry.2 += table * quantity.amount;
+    };
+
+    for event in events {
+        let (quantity, sign) = match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(Data::Stake(e)) => (&e.quantity, 1),
+            Some(Data::Unstake(e)) => (&e.quantity, -1),
+            _ => continue,
+        };
+        if let Some(quantity) = asset::parse(quantity) {
+            add(quantity, sign, 0);
+        }
+    }
+
+    let ops = block
+        .transaction_traces()
+        .flat_map(|trx| &trx.db_ops)
+        .filter(|op| op.code == contract_account && op.table_name == STAKES_TABLE);
+    for op in ops {
+        if let Some(old) = stake_row_amount(&op.old_data_json, &op.old_data) {
+            add(old, 0, -1);
+        }
+        if let Some(new) = stake_row_amount(&op.new_data_json, &op.new_data) {
+            add(new, 0, 1);
+        }
+    }
+
+    totals
+        .into_iter()
+        .filter(|(_, (_, action, table))| action != table)
+        .map(|(symbol, (precision, action, table))| StakeReconciliation {
+            block_num: u64::from(block.number),
+            action_total: asset::format_asset(action, precision, &symbol),
+            table_total: asset::format_asset(table, precision, &symbol),
+            delta: asset::format_asset(action - table, precision, &symbol),
+            symbol,
+        })
+        .collect()
+}
+
+/// `amount` of a `stakes` row image, from its JSON or else its binary form
+///
+/// Inserts have no old image and removals no new one; both sides are empty
+/// then and yield `None`.
+fn stake_row_amount(json: &str, raw: &[u8]) -> Option<asset::Asset> {
+    #[derive(serde::Deserialize)]
+    struct StakeRow {
+        amount: String,
+    }
+
+    let amount = if !json.is_empty() {
+        serde_json::from_str::<StakeRow>(json).ok()?.amount
+    } else if !raw.is_empty() {
+        abi::binary::decode_stake_row_amount(raw)?
+    } else {
+        return None;
+    };
+    asset::parse(&amount)
+}
+
+/// Contract table holding registry anchors (`anchor`), scoped to the contract
+const ANCHORS_TABLE: &str = "anchors";
+
+/// `expires_at` of the `anchors` row `trx` wrote for `hash`, from its JSON or
+/// else its binary image
+///
+/// The contract sets it on `put` to the block time plus the type's voting
+/// window; the action data does not carry it.
+fn anchor_expiry(
+    trx: &substreams_antelope::pb::TransactionTrace,
+    contract_account: &str,
+    hash: &str,
+) -> Option<u64> {
+    #[derive(serde::Deserialize)]
+    struct AnchorRow {
+        hash: String,
+        expires_at: u64,
+    }
+
+    trx.db_ops
+        .iter()
+        .filter(|op| {
+            op.code == contract_account
+                && op.scope == contract_account
+                && op.table_name == ANCHORS_TABLE
+        })
+        .filter_map(|op| {
+            if !op.new_data_json.is_empty() {
+                serde_json::from_str::<AnchorRow>(&op.new_data_json).ok()
+            } else {
+                serde_json::from_value(abi::binary::decode_anchor_row(&op.new_data)?).ok()
+            }
+        })
+        .find(|row| row.hash.eq_ignore_ascii_case(hash))
+        .map(|row| row.expires_at)
+}
+
+/// Contract table read by `map_table_deltas`: a table name, in one scope or
+/// (without scope) in every scope
+#[derive(Debug, PartialEq)]
+struct TableFilter {
+    scope: Option<String>,
+    table: String,
+}
+
+impl TableFilter {
+    /// Parse `tables=<table>|<scope>:<table>,...`; without the param, the
+    /// `stakes` table in every scope and the contract-scoped `anchors` table
+    fn from_params(params: &Params) -> Result<Vec<TableFilter>, Error> {
+        let Some(list) = params.get("tables") else {
+            return Ok(vec![
+                TableFilter {
+                    scope: None,
+                    table: STAKES_TABLE.to_string(),
+                },
+                TableFilter {
+                    scope: Some(params.contract().to_string()),
+                    table: ANCHORS_TABLE.to_string(),
+                },
+            ]);
+        };
+        list.split(',')
+            .map(str::trim)
+            .map(|entry| {
+                let (scope, table) = match entry.split_once(':') {
+                    Some((scope, table)) => (Some(scope.trim().to_string()), table.trim()),
+                    None => (None, entry),
+                };
+                if table.is_empty() || scope.as_deref() == Some("") {
+                    return Err(anyhow::anyhow!(
+                        "invalid entry in param `tables`: {:?} (expected <table> or <scope>:<table>)",
+                        entry
+                    ));
+                }
+                Ok(TableFilter {
+                    scope,
+                    table: table.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn matches(&self, op: &substreams_antelope::pb::DbOp) -> bool {
+        op.table_name == self.table && self.scope.as_ref().is_none_or(|s| *s == op.scope)
+    }
+}
+
+/// Map module: Row changes of the contract's tables
+///
+/// Params: contract account (as for `map_events`) and `tables=<list>`, a
+/// comma-separated list of `<table>` (any scope) or `<scope>:<table>`
+/// entries. Defaults to `stakes` in every scope (stakes are scoped by
+/// account) and `<contract>:anchors`, the registry. Only `db_ops` of the
+/// contract on a listed table are emitted, in block order, so new tables
+/// such as `pendingrwd` can be followed without a code change.
+#[substreams::handlers::map]
+fn map_table_deltas(params: String, block: Block) -> Result<TableDeltas, Error> {
+    let params = Params::parse(&params);
+    let filters = TableFilter::from_params(&params)?;
+    Ok(TableDeltas {
+        deltas: table_deltas(&block, params.contract(), &filters),
+    })
+}
+
+/// Row changes of `block` on the tables in `filters`, see `map_table_deltas`
+fn table_deltas(block: &Block, contract_account: &str, filters: &[TableFilter]) -> Vec<TableDelta> {
+    use substreams_antelope::pb::db_op::Operation as DbOperation;
+
+    block
+        .transaction_traces()
+        .flat_map(|trx| trx.db_ops.iter().map(move |op| (trx, op)))
+        .filter(|(_, op)| op.code == contract_account && filters.iter().any(|f| f.matches(op)))
+        .map(|(trx, op)| TableDelta {
+            tx_hash: trx.id.clone(),
+            block_num: u64::from(block.number),
+            operation: DbOperation::try_from(op.operation)
+                .unwrap_or(DbOperation::Unknown)
+                .as_str_name()
+                .trim_start_matches("OPERATION_")
+                .to_string(),
+            code: op.code.clone(),
+            scope: op.scope.clone(),
+            table_name: op.table_name.clone(),
+            primary_key: op.primary_key.clone(),
+            old_data_json: op.old_data_json.clone(),
+            new_data_json: op.new_data_json.clone(),
+            old_data: op.old_data.clone(),
+            new_data: op.new_data.clone(),
+            action_index: op.action_index,
+        })
+        .collect()
+}
+
+/// Contract singleton holding `global_state`, scoped to the contract
+const GLOBALS_TABLE: &str = "globals";
+
+/// Config written by the last `globals` change of `block`, if any
+fn contract_config(block: &Block, contract_account: &str) -> Option<ContractConfig> {
+    let (trx, op) = block
+        .transaction_traces()
+        .flat_map(|trx| trx.db_ops.iter().map(move |op| (trx, op)))
+        .filter(|(_, op)| {
+            op.code == contract_account
+                && op.scope == contract_account
+                && op.table_name == GLOBALS_TABLE
+        })
+        .filter(|(_, op)| !op.new_data_json.is_empty() || !op.new_data.is_empty())
+        .last()?;
+
+    let row = if !op.new_data_json.is_empty() {
+        serde_json::from_str(&op.new_data_json).ok()
+    } else {
+        abi::binary::decode_globals_row(&op.new_data)
+    };
+    let config = row.as_ref().and_then(config_from_row);
+    if config.is_none() {
+        log::info!(
+            "WARN: undecodable {} row in trx {} (block {})",
+            GLOBALS_TABLE,
+            trx.id,
+            block.number
+        );
+    }
+    config.map(|config| ContractConfig {
+        block_num: u64::from(block.number),
+        tx_hash: trx.id.clone(),
+        ..config
+    })
+}
+
+/// Governance fields of a JSON `global_state` row; the `x` and `carry`
+/// accumulators change with every submission and are left out
+fn config_from_row(row: &serde_json::Value) -> Option<ContractConfig> {
+    use serde_json::Value;
+
+    // nodeos writes 64-bit integers as strings, smaller ones as numbers
+    let uint = |field: &str| match row.get(field)? {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    };
+    let text = |field: &str| Some(row.get(field)?.as_str()?.to_string());
+    let flag = |field: &str| match row.get(field)? {
+        Value::Bool(b) => Some(*b),
+        Value::Number(n) => Some(n.as_u64()? != 0),
+        _ => None,
+    };
+
+    Some(ContractConfig {
+        round: uint("round")?,
+        fractally_oracle: text("fractally_oracle")?,
+        token_contract: text("token_contract")?,
+        token_symbol: text("token_symbol")?,
+        council_account: text("council_account")?,
+        approval_threshold_bp: uint("approval_threshold_bp")?,
+        max_vote_weight: u32::try_from(uint("max_vote_weight")?).ok()?,
+        attestor_respect_threshold: u32::try_from(uint("attestor_respect_threshold")?).ok()?,
+        paused: flag("paused")?,
+        vote_window_release: u32::try_from(uint("vote_window_release")?).ok()?,
+        vote_window_mint: u32::try_from(uint("vote_window_mint")?).ok()?,
+        vote_window_resolve: u32::try_from(uint("vote_window_resolve")?).ok()?,
+        vote_window_claim: u32::try_from(uint("vote_window_claim")?).ok()?,
+        vote_window_merge: u32::try_from(uint("vote_window_merge")?).ok()?,
+        vote_window_default: u32::try_from(uint("vote_window_default")?).ok()?,
+        multiplier_release: uint("multiplier_release")?,
+        multiplier_mint: uint("multiplier_mint")?,
+        multiplier_resolve: uint("multiplier_resolve")?,
+        multiplier_add_claim: uint("multiplier_add_claim")?,
+        multiplier_edit_claim: uint("multiplier_edit_claim")?,
+        multiplier_merge: uint("multiplier_merge")?,
+        approved_author_pct: uint("approved_author_pct")?,
+        approved_voters_pct: uint("approved_voters_pct")?,
+        approved_stakers_pct: uint("approved_stakers_pct")?,
+        rejected_voters_pct: uint("rejected_voters_pct")?,
+        rejected_stakers_pct: uint("rejected_stakers_pct")?,
+        block_num: 0,
+        tx_hash: String::new(),
+    })
+}
+
+/// Store module: Latest contract config
+///
+/// Params: contract account (as for `map_events`). Key: `config`, set on
+/// every block that writes the `globals` row.
+#[substreams::handlers::store]
+fn store_contract_config(params: String, block: Block, store: StoreSetProto<ContractConfig>) {
+    let params = Params::parse(&params);
+    if let Some(config) = contract_config(&block, params.contract()) {
+        store.set(0, keys::CONTRACT_CONFIG, &config);
+    }
+}
+
+/// Map module: Contract config, on the blocks that change it
+///
+/// The contract rewrites `globals` on every submission (its counter moves),
+/// so writes that leave every governance field as it was are skipped. The
+/// first config seen after the start block is always emitted.
+#[substreams::handlers::map]
+fn map_contract_config(
+    config: Deltas<DeltaProto<ContractConfig>>,
+) -> Result<Option<ContractConfig>, Error> {
+    let Some(delta) = config.deltas.into_iter().last() else {
+        return Ok(None);
+    };
+    let governance = |config: &ContractConfig| ContractConfig {
+        block_num: 0,
+        tx_hash: String::new(),
+        ..config.clone()
+    };
+    let unchanged = delta.operation == Operation::Update
+        && governance(&delta.old_value) == governance(&delta.new_value);
+    Ok((!unchanged).then_some(delta.new_value))
+}
+
+/// Event types emitted by `map_events`, tracked by the rate and liveness modules
+const EVENT_TYPES: [&str; 13] = [
+    "PUT",
+    "ATTEST",
+    "VOTE",
+    "FINALIZE",
+    "STAKE",
+    "UNSTAKE",
+    "LIKE",
+    "UNLIKE",
+    "UPDATE_RESPECT",
+    "REWARD",
+    "CONTRACT_PAUSED",
+    "CONTRACT_UNPAUSED",
+    "ORACLE_SET",
+];
+
+/// Ring size of `store_rate_checkpoints`; rate windows must be shorter
+const RATE_RING_BLOCKS: u64 = 1024;
+
+/// Cumulative counter keys in `store_stats` followed by their rate labels
+fn rate_counters() -> impl Iterator<Item = (String, &'static str)> {
+    std::iter::once(("total_events".to_string(), "ALL"))
+        .chain(EVENT_TYPES.iter().map(|t| (keys::stats_type(t), *t)))
+}
+
+/// Store module: Per-block snapshots of the cumulative event counters
+///
+/// Keys live in a ring of `RATE_RING_BLOCKS` slots so the store stays bounded:
+/// `slot:{n % ring}:block` holds the block number and `slot:{n % ring}:{type}`
+/// the cumulative count of that type (or `ALL`) after block `n`.
+#[substreams::handlers::store]
+fn store_rate_checkpoints(clock: Clock, stats: StoreGetInt64, store: StoreSetInt64) {
+    let slot = clock.number % RATE_RING_BLOCKS;
+    store.set(0, keys::rate_slot_block(slot), &block_value(clock.number));
+    for (key, label) in rate_counters() {
+        let total = stats.get_last(&key).unwrap_or(0);
+        store.set(0, keys::rate_slot_counter(slot, label), &total);
+    }
+}
+
+/// Map module: Rolling events-per-block throughput over the last K blocks
+///
+/// Params: `window=<blocks>` (default 100, must be below 1024). The window is
+/// measured in blocks, not wall time; output starts once K blocks have been
+/// processed.
+#[substreams::handlers::map]
+fn map_rates(
+    params: String,
+    clock: Clock,
+    stats: StoreGetInt64,
+    checkpoints: StoreGetInt64,
+) -> Result<Option<EventRates>, Error> {
+    const DEFAULT_WINDOW: u64 = 100;
+
+    let window = Params::parse(&params).parse_or("window", DEFAULT_WINDOW)?;
+    if window == 0 || window >= RATE_RING_BLOCKS {
+        return Err(anyhow::anyhow!(
+            "map_rates `window` must be between 1 and {}",
+            RATE_RING_BLOCKS - 1
+        ));
+    }
+
+    // Snapshot taken `window` blocks ago; absent while the window warms up
+    let Some(start) = clock.number.checked_sub(window) else {
+        return Ok(None);
+    };
+    let slot = start % RATE_RING_BLOCKS;
+    if checkpoints.get_last(keys::rate_slot_block(slot)) != Some(block_value(start)) {
+        return Ok(None);
+    }
+
+    let rates = rate_counters()
+        .map(|(key, label)| {
+            let now = stats.get_last(&key).unwrap_or(0);
+            let then = checkpoints
+                .get_last(keys::rate_slot_counter(slot, label))
+                .unwrap_or(0);
+            let events = (now - then).max(0) as u64;
+            EventRate {
+                event_type: label.to_string(),
+                events,
+                events_per_block: events as f64 / window as f64,
+            }
+        })
+        .collect();
+
+    Ok(Some(EventRates {
+        block_num: clock.number,
+        window_blocks: window,
+        rates,
+    }))
+}
+
+/// Store module: Block and time of the most recent event of each type
+///
+/// Keys: `lastseen:{EVENT_TYPE}:block` and `lastseen:{EVENT_TYPE}:time`.
+#[substreams::handlers::store]
+fn store_last_seen(events: Events, store: StoreSetInt64) {
+    for event in &events.events {
+        store.set(
+            0,
+            keys::last_seen_block(&event.event_type),
+            &block_value(event.block_num),
+        );
+        store.set(
+            0,
+            keys::last_seen_time(&event.event_type),
+            &(event.timestamp as i64),
+        );
+    }
+}
+
+/// Map module: Blocks and seconds since the last event of each type
+///
+/// Emitted every block so a monitor can alert when, for example, no FINALIZE
+/// has landed in N blocks. Types never seen since the start block report
+/// `seen = false` and zero ages.
+#[substreams::handlers::map]
+fn map_liveness(clock: Clock, last_seen: StoreGetInt64) -> Result<Liveness, Error> {
+    let now = clock.timestamp.map(|t| t.seconds as u64).unwrap_or(0);
+
+    let types = EVENT_TYPES
+        .iter()
+        .map(|event_type| {
+            let block = last_seen
+                .get_last(keys::last_seen_block(event_type))
+                .map(value_block);
+            let time = last_seen
+                .get_last(keys::last_seen_time(event_type))
+                .unwrap_or(0) as u64;
+            match block {
+                Some(block) => EventLiveness {
+                    event_type: event_type.to_string(),
+                    seen: true,
+                    last_block: block,
+                    blocks_since: clock.number.saturating_sub(block),
+                    seconds_since: now.saturating_sub(time),
+                },
+                None => EventLiveness {
+                    event_type: event_type.to_string(),
+                    ..Default::default()
+                },
+            }
+        })
+        .collect();
+
+    Ok(Liveness {
+        block_num: clock.number,
+        timestamp: now,
+        types,
+    })
+}
+
+// ============ EVENT EXTRACTION FUNCTIONS ============
+// PERF-05: Use Action::decode() trait method for type-safe deserialization.
+// Pre-computed block_num/timestamp passed as args to avoid redundant lookups.
 
 #[inline]
 fn extract_put_event(
-    tx_hash: &str,
+    trx: &substreams_antelope::pb::TransactionTrace,
     block_num: u64,
     timestamp: u64,
     action_trace: &substreams_antelope::pb::ActionTrace,
+    contract_account: &str,
 ) -> Option<Event> {
-    use abi::polaris_music::actions::Put;
-
-    let put = Put::decode(action_trace).ok()?;
+    let put = decode_put(action_trace)?;
+    let parent = normalize_parent(put.parent);
+    let expires_at = anchor_expiry(trx, contract_account, &put.hash).unwrap_or(0);
 
     Some(Event {
-        tx_hash: tx_hash.to_string(),
+        tx_hash: trx.id.clone(),
         block_num,
         timestamp,
         event_type: "PUT".to_string(),
@@ -328,16 +6762,74 @@ fn extract_put_event(
             event: Some(pb::polaris::v1::event_data::Event::Put(PutEvent {
                 author: put.author,
                 type_: put.type_ as u32,
+                type_name: abi::content_types::content_type_name(put.type_ as u32).to_string(),
                 hash: put.hash,
-                parent: put.parent,
+                is_root: parent.is_empty(),
+                parent,
                 ts: put.ts as u64,
                 tags: put.tags,
-                expires_at: 0,
+                expires_at,
             })),
         }),
+        ..Default::default()
     })
 }
 
+/// Decode a contract action from `json_data`, falling back to the ABI-decoded
+/// `raw_data` on block sources that do not pre-decode actions.
+fn decode_action<A>(action_trace: &substreams_antelope::pb::ActionTrace) -> Option<A>
+where
+    A: Action + serde::de::DeserializeOwned,
+{
+    let action = action_trace.action.as_ref()?;
+    if !action.json_data.is_empty() {
+        return A::decode(action_trace).ok();
+    }
+    serde_json::from_value(action_data(action_trace)?).ok()
+}
+
+/// Action payload as JSON, from `json_data` or decoded from `raw_data`
+fn action_data(action_trace: &substreams_antelope::pb::ActionTrace) -> Option<serde_json::Value> {
+    let action = action_trace.action.as_ref()?;
+    if !action.json_data.is_empty() {
+        return serde_json::from_str(&action.json_data).ok();
+    }
+    abi::binary::decode_contract_action(&action.name, &action.raw_data)
+}
+
+/// Decode a `put` action, tolerating an absent parent.
+///
+/// The ABI declares `parent` as `checksum256?`, but the generated binding types
+/// it as a plain string, so root posts serialized with `"parent": null` fail the
+/// strict decode. Retry with the null replaced by an empty string.
+fn decode_put(
+    action_trace: &substreams_antelope::pb::ActionTrace,
+) -> Option<abi::polaris_music::actions::Put> {
+    if let Some(put) = decode_action(action_trace) {
+        return Some(put);
+    }
+
+    let mut value = action_data(action_trace)?;
+    let parent = value.get_mut("parent")?;
+    if !parent.is_null() {
+        return None;
+    }
+    *parent = serde_json::Value::String(String::new());
+    serde_json::from_value(value).ok()
+}
+
+/// Canonical parent hash: empty for root posts.
+///
+/// An all-zero checksum256 is the null hash, not a real node, so it is treated
+/// as "no parent" just like an absent or empty value.
+fn normalize_parent(parent: String) -> String {
+    if parent.bytes().all(|b| b == b'0') {
+        String::new()
+    } else {
+        parent
+    }
+}
+
 #[inline]
 fn extract_attest_event(
     tx_hash: &str,
@@ -347,7 +6839,7 @@ fn extract_attest_event(
 ) -> Option<Event> {
     use abi::polaris_music::actions::Attest;
 
-    let attest = Attest::decode(action_trace).ok()?;
+    let attest = decode_action::<Attest>(action_trace)?;
 
     Some(Event {
         tx_hash: tx_hash.to_string(),
@@ -359,8 +6851,14 @@ fn extract_attest_event(
                 attestor: attest.attestor,
                 tx_hash: attest.tx_hash,
                 confirmed_type: attest.confirmed_type as u32,
+                content_type: 0, // Filled in by map_content_type_events
+                confirmed_type_name: abi::content_types::content_type_name(
+                    attest.confirmed_type as u32,
+                )
+                .to_string(),
             })),
         }),
+        ..Default::default()
     })
 }
 
@@ -373,7 +6871,7 @@ fn extract_vote_event(
 ) -> Option<Event> {
     use abi::polaris_music::actions::Vote;
 
-    let vote = Vote::decode(action_trace).ok()?;
+    let vote = decode_action::<Vote>(action_trace)?;
 
     Some(Event {
         tx_hash: tx_hash.to_string(),
@@ -385,36 +6883,43 @@ fn extract_vote_event(
                 voter: vote.voter,
                 tx_hash: vote.tx_hash,
                 val: vote.val as i32,
-                weight: 0, // Not in action data - would need table lookup
+                weight: 0,        // Not in action data - would need table lookup
+                voter_respect: 0, // Filled in by map_vote_respect
             })),
         }),
+        ..Default::default()
     })
 }
 
 #[inline]
 fn extract_finalize_event(
-    tx_hash: &str,
+    trx: &substreams_antelope::pb::TransactionTrace,
     block_num: u64,
     timestamp: u64,
     action_trace: &substreams_antelope::pb::ActionTrace,
+    contract_account: &str,
 ) -> Option<Event> {
     use abi::polaris_music::actions::Finalize;
 
-    let finalize = Finalize::decode(action_trace).ok()?;
+    let finalize = decode_action::<Finalize>(action_trace)?;
 
     Some(Event {
-        tx_hash: tx_hash.to_string(),
+        tx_hash: trx.id.clone(),
         block_num,
         timestamp,
         event_type: "FINALIZE".to_string(),
         data: Some(EventData {
-            event: Some(pb::polaris::v1::event_data::Event::Finalize(FinalizeEvent {
-                tx_hash: finalize.tx_hash,
-                accepted: false,
-                approval_percent: 0,
-                reward_amount: 0,
-            })),
+            event: Some(pb::polaris::v1::event_data::Event::Finalize(
+                FinalizeEvent {
+                    tx_hash: finalize.tx_hash,
+                    accepted: false,
+                    approval_percent: 0,
+                    reward_amount: finalize_reward_amount(trx, action_trace, contract_account),
+                    action_ordinal: action_trace.action_ordinal,
+                },
+            )),
         }),
+        ..Default::default()
     })
 }
 
@@ -427,7 +6932,7 @@ fn extract_stake_event(
 ) -> Option<Event> {
     use abi::polaris_music::actions::Stake;
 
-    let stake = Stake::decode(action_trace).ok()?;
+    let stake = decode_action::<Stake>(action_trace)?;
 
     Some(Event {
         tx_hash: tx_hash.to_string(),
@@ -439,8 +6944,10 @@ fn extract_stake_event(
                 account: stake.account,
                 node_id: stake.node_id,
                 quantity: stake.quantity,
+                content_type: 0,
             })),
         }),
+        ..Default::default()
     })
 }
 
@@ -453,7 +6960,7 @@ fn extract_unstake_event(
 ) -> Option<Event> {
     use abi::polaris_music::actions::Unstake;
 
-    let unstake = Unstake::decode(action_trace).ok()?;
+    let unstake = decode_action::<Unstake>(action_trace)?;
 
     Some(Event {
         tx_hash: tx_hash.to_string(),
@@ -465,8 +6972,11 @@ fn extract_unstake_event(
                 account: unstake.account,
                 node_id: unstake.node_id,
                 quantity: unstake.quantity,
+                reverses_tx_hash: String::new(),
+                content_type: 0,
             })),
         }),
+        ..Default::default()
     })
 }
 
@@ -479,7 +6989,7 @@ fn extract_like_event(
 ) -> Option<Event> {
     use abi::polaris_music::actions::Like;
 
-    let like = Like::decode(action_trace).ok()?;
+    let like = decode_action::<Like>(action_trace)?;
 
     Some(Event {
         tx_hash: tx_hash.to_string(),
@@ -491,8 +7001,11 @@ fn extract_like_event(
                 account: like.account,
                 node_id: like.node_id,
                 path: like.node_path,
+                content_author: String::new(),
+                content_type: 0,
             })),
         }),
+        ..Default::default()
     })
 }
 
@@ -505,7 +7018,7 @@ fn extract_unlike_event(
 ) -> Option<Event> {
     use abi::polaris_music::actions::Unlike;
 
-    let unlike = Unlike::decode(action_trace).ok()?;
+    let unlike = decode_action::<Unlike>(action_trace)?;
 
     Some(Event {
         tx_hash: tx_hash.to_string(),
@@ -516,23 +7029,40 @@ fn extract_unlike_event(
             event: Some(pb::polaris::v1::event_data::Event::Unlike(UnlikeEvent {
                 account: unlike.account,
                 node_id: unlike.node_id,
+                content_author: String::new(),
+                reverses_tx_hash: String::new(),
+                content_type: 0,
             })),
         }),
+        ..Default::default()
     })
 }
 
+/// Most respect updates carried by one `UpdateRespectEvent`
+///
+/// A single `updrespect` can score thousands of accounts. Larger payloads are
+/// split into consecutive events sharing the election round, tagged with
+/// `part`/`parts`, rather than truncated: respect feeds `store_respect`, so a
+/// dropped account would keep a stale score with no way to recover it.
+const MAX_RESPECT_UPDATES: usize = 500;
+
 #[inline]
-fn extract_update_respect_event(
+fn extract_update_respect_events(
     tx_hash: &str,
     block_num: u64,
     timestamp: u64,
     action_trace: &substreams_antelope::pb::ActionTrace,
-) -> Option<Event> {
+) -> Vec<Event> {
     use abi::polaris_music::actions::Updrespect;
 
-    let update = Updrespect::decode(action_trace).ok()?;
+    // `respect_data` values are uint32 in the ABI: a payload with a negative
+    // or oversized score fails to decode as a whole instead of wrapping
+    let Some(update) = decode_action::<Updrespect>(action_trace) else {
+        log::info!("Failed to decode updrespect action in tx {}", tx_hash);
+        return Vec::new();
+    };
 
-    let updates = update
+    let updates: Vec<RespectUpdate> = update
         .respect_data
         .iter()
         .map(|pair| RespectUpdate {
@@ -541,18 +7071,1524 @@ fn extract_update_respect_event(
         })
         .collect();
 
+    if updates.len() > MAX_RESPECT_UPDATES {
+        log::info!(
+            "Splitting {} respect updates in tx {} into chunks of {}",
+            updates.len(),
+            tx_hash,
+            MAX_RESPECT_UPDATES
+        );
+    }
+
+    // An empty update still yields one event so the round is recorded
+    let chunks: Vec<&[RespectUpdate]> = if updates.is_empty() {
+        vec![&[]]
+    } else {
+        updates.chunks(MAX_RESPECT_UPDATES).collect()
+    };
+    let parts = chunks.len() as u32;
+
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(part, chunk)| Event {
+            tx_hash: tx_hash.to_string(),
+            block_num,
+            timestamp,
+            event_type: "UPDATE_RESPECT".to_string(),
+            data: Some(EventData {
+                event: Some(pb::polaris::v1::event_data::Event::Updrespect(
+                    UpdateRespectEvent {
+                        updates: chunk.to_vec(),
+                        election_round: update.election_round,
+                        part: part as u32,
+                        parts,
+                    },
+                )),
+            }),
+            ..Default::default()
+        })
+        .collect()
+}
+
+#[inline]
+fn extract_pause_event(
+    tx_hash: &str,
+    block_num: u64,
+    timestamp: u64,
+    action_trace: &substreams_antelope::pb::ActionTrace,
+) -> Option<Event> {
+    use abi::polaris_music::actions::Pause;
+
+    decode_action::<Pause>(action_trace)?;
+
+    Some(Event {
+        tx_hash: tx_hash.to_string(),
+        block_num,
+        timestamp,
+        event_type: "CONTRACT_PAUSED".to_string(),
+        data: Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Paused(
+                ContractPausedEvent {
+                    admin: admin_account(action_trace),
+                    timestamp,
+                },
+            )),
+        }),
+        ..Default::default()
+    })
+}
+
+#[inline]
+fn extract_unpause_event(
+    tx_hash: &str,
+    block_num: u64,
+    timestamp: u64,
+    action_trace: &substreams_antelope::pb::ActionTrace,
+) -> Option<Event> {
+    use abi::polaris_music::actions::Unpause;
+
+    decode_action::<Unpause>(action_trace)?;
+
+    Some(Event {
+        tx_hash: tx_hash.to_string(),
+        block_num,
+        timestamp,
+        event_type: "CONTRACT_UNPAUSED".to_string(),
+        data: Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Unpaused(
+                ContractUnpausedEvent {
+                    admin: admin_account(action_trace),
+                    timestamp,
+                },
+            )),
+        }),
+        ..Default::default()
+    })
+}
+
+#[inline]
+fn extract_set_oracle_event(
+    tx_hash: &str,
+    block_num: u64,
+    timestamp: u64,
+    action_trace: &substreams_antelope::pb::ActionTrace,
+) -> Option<Event> {
+    use abi::polaris_music::actions::Setoracle;
+
+    let set_oracle = decode_action::<Setoracle>(action_trace)?;
+
     Some(Event {
         tx_hash: tx_hash.to_string(),
         block_num,
         timestamp,
-        event_type: "UPDATE_RESPECT".to_string(),
+        event_type: "ORACLE_SET".to_string(),
         data: Some(EventData {
-            event: Some(pb::polaris::v1::event_data::Event::Updrespect(
-                UpdateRespectEvent {
-                    updates,
-                    election_round: update.election_round,
+            event: Some(pb::polaris::v1::event_data::Event::OracleSet(
+                OracleSetEvent {
+                    oracle: set_oracle.oracle,
+                    admin: admin_account(action_trace),
                 },
             )),
         }),
+        ..Default::default()
+    })
+}
+
+/// Account that authorized an admin action (first authorization actor)
+fn admin_account(action_trace: &substreams_antelope::pb::ActionTrace) -> String {
+    action_trace
+        .action
+        .as_ref()
+        .and_then(|a| a.authorization.first())
+        .map(|p| p.actor.clone())
+        .unwrap_or_default()
+}
+
+/// Token `transfer` payload (eosio.token compatible)
+#[derive(serde::Deserialize)]
+struct TokenTransfer {
+    from: String,
+    to: String,
+    quantity: String,
+    #[serde(default)]
+    memo: String,
+}
+
+/// `transfer` payload of `action`, from `json_data` or else `raw_data`
+fn decode_transfer(action: &substreams_antelope::pb::Action) -> Option<TokenTransfer> {
+    if action.json_data.is_empty() {
+        serde_json::from_value(abi::binary::decode_token_transfer(&action.raw_data)?).ok()
+    } else {
+        serde_json::from_str(&action.json_data).ok()
+    }
+}
+
+/// Raw amount the contract paid out under `finalize`: the quantities of
+/// transfers from `contract_account` created (at any depth) by it, summed
+///
+/// Each transfer counts once, where it executed on its token contract, not
+/// again for its notifications. Only transfers in the symbol and precision of
+/// the first one are summed; malformed or negative quantities are skipped.
+fn finalize_reward_amount(
+    trx: &substreams_antelope::pb::TransactionTrace,
+    finalize: &substreams_antelope::pb::ActionTrace,
+    contract_account: &str,
+) -> u64 {
+    let mut unit: Option<(String, u8)> = None;
+    let mut total: u64 = 0;
+    for trace in &trx.action_traces {
+        let Some(action) = trace.action.as_ref() else {
+            continue;
+        };
+        if action.name != "transfer" || action.account != trace.receiver {
+            continue;
+        }
+        let Some(transfer) = decode_transfer(action).filter(|t| t.from == contract_account) else {
+            continue;
+        };
+        if find_ancestor(trx, trace, |t| t.action_ordinal == finalize.action_ordinal).is_none() {
+            continue;
+        }
+        let Some(asset) = asset::parse(&transfer.quantity).filter(|a| a.amount >= 0) else {
+            continue;
+        };
+        let unit = unit.get_or_insert_with(|| (asset.symbol.clone(), asset.precision));
+        if (asset.symbol.as_str(), asset.precision) == (unit.0.as_str(), unit.1) {
+            total = total.saturating_add(asset.amount as u64);
+        }
+    }
+    total
+}
+
+/// Actions whose outgoing transfers are reward payouts (unstake refunds are not)
+const REWARD_SOURCE_ACTIONS: [&str; 3] = ["finalize", "claimreward", "claimall"];
+
+/// Upper bound on ancestor hops when resolving an inline action's origin
+const MAX_INLINE_DEPTH: usize = 16;
+
+#[inline]
+fn extract_reward_event(
+    trx: &substreams_antelope::pb::TransactionTrace,
+    block_num: u64,
+    timestamp: u64,
+    action_trace: &substreams_antelope::pb::ActionTrace,
+    contract_account: &str,
+) -> Option<Event> {
+    let transfer = decode_transfer(action_trace.action.as_ref()?)?;
+    if transfer.from != contract_account {
+        return None;
+    }
+
+    // Walk creator ordinals up to the contract action that caused this payout
+    let source = find_ancestor(trx, action_trace, |trace| {
+        trace.receiver == contract_account
+            && trace.action.as_ref().is_some_and(|a| {
+                a.account == contract_account && REWARD_SOURCE_ACTIONS.contains(&a.name.as_str())
+            })
+    })?;
+    let source_action = source.action.as_ref()?;
+
+    let submission_hash = if source_action.name == "finalize" {
+        decode_action::<abi::polaris_music::actions::Finalize>(source)
+            .map(|f| f.tx_hash)
+            .unwrap_or_default()
+    } else {
+        String::new()
+    };
+
+    Some(Event {
+        tx_hash: trx.id.clone(),
+        block_num,
+        timestamp,
+        event_type: "REWARD".to_string(),
+        data: Some(EventData {
+            event: Some(pb::polaris::v1::event_data::Event::Reward(RewardEvent {
+                to: transfer.to,
+                quantity: transfer.quantity,
+                memo: transfer.memo,
+                source_action: source_action.name.clone(),
+                submission_hash,
+                action_ordinal: action_trace.action_ordinal,
+                source_action_ordinal: source.action_ordinal,
+            })),
+        }),
+        ..Default::default()
     })
 }
+
+/// Inline actions and notifications created under `trace`, all levels, in
+/// action ordinal order (`include_inline=true`)
+fn inline_actions(
+    trx: &substreams_antelope::pb::TransactionTrace,
+    trace: &substreams_antelope::pb::ActionTrace,
+) -> Vec<InlineActionRef> {
+    let mut traces: Vec<&substreams_antelope::pb::ActionTrace> = trx.action_traces.iter().collect();
+    // Creators always have a lower ordinal than the actions they create
+    traces.sort_by_key(|t| t.action_ordinal);
+
+    let mut depths: HashMap<u32, u32> = HashMap::from([(trace.action_ordinal, 0)]);
+    let mut children = Vec::new();
+    for child in traces {
+        // A notification (receiver other than the action's contract) keeps the
+        // creator of the action it notifies about; that action is its closest
+        // unnotified ancestor instead
+        let notified = child
+            .action
+            .as_ref()
+            .is_some_and(|a| a.account != child.receiver);
+        let parent = if notified {
+            child.closest_unnotified_ancestor_action_ordinal
+        } else {
+            child.creator_action_ordinal
+        };
+        let Some(&depth) = depths.get(&parent) else {
+            continue;
+        };
+        if child.action_ordinal == trace.action_ordinal || depth as usize >= MAX_INLINE_DEPTH {
+            continue;
+        }
+        depths.insert(child.action_ordinal, depth + 1);
+        let action = child.action.as_ref();
+        children.push(InlineActionRef {
+            name: action.map(|a| a.name.clone()).unwrap_or_default(),
+            account: action.map(|a| a.account.clone()).unwrap_or_default(),
+            receiver: child.receiver.clone(),
+            depth: depth + 1,
+        });
+    }
+    children
+}
+
+/// Nearest ancestor of `trace` (following `creator_action_ordinal`) matching `pred`
+fn find_ancestor<'a>(
+    trx: &'a substreams_antelope::pb::TransactionTrace,
+    trace: &substreams_antelope::pb::ActionTrace,
+    pred: impl Fn(&substreams_antelope::pb::ActionTrace) -> bool,
+) -> Option<&'a substreams_antelope::pb::ActionTrace> {
+    let mut ordinal = trace.creator_action_ordinal;
+    for _ in 0..MAX_INLINE_DEPTH {
+        if ordinal == 0 {
+            return None;
+        }
+        let parent = trx
+            .action_traces
+            .iter()
+            .find(|t| t.action_ordinal == ordinal)?;
+        if pred(parent) {
+            return Some(parent);
+        }
+        ordinal = parent.creator_action_ordinal;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substreams_antelope::pb::{
+        Action, ActionTrace, DbOp, TransactionReceiptHeader, TransactionStatus, TransactionTrace,
+    };
+
+    fn updrespect_trace(accounts: usize) -> ActionTrace {
+        let respect_data: Vec<serde_json::Value> = (0..accounts)
+            .map(|i| serde_json::json!({ "key": format!("acct{}", i), "value": i }))
+            .collect();
+        ActionTrace {
+            receiver: "polaris".to_string(),
+            action: Some(Action {
+                account: "polaris".to_string(),
+                name: "updrespect".to_string(),
+                json_data: serde_json::json!({
+                    "respect_data": respect_data,
+                    "election_round": 7,
+                })
+                .to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    fn respect_parts(events: &[Event]) -> Vec<&UpdateRespectEvent> {
+        events
+            .iter()
+            .map(
+                |event| match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+                    Some(pb::polaris::v1::event_data::Event::Updrespect(update)) => update,
+                    other => panic!("expected UPDATE_RESPECT, got {:?}", other),
+                },
+            )
+            .collect()
+    }
+
+    #[test]
+    fn oversized_respect_update_is_split() {
+        let events = extract_update_respect_events("tx", 1, 0, &updrespect_trace(1_234));
+        let parts = respect_parts(&events);
+
+        assert_eq!(parts.len(), 3);
+        assert!(parts.iter().all(|p| p.updates.len() <= MAX_RESPECT_UPDATES));
+        assert!(parts.iter().all(|p| p.election_round == 7 && p.parts == 3));
+        assert_eq!(
+            parts.iter().map(|p| p.part).collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+
+        // Every account appears once, in the original order
+        let accounts: Vec<&str> = parts
+            .iter()
+            .flat_map(|p| p.updates.iter().map(|u| u.account.as_str()))
+            .collect();
+        let expected: Vec<String> = (0..1_234).map(|i| format!("acct{}", i)).collect();
+        assert_eq!(accounts, expected);
+    }
+
+    #[test]
+    fn negative_respect_is_rejected_not_wrapped() {
+        let mut trace = updrespect_trace(2);
+        let action = trace.action.as_mut().unwrap();
+        let mut data: serde_json::Value = serde_json::from_str(&action.json_data).unwrap();
+        data["respect_data"][1]["value"] = serde_json::json!(-5);
+        action.json_data = data.to_string();
+
+        assert!(extract_update_respect_events("tx", 1, 0, &trace).is_empty());
+    }
+
+    #[test]
+    fn respect_update_within_cap_is_single_event() {
+        let events =
+            extract_update_respect_events("tx", 1, 0, &updrespect_trace(MAX_RESPECT_UPDATES));
+        let parts = respect_parts(&events);
+
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].updates.len(), MAX_RESPECT_UPDATES);
+        assert_eq!((parts[0].part, parts[0].parts), (0, 1));
+    }
+
+    fn put_trace(receiver: &str, account: &str) -> ActionTrace {
+        ActionTrace {
+            receiver: receiver.to_string(),
+            action: Some(Action {
+                account: account.to_string(),
+                name: "put".to_string(),
+                json_data: serde_json::json!({
+                    "author": "alice",
+                    "type": 1,
+                    "hash": "ab".repeat(32),
+                    "event_cid": "cid",
+                    "parent": null,
+                    "ts": 0,
+                    "tags": [],
+                })
+                .to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn put_and_finalize_in_one_transaction_link_with_zero_latency() {
+        let finalize = ActionTrace {
+            receiver: "polaris".to_string(),
+            action: Some(Action {
+                account: "polaris".to_string(),
+                name: "finalize".to_string(),
+                json_data: serde_json::json!({ "tx_hash": "ab".repeat(32) }).to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let block = Block {
+            number: 42,
+            unfiltered_transaction_traces: vec![TransactionTrace {
+                id: "trx".to_string(),
+                receipt: Some(TransactionReceiptHeader {
+                    status: TransactionStatus::TransactionstatusExecuted as i32,
+                    ..Default::default()
+                }),
+                action_traces: vec![put_trace("polaris", "polaris"), finalize],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let events = extract_events(&block, "polaris", true, false, false, false);
+        let types: Vec<&str> = events.iter().map(|e| e.event_type.as_str()).collect();
+        assert_eq!(types, ["PUT", "FINALIZE"]);
+
+        // Nothing stored yet: the put is only known from this transaction
+        let latencies = finalize_latencies(&events, |_| None);
+        assert_eq!(
+            latencies,
+            vec![FinalizeLatency {
+                tx_hash: "ab".repeat(32),
+                put_block: 42,
+                finalize_block: 42,
+                seconds: 0,
+            }]
+        );
+
+        let mut stats = HashMap::new();
+        for event in &events {
+            add_event_stats(
+                event,
+                |_| None,
+                |key, value| *stats.entry(key.to_string()).or_insert(0) += value,
+            );
+        }
+        assert_eq!(stats.get("total_puts"), Some(&1));
+        assert_eq!(stats.get(&keys::stats_type("FINALIZE")), Some(&1));
+    }
+
+    #[test]
+    fn put_expiry_comes_from_the_anchors_row() {
+        let anchors_op = |new_data_json: String, new_data: Vec<u8>| DbOp {
+            code: "polaris".to_string(),
+            scope: "polaris".to_string(),
+            table_name: ANCHORS_TABLE.to_string(),
+            new_data_json,
+            new_data,
+            ..Default::default()
+        };
+        // `anchor` as packed by EOSLIB_SERIALIZE, root node without tags
+        let mut binary = Vec::new();
+        binary.extend(7u64.to_le_bytes()); // id
+        binary.extend(0u64.to_le_bytes()); // author
+        binary.push(1); // type
+        binary.extend([0xab; 32]); // hash
+        binary.extend([3, b'c', b'i', b'd']); // event_cid
+        binary.push(0); // parent
+        binary.extend(0u32.to_le_bytes()); // ts
+        binary.push(0); // tags
+        binary.extend(1_700_000_300u32.to_le_bytes()); // expires_at
+        binary.push(0); // finalized
+        binary.extend(0u64.to_le_bytes()); // escrowed_amount
+        binary.extend(1u64.to_le_bytes()); // submission_x
+        let json = serde_json::json!({ "hash": "ab".repeat(32), "expires_at": 1_700_000_300 });
+
+        for op in [
+            anchors_op(json.to_string(), Vec::new()),
+            anchors_op(String::new(), binary),
+        ] {
+            let block = Block {
+                unfiltered_transaction_traces: vec![TransactionTrace {
+                    id: "trx".to_string(),
+                    receipt: Some(TransactionReceiptHeader {
+                        status: TransactionStatus::TransactionstatusExecuted as i32,
+                        ..Default::default()
+                    }),
+                    action_traces: vec![put_trace("polaris", "polaris")],
+                    db_ops: vec![op],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            };
+            let events = extract_events(&block, "polaris", true, false, false, false);
+            let Some(pb::polaris::v1::event_data::Event::Put(put)) =
+                events[0].data.as_ref().and_then(|d| d.event.as_ref())
+            else {
+                panic!("expected PUT, got {:?}", events[0]);
+            };
+            assert_eq!(put.expires_at, 1_700_000_300);
+
+            // As stored by store_registry_nodes and filtered by map_registry_state
+            let node = RegistryNode {
+                expires_at: put.expires_at,
+                ..Default::default()
+            };
+            assert!(node_live(&node, 1_700_000_299));
+            assert!(!node_live(&node, 1_700_000_300));
+        }
+
+        // Without the row the expiry is unknown and the node stays live
+        let unknown = RegistryNode::default();
+        assert!(node_live(&unknown, u64::MAX));
+    }
+
+    #[test]
+    fn finalize_results_follow_contract_weighted_votes_across_blocks() {
+        use pb::polaris::v1::event_data::Event as Data;
+
+        let event = |data| Event {
+            data: Some(EventData { event: Some(data) }),
+            ..Default::default()
+        };
+        let vote = |voter: &str, val| {
+            event(Data::Vote(VoteEvent {
+                voter: voter.to_string(),
+                tx_hash: "sub".to_string(),
+                val,
+                ..Default::default()
+            }))
+        };
+        let finalize = || {
+            event(Data::Finalize(FinalizeEvent {
+                tx_hash: "sub".to_string(),
+                ..Default::default()
+            }))
+        };
+        // Respect as in store_respect; bob and erin have none, max_vote_weight 10
+        let respect = HashMap::from([("alice", 30), ("carol", 3), ("dave", 5)]);
+        let weight_of =
+            |_, voter: &str| contract_vote_weight(respect.get(voter).copied(), Some(10));
+
+        // Both stores as they stand after the previous blocks
+        let mut contributions: HashMap<String, i64> = HashMap::new();
+        let mut weights: HashMap<String, i64> = HashMap::new();
+        let mut run_block = |events: Vec<Event>| {
+            let mut events = Events { events };
+            // store_vote_contributions: (before, after) of each vote's key
+            let mut at_vote = HashMap::new();
+            for (index, key, value) in vote_contributions(&events, weight_of) {
+                let before = contributions.insert(key, value).unwrap_or(0);
+                at_vote.insert(index, (before, value));
+            }
+            for (_, key, delta) in
+                tally_changes(&events, keys::vote_weight, |index, _| at_vote[&index])
+            {
+                *weights.entry(key).or_default() += delta;
+            }
+            // Nothing follows the finalize in these blocks, so the final tally
+            // is the one just before it
+            resolve_finalizes(
+                &mut events.events,
+                |_, k| weights.get(k).copied().unwrap_or(0),
+                5000,
+            );
+            events
+                .events
+                .iter()
+                .find_map(|e| match e.data.as_ref()?.event.as_ref()? {
+                    Data::Finalize(f) => Some((f.approval_percent, f.accepted)),
+                    _ => None,
+                })
+        };
+
+        // Block 1: alice (capped at 10) and erin (default 1) for, bob against
+        assert_eq!(
+            run_block(vec![vote("alice", 1), vote("bob", -1), vote("erin", 1)]),
+            None
+        );
+        // Block 2: bob switches to for, erin clears her vote, carol for and
+        // dave against, then the finalize: 14 for, 5 against
+        let result = run_block(vec![
+            vote("bob", 1),
+            vote("erin", 0),
+            vote("carol", 1),
+            vote("dave", -1),
+            finalize(),
+        ]);
+        assert_eq!(result, Some((74, true)));
+        assert_eq!(weights["voteweight:sub:for"], 14);
+        assert_eq!(weights["voteweight:sub:against"], 5);
+
+        // Rounded percent, exact ratio against the threshold in basis points;
+        // the same tally fails the contract's default 90%
+        assert_eq!(finalize_result(0, 0, 5000), (0, false));
+        assert_eq!(finalize_result(2, 1, 6700), (67, false));
+        assert_eq!(finalize_result(14, 5, 9000), (74, false));
+        assert_eq!(finalize_result(9, 1, 9000), (90, true));
+    }
+
+    #[test]
+    fn threshold_crossings_ignore_re_votes_and_follow_cleared_votes() {
+        use pb::polaris::v1::event_data::Event as Data;
+
+        let vote = |voter: &str, val| Event {
+            block_num: 42,
+            data: Some(EventData {
+                event: Some(Data::Vote(VoteEvent {
+                    voter: voter.to_string(),
+                    tx_hash: "sub".to_string(),
+                    val,
+                    ..Default::default()
+                })),
+            }),
+            ..Default::default()
+        };
+        // alice and bob vote against, alice switches to for and repeats it
+        // twice, then bob clears theirs
+        let events = Events {
+            events: vec![
+                vote("alice", -1),
+                vote("bob", -1),
+                vote("alice", 1),
+                vote("alice", 1),
+                vote("alice", 1),
+                vote("bob", 0),
+            ],
+        };
+
+        // store_vote_contributions, then store_vote_tallies snapshotted after
+        // each event index; weights do not matter to the counts
+        let mut contributions: HashMap<String, i64> = HashMap::new();
+        let mut at_vote = HashMap::new();
+        for (index, key, value) in vote_contributions(&events, |_, _| 7) {
+            let before = contributions.insert(key, value).unwrap_or(0);
+            at_vote.insert(index, (before.signum(), value.signum()));
+        }
+        let mut tally: HashMap<String, i64> = HashMap::new();
+        let mut snapshots = Vec::new();
+        let changes = tally_changes(&events, keys::vote_tally, |index, _| at_vote[&index]);
+        for index in 0..events.events.len() {
+            for (_, key, delta) in changes.iter().filter(|(i, _, _)| *i == index) {
+                *tally.entry(key.clone()).or_default() += delta;
+            }
+            snapshots.push(tally.clone());
+        }
+        let value = |index: usize, key: &str| snapshots[index].get(key).copied().unwrap_or(0);
+        let around = |index: usize, key: &str| {
+            let before = index.checked_sub(1).map_or(0, |i| value(i, key));
+            (before, value(index, key))
+        };
+
+        // Counting every vote would reach 3 for / 2 against (60%) on alice's
+        // third vote; the current votes stay at 1 / 1 until bob clears theirs
+        let crossings = threshold_crossings(&events, around, 60.0);
+        assert_eq!(
+            crossings,
+            vec![ThresholdCrossedEvent {
+                tx_hash: "sub".to_string(),
+                crossed_at_block: 42,
+                approval_percent: 100.0,
+                votes_for: 1,
+                votes_against: 0,
+            }]
+        );
+        assert_eq!(tally["tally:sub:for"], 1);
+        assert_eq!(tally["tally:sub:against"], 0);
+    }
+
+    #[test]
+    fn controversy_rewards_even_splits_of_large_audiences() {
+        assert_eq!(controversy(50, 0), 0.0);
+        assert_eq!(controversy(0, 0), 0.0);
+        assert_eq!(controversy(5, 5), 10.0);
+        assert_eq!(controversy(50, 50), 100.0);
+        assert_eq!(controversy(50, 10), 12.0);
+        assert_eq!(controversy(10, 50), controversy(50, 10));
+    }
+
+    #[test]
+    fn block_gaps_are_told_apart_from_forks_and_rewinds() {
+        let meta = |number, id: &str, parent_id: &str| BlockMeta {
+            number,
+            id: id.to_string(),
+            parent_id: parent_id.to_string(),
+            ..Default::default()
+        };
+        let previous = meta(10, "a10", "a9");
+        let kind = |current| block_gap(&previous, &current).map(|gap| (gap.kind, gap.expected));
+
+        assert_eq!(kind(meta(11, "a11", "a10")), None);
+        assert_eq!(kind(meta(11, "a11", "")), None);
+        assert_eq!(kind(meta(11, "b11", "b10")), Some(("fork".to_string(), 11)));
+        assert_eq!(kind(meta(13, "a13", "a12")), Some(("gap".to_string(), 11)));
+        assert_eq!(
+            kind(meta(10, "b10", "a9")),
+            Some(("rewind".to_string(), 11))
+        );
+    }
+
+    #[test]
+    fn time_drift_reports_the_action_furthest_from_the_header() {
+        let at = |seconds: i64, nanos: i32| Some(prost_types::Timestamp { seconds, nanos });
+        let action = |ordinal: u32, block_time| ActionTrace {
+            transaction_id: "trx".to_string(),
+            action_ordinal: ordinal,
+            block_time,
+            ..Default::default()
+        };
+        let block = |action_traces| Block {
+            number: 7,
+            header: Some(substreams_antelope::pb::BlockHeader {
+                timestamp: at(1_000, 500_000_000),
+                ..Default::default()
+            }),
+            unfiltered_transaction_traces: vec![TransactionTrace {
+                receipt: Some(TransactionReceiptHeader {
+                    status: TransactionStatus::TransactionstatusExecuted as i32,
+                    ..Default::default()
+                }),
+                action_traces,
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let drifting = block(vec![
+            action(1, at(1_000, 500_000_000)),
+            action(2, at(998, 0)),
+            action(3, at(1_001, 0)),
+            action(4, None),
+        ]);
+        let drift = time_drift(&drifting).unwrap();
+        assert_eq!((drift.drift_ms, drift.action_ordinal), (-2_500, 2));
+        assert_eq!(drift.block_num, 7);
+
+        let aligned = time_drift(&block(vec![action(1, at(1_000, 500_000_000))])).unwrap();
+        assert_eq!(aligned.drift_ms, 0);
+    }
+
+    #[test]
+    fn block_numbers_at_the_top_of_the_u32_range_are_not_truncated() {
+        let block = Block {
+            number: u32::MAX,
+            unfiltered_transaction_traces: vec![TransactionTrace {
+                id: "trx".to_string(),
+                receipt: Some(TransactionReceiptHeader {
+                    status: TransactionStatus::TransactionstatusExecuted as i32,
+                    ..Default::default()
+                }),
+                action_traces: vec![put_trace("polaris", "polaris")],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let events = extract_events(&block, "polaris", true, false, false, false);
+        assert_eq!(events[0].block_num, 4_294_967_295);
+        assert_eq!(block_meta(&block).number, 4_294_967_295);
+
+        let stored = block_value(events[0].block_num);
+        assert_eq!(stored, 4_294_967_295);
+        assert_eq!(value_block(stored), events[0].block_num);
+        assert_eq!(
+            keys::parse_pending_block(&keys::pending_block(events[0].block_num)),
+            Some(4_294_967_295)
+        );
+        assert_eq!(block_value(u64::MAX), i64::MAX);
+        assert_eq!(value_block(-1), 0);
+    }
+
+    #[test]
+    fn notification_duplicate_counts_once() {
+        let trx = TransactionTrace {
+            id: "trx".to_string(),
+            receipt: Some(TransactionReceiptHeader {
+                status: TransactionStatus::TransactionstatusExecuted as i32,
+                ..Default::default()
+            }),
+            action_traces: vec![
+                put_trace("polaris", "polaris"),
+                // Another contract's put notifying polaris via require_recipient
+                put_trace("polaris", "proxy"),
+                // polaris notifying a listener of its own put
+                put_trace("listener", "polaris"),
+            ],
+            ..Default::default()
+        };
+        let block = Block {
+            unfiltered_transaction_traces: vec![trx],
+            ..Default::default()
+        };
+
+        let events = extract_events(&block, "polaris", true, false, false, false);
+        assert_eq!(events.len(), 1);
+
+        let mut total_puts = 0;
+        for event in &events {
+            add_event_stats(
+                event,
+                |_| None,
+                |key, value| {
+                    if key == "total_puts" {
+                        total_puts += value;
+                    }
+                },
+            );
+        }
+        assert_eq!(total_puts, 1);
+    }
+
+    #[test]
+    fn compact_events_drop_default_fields() {
+        let block = Block {
+            number: 100_000_000,
+            unfiltered_transaction_traces: vec![TransactionTrace {
+                id: "trx".to_string(),
+                receipt: Some(TransactionReceiptHeader {
+                    status: TransactionStatus::TransactionstatusExecuted as i32,
+                    ..Default::default()
+                }),
+                action_traces: vec![put_trace("polaris", "polaris")],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let event = &extract_events(&block, "polaris", true, false, false, false)[0];
+
+        let full = compact::event_json(event, true);
+        let compact = compact::event_json(event, false);
+        assert_eq!(full["data"]["put"]["expires_at"], 0);
+        assert!(compact["data"]["put"].get("expires_at").is_none());
+        assert!(compact.get("network").is_none());
+        assert_eq!(compact["data"]["put"]["author"], "alice");
+        assert!(compact.to_string().len() < full.to_string().len());
+    }
+
+    #[test]
+    fn content_type_names_fall_back_to_unknown() {
+        assert_eq!(
+            abi::content_types::content_type_name(21),
+            "CREATE_RELEASE_BUNDLE"
+        );
+        assert_eq!(abi::content_types::content_type_name(0), "UNKNOWN");
+
+        // put_trace uses type 1, which the contract never assigns
+        let block = Block {
+            unfiltered_transaction_traces: vec![TransactionTrace {
+                id: "trx".to_string(),
+                receipt: Some(TransactionReceiptHeader {
+                    status: TransactionStatus::TransactionstatusExecuted as i32,
+                    ..Default::default()
+                }),
+                action_traces: vec![put_trace("polaris", "polaris")],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let event = &extract_events(&block, "polaris", true, false, false, false)[0];
+        match event.data.as_ref().and_then(|d| d.event.as_ref()) {
+            Some(pb::polaris::v1::event_data::Event::Put(put)) => {
+                assert_eq!(put.type_name, "UNKNOWN")
+            }
+            other => panic!("expected put, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deferred_actions_are_flagged_and_optional() {
+        let stake = ActionTrace {
+            receiver: "polaris".to_string(),
+            action: Some(Action {
+                account: "polaris".to_string(),
+                name: "stake".to_string(),
+                json_data: serde_json::json!({
+                    "account": "bot",
+                    "node_id": "ab".repeat(32),
+                    "quantity": "1.0000 MUS",
+                })
+                .to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        // Scheduled run of a deferred transaction, plus its earlier scheduling
+        // trace (delayed, not executed)
+        let trx = |id: &str, scheduled, status: TransactionStatus| TransactionTrace {
+            id: id.to_string(),
+            scheduled,
+            receipt: Some(TransactionReceiptHeader {
+                status: status as i32,
+                ..Default::default()
+            }),
+            action_traces: vec![stake.clone()],
+            ..Default::default()
+        };
+        let block = Block {
+            unfiltered_transaction_traces: vec![
+                trx(
+                    "deferred",
+                    true,
+                    TransactionStatus::TransactionstatusExecuted,
+                ),
+                trx(
+                    "scheduling",
+                    false,
+                    TransactionStatus::TransactionstatusDelayed,
+                ),
+                trx(
+                    "direct",
+                    false,
+                    TransactionStatus::TransactionstatusExecuted,
+                ),
+            ],
+            ..Default::default()
+        };
+
+        let events = extract_events(&block, "polaris", true, false, false, false);
+        let flags: Vec<(&str, bool)> = events
+            .iter()
+            .map(|e| (e.tx_hash.as_str(), e.deferred))
+            .collect();
+        assert_eq!(flags, vec![("deferred", true), ("direct", false)]);
+
+        let events = extract_events(&block, "polaris", false, false, false, false);
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].tx_hash, "direct");
+    }
+
+    #[test]
+    fn fingerprints_are_stable_and_distinguish_repeats() {
+        let like = |account: &str| Event {
+            tx_hash: "tx".to_string(),
+            block_num: 7,
+            event_type: "LIKE".to_string(),
+            data: Some(EventData {
+                event: Some(pb::polaris::v1::event_data::Event::Like(LikeEvent {
+                    account: account.to_string(),
+                    node_id: "ab".repeat(32),
+                    ..Default::default()
+                })),
+            }),
+            ..Default::default()
+        };
+        let mut events = vec![like("alice"), like("alice"), like("bob")];
+        set_fingerprints(&mut events);
+
+        let prints: Vec<&[u8]> = events.iter().map(|e| e.fingerprint.as_slice()).collect();
+        assert!(prints.iter().all(|p| p.len() == FINGERPRINT_LEN));
+        assert_ne!(prints[0], prints[1]);
+        assert_ne!(prints[0], prints[2]);
+
+        // Same input, same fingerprints; rfc3339 rendering does not count
+        let mut again = vec![like("alice"), like("alice"), like("bob")];
+        again[0].timestamp_rfc3339 = "1970-01-01T00:00:00Z".to_string();
+        set_fingerprints(&mut again);
+        let again: Vec<&[u8]> = again.iter().map(|e| e.fingerprint.as_slice()).collect();
+        assert_eq!(prints, again);
+    }
+
+    #[test]
+    fn event_uuids_follow_the_documented_scheme() {
+        // UUIDv5 of "events.polaris.music" in the DNS namespace
+        let dns = [
+            0x6b, 0xa7, 0xb8, 0x10, 0x9d, 0xad, 0x11, 0xd1, 0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4,
+            0x30, 0xc8,
+        ];
+        assert_eq!(
+            uuid_v5(&dns, "events.polaris.music"),
+            "38c15361-273d-54b7-8072-8241cc57a0c4"
+        );
+
+        let event = Event {
+            tx_hash: "abc123".to_string(),
+            event_type: "PUT".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            event_uuid(&event, 7, None),
+            "3447ef1d-6474-5f49-b4ac-9e92a6ebfc92"
+        );
+        assert_ne!(event_uuid(&event, 7, Some(0)), event_uuid(&event, 7, None));
+    }
+
+    #[test]
+    fn order_keys_sort_like_the_chain_position() {
+        let positions = [
+            (9, 7, 1, 0),
+            (10, 0, 2, 0),
+            (10, 0, 10, 0),
+            (10, 0, 10, 1),
+            (10, 3, 1, 0),
+            (10, 12, 1, 0),
+            (u32::MAX, u64::MAX, u32::MAX, 0),
+        ];
+        let keys: Vec<String> = positions
+            .iter()
+            .map(|&(block, trx, ordinal, part)| order_key(block, trx, ordinal, part))
+            .collect();
+
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]), "{:?}", keys);
+        assert!(keys.iter().all(|key| key.len() == keys[0].len()));
+        assert_eq!(
+            keys[1],
+            "0000000010-00000000000000000000-0000000002-0000000000"
+        );
+    }
+
+    #[test]
+    fn sampling_is_deterministic_and_per_type() {
+        let rates = sample_rates("like:10").unwrap();
+        assert_eq!(rates.get("LIKE"), Some(&10));
+        assert!(sample_rates("like:0").is_err());
+        assert!(sample_rates("like").is_err());
+
+        let kept = (0..1000)
+            .filter(|i| sample_keep(&format!("tx{}", i), 10))
+            .count();
+        assert!((50..150).contains(&kept), "kept {} of 1000", kept);
+        let again = (0..1000)
+            .filter(|i| sample_keep(&format!("tx{}", i), 10))
+            .count();
+        assert_eq!(kept, again);
+        assert!((0..100).all(|i| sample_keep(&format!("tx{}", i), 1)));
+    }
+
+    #[test]
+    fn stake_reconciliation_reports_missing_events() {
+        let stake_trace = ActionTrace {
+            receiver: "polaris".to_string(),
+            action: Some(Action {
+                account: "polaris".to_string(),
+                name: "stake".to_string(),
+                json_data: serde_json::json!({
+                    "account": "alice",
+                    "node_id": "ab".repeat(32),
+                    "quantity": "10.0000 MUS",
+                })
+                .to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let row = |amount: &str| serde_json::json!({ "id": 0, "amount": amount }).to_string();
+        // Raw row: id, node_id, then 25.0000 MUS (4-digit precision)
+        let mut raw_row = vec![0u8; 40];
+        raw_row.extend_from_slice(&250_000i64.to_le_bytes());
+        raw_row.extend_from_slice(&[4, b'M', b'U', b'S', 0, 0, 0, 0]);
+        let block = Block {
+            unfiltered_transaction_traces: vec![TransactionTrace {
+                id: "trx".to_string(),
+                receipt: Some(TransactionReceiptHeader {
+                    status: TransactionStatus::TransactionstatusExecuted as i32,
+                    ..Default::default()
+                }),
+                action_traces: vec![stake_trace],
+                db_ops: vec![
+                    // 5.0000 -> 15.0000 MUS, matching the stake action
+                    DbOp {
+                        code: "polaris".to_string(),
+                        table_name: "stakes".to_string(),
+                        old_data_json: row("5.0000 MUS"),
+                        new_data_json: row("15.0000 MUS"),
+                        ..Default::default()
+                    },
+                    // New binary row with no matching action
+                    DbOp {
+                        code: "polaris".to_string(),
+                        table_name: "stakes".to_string(),
+                        new_data: raw_row,
+                        ..Default::default()
+                    },
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let events = extract_events(&block, "polaris", true, false, false, false);
+        let diverging = stake_reconciliation(&block, "polaris", &events);
+        assert_eq!(diverging.len(), 1);
+        assert_eq!(diverging[0].symbol, "MUS");
+        assert_eq!(diverging[0].action_total, "10.0000 MUS");
+        assert_eq!(diverging[0].table_total, "35.0000 MUS");
+        assert_eq!(diverging[0].delta, "-25.0000 MUS");
+
+        let mut matching = block.clone();
+        matching.unfiltered_transaction_traces[0].db_ops.pop();
+        assert!(stake_reconciliation(&matching, "polaris", &events).is_empty());
+    }
+
+    #[test]
+    fn contract_config_decodes_json_and_binary_rows() {
+        // Antelope name encoding: 5 bits per character from the top
+        let name = |s: &str| {
+            s.bytes().enumerate().fold(0u64, |value, (i, c)| {
+                let code = match c {
+                    b'.' => 0,
+                    b'1'..=b'5' => c - b'1' + 1,
+                    _ => c - b'a' + 6,
+                };
+                value | (u64::from(code) << (64 - 5 * (i + 1)))
+            })
+        };
+        let mut raw = Vec::new();
+        raw.extend_from_slice(&7u64.to_le_bytes()); // x
+        raw.extend_from_slice(&0.5f64.to_le_bytes()); // carry
+        raw.extend_from_slice(&3u64.to_le_bytes()); // round
+        raw.extend_from_slice(&name("oracle").to_le_bytes());
+        raw.extend_from_slice(&name("eosio.token").to_le_bytes());
+        raw.extend_from_slice(&[4, b'M', b'U', b'S', 0, 0, 0, 0]);
+        raw.extend_from_slice(&0u64.to_le_bytes()); // council_account
+        raw.extend_from_slice(&9000u64.to_le_bytes());
+        raw.extend_from_slice(&100u32.to_le_bytes());
+        raw.extend_from_slice(&50u32.to_le_bytes());
+        raw.push(0); // paused
+        for window in [604800u32, 259200, 172800, 259200, 432000, 86400] {
+            raw.extend_from_slice(&window.to_le_bytes());
+        }
+        for value in [100000000u64, 100000, 5000, 1000000, 1000, 20000] {
+            raw.extend_from_slice(&value.to_le_bytes());
+        }
+        for pct in [5000u64, 5000, 0, 5000, 5000] {
+            raw.extend_from_slice(&pct.to_le_bytes());
+        }
+
+        let block = |op: DbOp| Block {
+            number: 42,
+            unfiltered_transaction_traces: vec![TransactionTrace {
+                id: "trx".to_string(),
+                receipt: Some(TransactionReceiptHeader {
+                    status: TransactionStatus::TransactionstatusExecuted as i32,
+                    ..Default::default()
+                }),
+                db_ops: vec![op],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let globals = DbOp {
+            code: "polaris".to_string(),
+            scope: "polaris".to_string(),
+            table_name: "globals".to_string(),
+            ..Default::default()
+        };
+
+        let binary = contract_config(
+            &block(DbOp {
+                new_data: raw,
+                ..globals.clone()
+            }),
+            "polaris",
+        )
+        .expect("binary row decodes");
+        assert_eq!(binary.round, 3);
+        assert_eq!(binary.fractally_oracle, "oracle");
+        assert_eq!(binary.token_contract, "eosio.token");
+        assert_eq!(binary.token_symbol, "4,MUS");
+        assert_eq!(binary.council_account, "");
+        assert_eq!(binary.vote_window_merge, 432000);
+        assert_eq!(binary.rejected_stakers_pct, 5000);
+        assert_eq!((binary.block_num, binary.tx_hash.as_str()), (42, "trx"));
+
+        // A row of another layout leaves nothing to decode
+        assert!(abi::binary::decode_globals_row(&[0; 8]).is_none());
+
+        let json = serde_json::json!({
+            "x": "7", "carry": 0.5, "round": "3", "fractally_oracle": "oracle",
+            "token_contract": "eosio.token", "token_symbol": "4,MUS", "council_account": "",
+            "approval_threshold_bp": "9000", "max_vote_weight": 100,
+            "attestor_respect_threshold": 50, "paused": 0,
+            "vote_window_release": 604800, "vote_window_mint": 259200,
+            "vote_window_resolve": 172800, "vote_window_claim": 259200,
+            "vote_window_merge": 432000, "vote_window_default": 86400,
+            "multiplier_release": "100000000", "multiplier_mint": "100000",
+            "multiplier_resolve": "5000", "multiplier_add_claim": "1000000",
+            "multiplier_edit_claim": "1000", "multiplier_merge": "20000",
+            "approved_author_pct": "5000", "approved_voters_pct": "5000",
+            "approved_stakers_pct": "0", "rejected_voters_pct": "5000",
+            "rejected_stakers_pct": "5000",
+        });
+        let from_json = contract_config(
+            &block(DbOp {
+                new_data_json: json.to_string(),
+                ..globals.clone()
+            }),
+            "polaris",
+        );
+        assert_eq!(from_json.as_ref(), Some(&binary));
+
+        // Another scope is not the singleton
+        let elsewhere = DbOp {
+            scope: "alice".to_string(),
+            new_data_json: json.to_string(),
+            ..globals
+        };
+        assert!(contract_config(&block(elsewhere), "polaris").is_none());
+    }
+
+    #[test]
+    fn table_deltas_follow_configured_tables() {
+        let op = |code: &str, scope: &str, table: &str| DbOp {
+            operation: 1,
+            code: code.to_string(),
+            scope: scope.to_string(),
+            table_name: table.to_string(),
+            ..Default::default()
+        };
+        let block = Block {
+            unfiltered_transaction_traces: vec![TransactionTrace {
+                id: "trx".to_string(),
+                receipt: Some(TransactionReceiptHeader {
+                    status: TransactionStatus::TransactionstatusExecuted as i32,
+                    ..Default::default()
+                }),
+                db_ops: vec![
+                    op("polaris", "alice", "stakes"),
+                    op("polaris", "polaris", "anchors"),
+                    op("polaris", "alice", "anchors"),
+                    op("polaris", "alice", "pendingrwd"),
+                    op("eosio.token", "alice", "stakes"),
+                ],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let tables = |params: &str| {
+            let filters = TableFilter::from_params(&Params::parse(params)).unwrap();
+            table_deltas(&block, "polaris", &filters)
+                .into_iter()
+                .map(|d| format!("{}:{}", d.scope, d.table_name))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(tables("polaris"), ["alice:stakes", "polaris:anchors"]);
+        assert_eq!(tables("tables=pendingrwd"), ["alice:pendingrwd"]);
+        assert_eq!(
+            tables("tables=alice:anchors, stakes"),
+            ["alice:stakes", "alice:anchors"]
+        );
+        assert!(table_deltas(&block, "polaris", &[]).is_empty());
+        assert!(TableFilter::from_params(&Params::parse("tables=:stakes")).is_err());
+
+        let delta = &table_deltas(
+            &block,
+            "polaris",
+            &TableFilter::from_params(&Params::parse("")).unwrap(),
+        )[0];
+        assert_eq!(delta.operation, "INSERT");
+    }
+
+    #[test]
+    fn grouped_gini_bounds() {
+        assert_eq!(grouped_gini(&[]), 0.0);
+        // Everyone in one bucket looks perfectly equal
+        assert_eq!(grouped_gini(&[(10, 1_000)]), 0.0);
+        // 9 accounts with 1 unit, 1 account with 91: exact Gini is 0.81
+        let gini = grouped_gini(&[(9, 9), (1, 91)]);
+        assert!((gini - 0.81).abs() < 1e-9, "{}", gini);
+
+        assert_eq!(stake_bucket(0), 0);
+        assert_eq!(stake_bucket(1), 1);
+        assert_eq!(stake_bucket(3), 2);
+        assert_eq!(stake_bucket(i64::MAX), 63);
+    }
+
+    #[test]
+    fn inline_actions_cover_children_and_notifications() {
+        let trace =
+            |ordinal, creator, closest, account: &str, name: &str, receiver: &str| ActionTrace {
+                receiver: receiver.to_string(),
+                action_ordinal: ordinal,
+                creator_action_ordinal: creator,
+                closest_unnotified_ancestor_action_ordinal: closest,
+                action: Some(Action {
+                    account: account.to_string(),
+                    name: name.to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+        let trx = TransactionTrace {
+            action_traces: vec![
+                trace(1, 0, 0, "polaris", "finalize", "polaris"),
+                trace(2, 1, 1, "eosio.token", "transfer", "eosio.token"),
+                trace(3, 1, 2, "eosio.token", "transfer", "polaris"),
+                trace(4, 1, 2, "eosio.token", "transfer", "alice"),
+                trace(5, 0, 0, "polaris", "vote", "polaris"),
+            ],
+            ..Default::default()
+        };
+
+        let inline = inline_actions(&trx, &trx.action_traces[0]);
+        let summary: Vec<(&str, u32)> = inline
+            .iter()
+            .map(|a| (a.receiver.as_str(), a.depth))
+            .collect();
+        assert_eq!(summary, [("eosio.token", 1), ("polaris", 2), ("alice", 2)]);
+        assert!(inline_actions(&trx, &trx.action_traces[4]).is_empty());
+    }
+
+    #[test]
+    fn finalize_reward_sums_its_payouts_once_each() {
+        let transfer = |ordinal, creator, from: &str, quantity: &str, receiver: &str| ActionTrace {
+            receiver: receiver.to_string(),
+            action_ordinal: ordinal,
+            creator_action_ordinal: creator,
+            action: Some(Action {
+                account: "eosio.token".to_string(),
+                name: "transfer".to_string(),
+                json_data: serde_json::json!({
+                    "from": from,
+                    "to": "alice",
+                    "quantity": quantity,
+                    "memo": "",
+                })
+                .to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let finalize = |ordinal| ActionTrace {
+            receiver: "polaris".to_string(),
+            action_ordinal: ordinal,
+            action: Some(Action {
+                account: "polaris".to_string(),
+                name: "finalize".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let trx = TransactionTrace {
+            action_traces: vec![
+                finalize(1),
+                transfer(2, 1, "polaris", "12.5000 MUS", "eosio.token"),
+                // Notifications of the same transfer
+                transfer(3, 1, "polaris", "12.5000 MUS", "polaris"),
+                transfer(4, 1, "polaris", "12.5000 MUS", "alice"),
+                transfer(5, 1, "polaris", "0.2500 MUS", "eosio.token"),
+                transfer(6, 1, "bob", "100.0000 MUS", "eosio.token"),
+                finalize(7),
+                transfer(8, 7, "polaris", "1.0000 MUS", "eosio.token"),
+                finalize(9),
+            ],
+            ..Default::default()
+        };
+
+        let reward =
+            |index: usize| finalize_reward_amount(&trx, &trx.action_traces[index], "polaris");
+        assert_eq!(reward(0), 127_500);
+        assert_eq!(reward(6), 10_000);
+        assert_eq!(reward(8), 0);
+    }
+
+    #[test]
+    fn unknown_actions_are_reported() {
+        let trace = |name: &str| ActionTrace {
+            receiver: "polaris".to_string(),
+            action: Some(Action {
+                account: "polaris".to_string(),
+                name: name.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        let trx = |id: &str, action_traces| TransactionTrace {
+            id: id.to_string(),
+            receipt: Some(TransactionReceiptHeader {
+                status: TransactionStatus::TransactionstatusExecuted as i32,
+                ..Default::default()
+            }),
+            action_traces,
+            ..Default::default()
+        };
+        let block = Block {
+            number: 42,
+            unfiltered_transaction_traces: vec![
+                trx(
+                    "trx1",
+                    vec![
+                        put_trace("polaris", "polaris"),
+                        trace("init"),
+                        trace("setcouncil"),
+                    ],
+                ),
+                trx("trx2", vec![trace("setcouncil"), trace("rmcouncil")]),
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            unknown_actions(&block, "polaris"),
+            vec![
+                ("trx1", "setcouncil"),
+                ("trx2", "setcouncil"),
+                ("trx2", "rmcouncil")
+            ]
+        );
+
+        // Every unknown action is logged and the block's events still flow
+        let mut lines = Vec::new();
+        let events = block_events("polaris&strict_actions=true", &block, |line| {
+            lines.push(line)
+        })
+        .expect("strict_actions does not fail the block");
+        assert_eq!(events.events.len(), 1);
+        let errors: Vec<&str> = lines
+            .iter()
+            .map(String::as_str)
+            .filter(|line| line.starts_with("ERROR: "))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                "ERROR: unhandled action polaris::setcouncil in trx trx1 (block 42)",
+                "ERROR: unhandled action polaris::setcouncil in trx trx2 (block 42)",
+                "ERROR: unhandled action polaris::rmcouncil in trx trx2 (block 42)",
+            ]
+        );
+
+        // Off by default
+        let mut lines = Vec::new();
+        block_events("polaris", &block, |line| lines.push(line)).unwrap();
+        assert!(lines.iter().all(|line| !line.starts_with("ERROR: ")));
+    }
+
+    #[test]
+    fn key_counts_track_every_unbounded_store() {
+        // Bounded by design, see `TRACKED_STORES`
+        const UNTRACKED: [&str; 9] = [
+            "store_lib",
+            "store_block_time",
+            "store_contract_config",
+            "store_stake_symbol_list",
+            "store_account_bloom",
+            "store_account_bloom_bits",
+            "store_rate_checkpoints",
+            "store_pending_events",
+            "store_key_counts",
+        ];
+
+        let manifest = include_str!("../substreams.yaml");
+        let modules: Vec<&str> = manifest.split("\n  - name: ").skip(1).collect();
+        let stores: Vec<&str> = modules
+            .iter()
+            .filter(|module| module.contains("\n    kind: store\n"))
+            .filter_map(|module| module.lines().next())
+            .filter(|name| !UNTRACKED.contains(name))
+            .collect();
+        let mut tracked = TRACKED_STORES.to_vec();
+        tracked.sort_unstable();
+        let mut expected = stores.clone();
+        expected.sort_unstable();
+        assert_eq!(tracked, expected);
+
+        // The manifest feeds store_key_counts in TRACKED_STORES order
+        let key_counts = modules
+            .iter()
+            .find(|module| module.starts_with("store_key_counts\n"))
+            .expect("store_key_counts in the manifest");
+        let inputs: Vec<&str> = key_counts
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("- store: "))
+            .collect();
+        assert_eq!(inputs, TRACKED_STORES);
+    }
+
+    #[test]
+    fn node_scores_rank_highest_first_with_ties_by_node_id() {
+        let weights = ScoreWeights {
+            like: 1.0,
+            stake: 0.5,
+            attest: 2.0,
+        };
+        let score = |node_id: &str, likes, staked, attests| NodeScore {
+            node_id: node_id.to_string(),
+            score: weights.score(likes, staked, attests),
+            ..Default::default()
+        };
+        let scores = vec![
+            score("c", 1, 0.0, 1),
+            score("b", 3, 0.0, 0),
+            score("a", 0, 6.0, 0),
+            score("d", 10, 0.0, 0),
+        ];
+
+        let ranked: Vec<(String, f64)> = top_scores(scores, 3)
+            .into_iter()
+            .map(|s| (s.node_id, s.score))
+            .collect();
+        assert_eq!(
+            ranked,
+            vec![
+                ("d".to_string(), 10.0),
+                ("a".to_string(), 3.0),
+                ("b".to_string(), 3.0)
+            ]
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Module params parsing
+//!
+//! Params are a `&`-separated list of `key=value` pairs, e.g.
+//! `account=alice&limit=20`. A bare segment without `=` names the contract
+//! account, so the historical plain `polaris` param keeps working.
+
+use std::str::FromStr;
+
+use substreams::errors::Error;
+
+/// Contract account used when params do not name one
+const DEFAULT_CONTRACT: &str = "polaris";
+
+#[derive(Debug, Default)]
+pub struct Params {
+    values: Vec<(String, String)>,
+    contract: Option<String>,
+}
+
+impl Params {
+    pub fn parse(raw: &str) -> Self {
+        let mut params = Params::default();
+        for segment in raw.split('&') {
+            match segment.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => params
+                    .values
+                    .push((key.trim().to_string(), value.trim().to_string())),
+                Some(_) => {}
+                None if !segment.trim().is_empty() => {
+                    params.contract = Some(segment.trim().to_string())
+                }
+                None => {}
+            }
+        }
+
+        params
+    }
+
+    /// Contract account: `contract=<name>`, a bare segment, or `polaris`
+    pub fn contract(&self) -> &str {
+        self.get("contract")
+            .or(self.contract.as_deref())
+            .unwrap_or(DEFAULT_CONTRACT)
+    }
+
+    /// Raw value for `key` (last occurrence wins)
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Value for `key` parsed as `T`, or `default` when absent
+    pub fn parse_or<T: FromStr>(&self, key: &str, default: T) -> Result<T, Error> {
+        match self.get(key) {
+            Some(value) => value
+                .parse::<T>()
+                .map_err(|_| anyhow::anyhow!("invalid value for param `{}`: {:?}", key, value)),
+            None => Ok(default),
+        }
+    }
+}
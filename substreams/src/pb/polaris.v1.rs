@@ -13,6 +13,21 @@ pub mod v1 {
         pub events: ::prost::alloc::vec::Vec<Event>,
     }
 
+    /// Events of a block under a per-block cap (map_capped_events)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct CappedEvents {
+        /// First events of the block, in block order
+        #[prost(message, repeated, tag = "1")]
+        pub events: ::prost::alloc::vec::Vec<Event>,
+        /// The block had more events than the cap
+        #[prost(bool, tag = "2")]
+        pub truncated: bool,
+        /// Events left out
+        #[prost(uint64, tag = "3")]
+        pub dropped: u64,
+    }
+
     /// Individual event from the blockchain
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Message)]
@@ -32,13 +47,162 @@ pub mod v1 {
         /// Event-specific data
         #[prost(message, optional, tag = "5")]
         pub data: ::core::option::Option<EventData>,
+        /// Block timestamp as UTC RFC3339 (only with timestamp_rfc3339=true)
+        #[prost(string, tag = "6")]
+        pub timestamp_rfc3339: ::prost::alloc::string::String,
+        /// Chain tag from the `network` param (e.g. "jungle4"), empty by default
+        #[prost(string, tag = "7")]
+        pub network: ::prost::alloc::string::String,
+        /// Action ran in a deferred (scheduled) transaction
+        #[prost(bool, tag = "8")]
+        pub deferred: bool,
+        /// 16-byte content hash for sink dedup, with `fingerprint=true` (see README)
+        #[prost(bytes = "vec", tag = "9")]
+        pub fingerprint: ::prost::alloc::vec::Vec<u8>,
+        /// Global event sequence number, set by map_sequenced_events only
+        #[prost(uint64, tag = "10")]
+        pub seq: u64,
+        /// Actions created by this one, with `include_inline=true`
+        #[prost(message, repeated, tag = "11")]
+        pub inline_actions: ::prost::alloc::vec::Vec<InlineActionRef>,
+        /// Deterministic UUIDv5, with `uuid=true` (see README)
+        #[prost(string, tag = "12")]
+        pub uuid: ::prost::alloc::string::String,
+        /// Sortable chain position, with `order_key=true` (see README)
+        #[prost(string, tag = "13")]
+        pub order_key: ::prost::alloc::string::String,
+    }
+
+    /// Inline action or notification created (directly or not) by an event's action
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct InlineActionRef {
+        /// Action name, e.g. "transfer"
+        #[prost(string, tag = "1")]
+        pub name: ::prost::alloc::string::String,
+        /// Contract of the action, e.g. "eosio.token"
+        #[prost(string, tag = "2")]
+        pub account: ::prost::alloc::string::String,
+        /// Account that ran it (differs from account for notifications)
+        #[prost(string, tag = "3")]
+        pub receiver: ::prost::alloc::string::String,
+        /// 1 for direct children, 2 for theirs, ...
+        #[prost(uint32, tag = "4")]
+        pub depth: u32,
+    }
+
+    /// Events with default/empty fields omitted, for JSON sinks (map_events_compact)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct CompactEvents {
+        /// Event fields by proto name, populated only
+        #[prost(message, repeated, tag = "1")]
+        pub events: ::prost::alloc::vec::Vec<::prost_types::Struct>,
+    }
+
+    /// One of the package's views, picked by map_output's view param
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OutputView {
+        #[prost(oneof = "output_view::View", tags = "1, 2, 3")]
+        pub view: ::core::option::Option<output_view::View>,
+    }
+
+    /// Nested message and enum types in `OutputView`.
+    pub mod output_view {
+        #[allow(clippy::derive_partial_eq_without_eq)]
+        #[derive(Clone, PartialEq, ::prost::Oneof)]
+        pub enum View {
+            #[prost(message, tag = "1")]
+            Events(super::Events),
+            #[prost(message, tag = "2")]
+            Stats(super::Stats),
+            #[prost(message, tag = "3")]
+            Json(super::CompactEvents),
+        }
+    }
+
+    /// Events encoded one per frame, for incremental decoding (map_event_frames)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct EventFrames {
+        /// Each an encoded Event, in block order
+        #[prost(bytes = "vec", repeated, tag = "1")]
+        pub frames: ::prost::alloc::vec::Vec<::prost::alloc::vec::Vec<u8>>,
+    }
+
+    /// Block header summary, shaped like the common Antelope/Substreams BlockMeta
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BlockMeta {
+        #[prost(uint64, tag = "1")]
+        pub number: u64,
+        /// Block hash
+        #[prost(string, tag = "2")]
+        pub id: ::prost::alloc::string::String,
+        /// Previous block hash
+        #[prost(string, tag = "3")]
+        pub parent_id: ::prost::alloc::string::String,
+        #[prost(message, optional, tag = "4")]
+        pub timestamp: ::core::option::Option<::prost_types::Timestamp>,
+        #[prost(string, tag = "5")]
+        pub producer: ::prost::alloc::string::String,
+    }
+
+    /// A block's events with its BlockMeta (map_block_events)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BlockEvents {
+        #[prost(message, optional, tag = "1")]
+        pub meta: ::core::option::Option<BlockMeta>,
+        #[prost(message, repeated, tag = "2")]
+        pub events: ::prost::alloc::vec::Vec<Event>,
+    }
+
+    /// Largest gap between an action trace's time and its block header (map_time_drift)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TimeDrift {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        /// Action time minus header time, milliseconds
+        #[prost(int64, tag = "2")]
+        pub drift_ms: i64,
+        /// Transaction of the most drifting action
+        #[prost(string, tag = "3")]
+        pub transaction_id: ::prost::alloc::string::String,
+        #[prost(uint32, tag = "4")]
+        pub action_ordinal: u32,
+    }
+
+    /// Block that does not directly follow the previously processed one
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BlockGapEvent {
+        /// Previous block number + 1
+        #[prost(uint64, tag = "1")]
+        pub expected: u64,
+        #[prost(uint64, tag = "2")]
+        pub got: u64,
+        /// "gap", "rewind" or "fork"
+        #[prost(string, tag = "3")]
+        pub kind: ::prost::alloc::string::String,
+        /// Hash of the previously processed block
+        #[prost(string, tag = "4")]
+        pub previous_id: ::prost::alloc::string::String,
+        /// Parent hash in this block's header
+        #[prost(string, tag = "5")]
+        pub parent_id: ::prost::alloc::string::String,
     }
 
     /// Event data union
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Message)]
     pub struct EventData {
-        #[prost(oneof = "event_data::Event", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9")]
+        #[prost(
+            oneof = "event_data::Event",
+            tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13"
+        )]
         pub event: ::core::option::Option<event_data::Event>,
     }
 
@@ -65,6 +229,14 @@ pub mod v1 {
             Unlike(super::UnlikeEvent),
             #[prost(message, tag = "9")]
             Updrespect(super::UpdateRespectEvent),
+            #[prost(message, tag = "10")]
+            Reward(super::RewardEvent),
+            #[prost(message, tag = "11")]
+            Paused(super::ContractPausedEvent),
+            #[prost(message, tag = "12")]
+            Unpaused(super::ContractUnpausedEvent),
+            #[prost(message, tag = "13")]
+            OracleSet(super::OracleSetEvent),
         }
     }
 
@@ -79,15 +251,22 @@ pub mod v1 {
         /// SHA256 hash as hex string
         #[prost(string, tag = "3")]
         pub hash: ::prost::alloc::string::String,
-        /// Optional parent hash
+        /// Parent hash, empty for root posts (null/zero parent)
         #[prost(string, tag = "4")]
         pub parent: ::prost::alloc::string::String,
         #[prost(uint64, tag = "5")]
         pub ts: u64,
         #[prost(string, repeated, tag = "6")]
         pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        /// Voting window close (Unix seconds) from the anchors row, 0 if not seen
         #[prost(uint64, tag = "7")]
         pub expires_at: u64,
+        /// True when the put has no parent
+        #[prost(bool, tag = "8")]
+        pub is_root: bool,
+        /// Name of `type` from abi/content_types.json, UNKNOWN if unmapped
+        #[prost(string, tag = "9")]
+        pub type_name: ::prost::alloc::string::String,
     }
 
     /// Attest action data
@@ -100,6 +279,12 @@ pub mod v1 {
         pub tx_hash: ::prost::alloc::string::String,
         #[prost(uint32, tag = "3")]
         pub confirmed_type: u32,
+        /// Put type of the node (map_content_type_events only, 0 if unknown)
+        #[prost(uint32, tag = "4")]
+        pub content_type: u32,
+        /// Name of `confirmed_type`, UNKNOWN if unmapped
+        #[prost(string, tag = "5")]
+        pub confirmed_type_name: ::prost::alloc::string::String,
     }
 
     /// Vote action data
@@ -116,6 +301,9 @@ pub mod v1 {
         /// Respect weight
         #[prost(uint32, tag = "4")]
         pub weight: u32,
+        /// Voter's respect at vote time, set by map_vote_respect
+        #[prost(uint32, tag = "5")]
+        pub voter_respect: u32,
     }
 
     /// Finalize action data
@@ -129,8 +317,12 @@ pub mod v1 {
         /// 0-100
         #[prost(uint64, tag = "3")]
         pub approval_percent: u64,
+        /// Raw sum of the finalize's payout transfers (12.5000 MUS -> 125000)
         #[prost(uint64, tag = "4")]
         pub reward_amount: u64,
+        /// Finalize action ordinal within its transaction
+        #[prost(uint32, tag = "5")]
+        pub action_ordinal: u32,
     }
 
     /// Stake action data
@@ -144,6 +336,9 @@ pub mod v1 {
         /// Asset string (e.g., "100.0000 MUS")
         #[prost(string, tag = "3")]
         pub quantity: ::prost::alloc::string::String,
+        /// Put type of the node (map_content_type_events only, 0 if unknown)
+        #[prost(uint32, tag = "4")]
+        pub content_type: u32,
     }
 
     /// Unstake action data
@@ -156,6 +351,12 @@ pub mod v1 {
         pub node_id: ::prost::alloc::string::String,
         #[prost(string, tag = "3")]
         pub quantity: ::prost::alloc::string::String,
+        /// Tx of the stake reversed (map_reversal_events only, empty if unknown)
+        #[prost(string, tag = "4")]
+        pub reverses_tx_hash: ::prost::alloc::string::String,
+        /// Put type of the node (map_content_type_events only, 0 if unknown)
+        #[prost(uint32, tag = "5")]
+        pub content_type: u32,
     }
 
     /// Like action data
@@ -169,6 +370,12 @@ pub mod v1 {
         /// Path of node IDs
         #[prost(string, repeated, tag = "3")]
         pub path: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        /// Author of node_id (map_like_events only, empty if unknown)
+        #[prost(string, tag = "4")]
+        pub content_author: ::prost::alloc::string::String,
+        /// Put type of the node (map_content_type_events only, 0 if unknown)
+        #[prost(uint32, tag = "5")]
+        pub content_type: u32,
     }
 
     /// Unlike action data
@@ -179,6 +386,15 @@ pub mod v1 {
         pub account: ::prost::alloc::string::String,
         #[prost(string, tag = "2")]
         pub node_id: ::prost::alloc::string::String,
+        /// Author of node_id (map_like_events only, empty if unknown)
+        #[prost(string, tag = "3")]
+        pub content_author: ::prost::alloc::string::String,
+        /// Tx of the like reversed (map_reversal_events only, empty if unknown)
+        #[prost(string, tag = "4")]
+        pub reverses_tx_hash: ::prost::alloc::string::String,
+        /// Put type of the node (map_content_type_events only, 0 if unknown)
+        #[prost(uint32, tag = "5")]
+        pub content_type: u32,
     }
 
     /// Update Respect action data
@@ -189,6 +405,12 @@ pub mod v1 {
         pub updates: ::prost::alloc::vec::Vec<RespectUpdate>,
         #[prost(uint64, tag = "2")]
         pub election_round: u64,
+        /// Chunk index when the payload was split, from 0
+        #[prost(uint32, tag = "3")]
+        pub part: u32,
+        /// Number of chunks the payload was split into
+        #[prost(uint32, tag = "4")]
+        pub parts: u32,
     }
 
     #[allow(clippy::derive_partial_eq_without_eq)]
@@ -200,6 +422,81 @@ pub mod v1 {
         pub respect: u32,
     }
 
+    /// Contract paused by its admin (put/vote/stake/finalize halted)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ContractPausedEvent {
+        /// Authorizing account
+        #[prost(string, tag = "1")]
+        pub admin: ::prost::alloc::string::String,
+        /// Block timestamp (Unix seconds)
+        #[prost(uint64, tag = "2")]
+        pub timestamp: u64,
+    }
+
+    /// Contract resumed by its admin
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ContractUnpausedEvent {
+        /// Authorizing account
+        #[prost(string, tag = "1")]
+        pub admin: ::prost::alloc::string::String,
+        /// Block timestamp (Unix seconds)
+        #[prost(uint64, tag = "2")]
+        pub timestamp: u64,
+    }
+
+    /// Oracle account replaced by the contract admin (setoracle)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OracleSetEvent {
+        /// New oracle account
+        #[prost(string, tag = "1")]
+        pub oracle: ::prost::alloc::string::String,
+        /// Authorizing account
+        #[prost(string, tag = "2")]
+        pub admin: ::prost::alloc::string::String,
+    }
+
+    /// Latest respect score of an account
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RespectScore {
+        #[prost(uint32, tag = "1")]
+        pub respect: u32,
+        /// Round that set the score
+        #[prost(uint64, tag = "2")]
+        pub election_round: u64,
+        #[prost(uint64, tag = "3")]
+        pub block_num: u64,
+    }
+
+    /// Token transfer paid out by the contract (inline from finalize/claim actions)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RewardEvent {
+        /// Recipient account
+        #[prost(string, tag = "1")]
+        pub to: ::prost::alloc::string::String,
+        /// Asset string, e.g. "12.5000 MUS"
+        #[prost(string, tag = "2")]
+        pub quantity: ::prost::alloc::string::String,
+        #[prost(string, tag = "3")]
+        pub memo: ::prost::alloc::string::String,
+        /// Contract action that issued the transfer
+        #[prost(string, tag = "4")]
+        pub source_action: ::prost::alloc::string::String,
+        /// Finalized submission (finalize payouts only)
+        #[prost(string, tag = "5")]
+        pub submission_hash: ::prost::alloc::string::String,
+        /// Ordinal of the transfer notification
+        #[prost(uint32, tag = "6")]
+        pub action_ordinal: u32,
+        /// Ordinal of the source action
+        #[prost(uint32, tag = "7")]
+        pub source_action_ordinal: u32,
+    }
+
     /// Statistics aggregated from events
     #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Message)]
@@ -216,8 +513,171 @@ pub mod v1 {
         pub total_likes: u64,
         #[prost(uint64, tag = "6")]
         pub unique_contributors: u64,
+        /// MUS entry of staked_amounts (kept for compatibility)
         #[prost(string, tag = "7")]
         pub total_staked_amount: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "8")]
+        pub finalizes_accepted: u64,
+        #[prost(uint64, tag = "9")]
+        pub finalizes_rejected: u64,
+        /// accepted / (accepted + rejected), 0 when none
+        #[prost(double, tag = "10")]
+        pub acceptance_rate: f64,
+        /// Net staked per token symbol
+        #[prost(message, repeated, tag = "11")]
+        pub staked_amounts: ::prost::alloc::vec::Vec<TokenAmount>,
+        /// Mean put -> finalize time, 0 when none
+        #[prost(double, tag = "12")]
+        pub avg_finalize_latency_seconds: f64,
+        /// Finalizes behind the mean (put seen)
+        #[prost(uint64, tag = "13")]
+        pub finalize_latency_samples: u64,
+    }
+
+    /// Stats accumulated over blocks start_block..=end_block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct WindowedStats {
+        #[prost(uint64, tag = "1")]
+        pub requested_start_block: u64,
+        /// Snapshot boundary + 1, at or before requested_start_block
+        #[prost(uint64, tag = "2")]
+        pub start_block: u64,
+        /// Current block
+        #[prost(uint64, tag = "3")]
+        pub end_block: u64,
+        /// Counters and staked amounts within the window
+        #[prost(message, optional, tag = "4")]
+        pub stats: ::core::option::Option<Stats>,
+    }
+
+    /// Amount of one token symbol
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TokenAmount {
+        /// Symbol code, e.g. "MUS"
+        #[prost(string, tag = "1")]
+        pub symbol: ::prost::alloc::string::String,
+        /// Asset string, e.g. "100.0000 MUS"
+        #[prost(string, tag = "2")]
+        pub amount: ::prost::alloc::string::String,
+    }
+
+    /// Finalize outcomes for a single UTC day
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct DailyAcceptance {
+        /// UTC date (YYYY-MM-DD)
+        #[prost(string, tag = "1")]
+        pub date: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub accepted: u64,
+        #[prost(uint64, tag = "3")]
+        pub rejected: u64,
+        #[prost(double, tag = "4")]
+        pub acceptance_rate: f64,
+    }
+
+    /// Vote valence of a UTC day (map_sentiment)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct DailySentiment {
+        /// UTC date (YYYY-MM-DD)
+        #[prost(string, tag = "1")]
+        pub date: ::prost::alloc::string::String,
+        /// Up votes, or the stake behind them in whole tokens
+        #[prost(double, tag = "2")]
+        pub positive: f64,
+        /// Down votes, or the stake behind them
+        #[prost(double, tag = "3")]
+        pub negative: f64,
+        /// positive - negative; strongly negative days are contentious
+        #[prost(double, tag = "4")]
+        pub net: f64,
+    }
+
+    /// Net vote sentiment per day, newest first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Sentiment {
+        /// "raw" or "stake"
+        #[prost(string, tag = "1")]
+        pub weighting: ::prost::alloc::string::String,
+        #[prost(message, repeated, tag = "2")]
+        pub days: ::prost::alloc::vec::Vec<DailySentiment>,
+    }
+
+    /// Daily over monthly active accounts of a UTC day (map_stickiness)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct DailyStickiness {
+        /// UTC date (YYYY-MM-DD)
+        #[prost(string, tag = "1")]
+        pub date: ::prost::alloc::string::String,
+        /// Distinct accounts active on the day
+        #[prost(uint64, tag = "2")]
+        pub dau: u64,
+        /// Distinct accounts active in the 30 days ending on it
+        #[prost(uint64, tag = "3")]
+        pub mau: u64,
+        /// dau / mau, 0 when mau is 0
+        #[prost(double, tag = "4")]
+        pub ratio: f64,
+    }
+
+    /// DAU/MAU stickiness per day, newest first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Stickiness {
+        #[prost(message, repeated, tag = "1")]
+        pub days: ::prost::alloc::vec::Vec<DailyStickiness>,
+    }
+
+    /// Monthly retention matrix of first-activity cohorts
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RetentionCohorts {
+        /// Current UTC month (YYYY-MM)
+        #[prost(string, tag = "1")]
+        pub month: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// Oldest cohort first, empty cohorts left out
+        #[prost(message, repeated, tag = "3")]
+        pub cohorts: ::prost::alloc::vec::Vec<RetentionCohort>,
+    }
+
+    /// Accounts first active in one month and their activity since
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RetentionCohort {
+        /// Cohort month (YYYY-MM)
+        #[prost(string, tag = "1")]
+        pub month: ::prost::alloc::string::String,
+        /// Accounts whose first event was in this month
+        #[prost(uint64, tag = "2")]
+        pub size: u64,
+        /// Of those, active in month + i, up to the current month
+        #[prost(uint64, repeated, tag = "3")]
+        pub active: ::prost::alloc::vec::Vec<u64>,
+        /// active\[i\] / size
+        #[prost(double, repeated, tag = "4")]
+        pub retention: ::prost::alloc::vec::Vec<f64>,
+    }
+
+    /// One UTC day of activity as a CSV row (map_daily_csv)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct DailyCsvRow {
+        /// YYYY-MM-DD
+        #[prost(string, tag = "1")]
+        pub date: ::prost::alloc::string::String,
+        /// Column names, the same on every row
+        #[prost(string, tag = "2")]
+        pub header: ::prost::alloc::string::String,
+        /// e.g. "2024-05-01,120,3,10,5,90,2,40,120.0000"
+        #[prost(string, tag = "3")]
+        pub row: ::prost::alloc::string::String,
     }
 
     /// Account activity tracking
@@ -245,6 +705,1543 @@ pub mod v1 {
         pub activities: ::prost::alloc::vec::Vec<AccountActivity>,
     }
 
+    /// Node put by an account (one entry of the per-author node index)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AuthorNode {
+        /// Node hash
+        #[prost(string, tag = "1")]
+        pub hash: ::prost::alloc::string::String,
+        /// Content type code
+        #[prost(uint32, tag = "2")]
+        pub type_: u32,
+        /// Submission timestamp from the put action
+        #[prost(uint64, tag = "3")]
+        pub ts: u64,
+        /// Block the put landed in
+        #[prost(uint64, tag = "4")]
+        pub block_num: u64,
+        /// Transaction of the put
+        #[prost(string, tag = "5")]
+        pub tx_hash: ::prost::alloc::string::String,
+    }
+
+    /// Page of nodes authored by an account, newest first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AuthorNodes {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        /// Total nodes authored (all pages)
+        #[prost(uint64, tag = "2")]
+        pub total: u64,
+        /// Offset of this page from the newest node
+        #[prost(uint64, tag = "3")]
+        pub offset: u64,
+        #[prost(message, repeated, tag = "4")]
+        pub nodes: ::prost::alloc::vec::Vec<AuthorNode>,
+    }
+
+    /// Event touching a node or account (one entry of a per-node or per-account history)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NodeEventRef {
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        /// PUT, LIKE, STAKE, ATTEST, ...
+        #[prost(string, tag = "2")]
+        pub event_type: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "3")]
+        pub block_num: u64,
+    }
+
+    /// Page of a node's history, oldest first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NodeHistory {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Total events for the node (all pages)
+        #[prost(uint64, tag = "2")]
+        pub total: u64,
+        /// Offset of this page from the oldest event
+        #[prost(uint64, tag = "3")]
+        pub offset: u64,
+        #[prost(message, repeated, tag = "4")]
+        pub events: ::prost::alloc::vec::Vec<NodeEventRef>,
+    }
+
+    /// Everything recorded about one account (data export)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AccountExport {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// Events involving the account (all pages)
+        #[prost(uint64, tag = "3")]
+        pub total_events: u64,
+        /// Offset of this page from the oldest event
+        #[prost(uint64, tag = "4")]
+        pub offset: u64,
+        #[prost(message, repeated, tag = "5")]
+        pub events: ::prost::alloc::vec::Vec<NodeEventRef>,
+        /// Nodes put by the account
+        #[prost(uint64, tag = "6")]
+        pub authored_nodes: u64,
+        /// Unset if the account never received respect
+        #[prost(message, optional, tag = "7")]
+        pub respect: ::core::option::Option<RespectScore>,
+        /// Every position ever opened, including closed ones
+        #[prost(message, repeated, tag = "8")]
+        pub positions: ::prost::alloc::vec::Vec<AccountStakePosition>,
+    }
+
+    /// Probabilistic "has this account ever appeared" check
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AccountKnown {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        /// False: never seen. True: seen, or a false positive
+        #[prost(bool, tag = "2")]
+        pub known: bool,
+        /// Bits set in the filter
+        #[prost(uint32, tag = "3")]
+        pub set_bits: u32,
+        /// Estimated chance that `known` is wrong when true
+        #[prost(double, tag = "4")]
+        pub false_positive_rate: f64,
+    }
+
+    /// Net stake of an account on one node
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AccountStakePosition {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Current net stake, "0.0000 MUS" once closed
+        #[prost(string, tag = "2")]
+        pub quantity: ::prost::alloc::string::String,
+    }
+
+    /// Metadata of a node from its put (one entry of the registry)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RegistryNode {
+        /// Node hash
+        #[prost(string, tag = "1")]
+        pub hash: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub author: ::prost::alloc::string::String,
+        /// Content type code
+        #[prost(uint32, tag = "3")]
+        pub type_: u32,
+        /// Parent hash, empty for root nodes
+        #[prost(string, tag = "4")]
+        pub parent: ::prost::alloc::string::String,
+        #[prost(string, repeated, tag = "5")]
+        pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        /// Submission timestamp from the put action
+        #[prost(uint64, tag = "6")]
+        pub ts: u64,
+        /// Unix seconds the voting window closes, 0 when unknown
+        #[prost(uint64, tag = "7")]
+        pub expires_at: u64,
+        /// Block the put landed in
+        #[prost(uint64, tag = "8")]
+        pub block_num: u64,
+        /// Transaction of the put
+        #[prost(string, tag = "9")]
+        pub tx_hash: ::prost::alloc::string::String,
+    }
+
+    /// Put of a submission (store_submission_puts)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct SubmissionPut {
+        /// Transaction of the put (not the put hash)
+        #[prost(string, tag = "1")]
+        pub transaction_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// Block time, Unix seconds
+        #[prost(uint64, tag = "3")]
+        pub timestamp: u64,
+    }
+
+    /// Time from put to finalize of a submission (map_finalize_latencies)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FinalizeLatency {
+        /// Submission's put hash, as in FinalizeEvent
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub put_block: u64,
+        #[prost(uint64, tag = "3")]
+        pub finalize_block: u64,
+        /// Block time of the finalize minus that of the put
+        #[prost(uint64, tag = "4")]
+        pub seconds: u64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FinalizeLatencies {
+        #[prost(message, repeated, tag = "1")]
+        pub latencies: ::prost::alloc::vec::Vec<FinalizeLatency>,
+    }
+
+    /// Author putting the same hash again and again (map_repost_spam)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RepostSpamEvent {
+        #[prost(string, tag = "1")]
+        pub author: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub hash: ::prost::alloc::string::String,
+        /// Reposts (puts after the first) within the window
+        #[prost(uint64, tag = "3")]
+        pub count: u64,
+    }
+
+    /// Repost spam detected in a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RepostSpamEvents {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        #[prost(uint64, tag = "2")]
+        pub window_start_block: u64,
+        #[prost(message, repeated, tag = "3")]
+        pub events: ::prost::alloc::vec::Vec<RepostSpamEvent>,
+    }
+
+    /// Current state of a live node
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RegistryRow {
+        #[prost(message, optional, tag = "1")]
+        pub node: ::core::option::Option<RegistryNode>,
+        /// Likes minus unlikes
+        #[prost(uint64, tag = "2")]
+        pub like_count: u64,
+        /// Net stake per symbol, non-zero symbols only
+        #[prost(message, repeated, tag = "3")]
+        pub staked: ::prost::alloc::vec::Vec<TokenAmount>,
+        /// Direct replies (store_child_count)
+        #[prost(uint64, tag = "4")]
+        pub reply_count: u64,
+    }
+
+    /// Direct reply count of a node (map_node_reply_counts)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NodeReplyCount {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub reply_count: u64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NodeReplyCounts {
+        #[prost(message, repeated, tag = "1")]
+        pub nodes: ::prost::alloc::vec::Vec<NodeReplyCount>,
+    }
+
+    /// Registry rows as of a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RegistryState {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        #[prost(message, repeated, tag = "2")]
+        pub rows: ::prost::alloc::vec::Vec<RegistryRow>,
+    }
+
+    /// Weights of a node score (map_node_score)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ScoreWeights {
+        #[prost(double, tag = "1")]
+        pub like: f64,
+        /// Per whole token
+        #[prost(double, tag = "2")]
+        pub stake: f64,
+        #[prost(double, tag = "3")]
+        pub attest: f64,
+    }
+
+    /// Composite engagement score of a node
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NodeScore {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        #[prost(double, tag = "2")]
+        pub score: f64,
+        #[prost(uint64, tag = "3")]
+        pub likes: u64,
+        /// Net stake in the scored symbol, e.g. "12.0000 MUS"
+        #[prost(string, tag = "4")]
+        pub staked: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "5")]
+        pub attestations: u64,
+    }
+
+    /// Highest-scoring nodes, highest first (ties by node_id)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NodeScores {
+        #[prost(message, repeated, tag = "1")]
+        pub scores: ::prost::alloc::vec::Vec<NodeScore>,
+        /// Weights the scores were computed with
+        #[prost(message, optional, tag = "2")]
+        pub weights: ::core::option::Option<ScoreWeights>,
+        /// Scored nodes left out for staking under min_stake
+        #[prost(uint64, tag = "3")]
+        pub below_min_stake: u64,
+    }
+
+    /// Like count of a node reaching a milestone (first time only)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LikeMilestoneEvent {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Like count reached, e.g. 1, 10, 100
+        #[prost(uint64, tag = "2")]
+        pub milestone: u64,
+        /// Liker whose like reached it
+        #[prost(string, tag = "3")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "4")]
+        pub block_num: u64,
+    }
+
+    /// Like milestones reached in a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LikeMilestones {
+        #[prost(message, repeated, tag = "1")]
+        pub milestones: ::prost::alloc::vec::Vec<LikeMilestoneEvent>,
+    }
+
+    /// First event of a type involving an account (map_first_time_events)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FirstTimeEvent {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        /// PUT, VOTE, STAKE, LIKE, ...
+        #[prost(string, tag = "2")]
+        pub event_type: ::prost::alloc::string::String,
+        #[prost(string, tag = "3")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "4")]
+        pub block_num: u64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FirstTimeEvents {
+        #[prost(message, repeated, tag = "1")]
+        pub events: ::prost::alloc::vec::Vec<FirstTimeEvent>,
+    }
+
+    /// Ancestor of a liked node, for thread breadcrumbs (map_like_breadcrumbs)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct PathBreadcrumb {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Content type code, 0 when unknown
+        #[prost(uint32, tag = "2")]
+        pub type_: u32,
+        /// e.g. "CREATE_RELEASE_BUNDLE"; "UNKNOWN" when unknown
+        #[prost(string, tag = "3")]
+        pub type_name: ::prost::alloc::string::String,
+        /// Empty when unknown
+        #[prost(string, tag = "4")]
+        pub author: ::prost::alloc::string::String,
+        /// False for ancestors put before the start block
+        #[prost(bool, tag = "5")]
+        pub known: bool,
+    }
+
+    /// Like with its path resolved into breadcrumbs
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LikeBreadcrumb {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub node_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "3")]
+        pub tx_hash: ::prost::alloc::string::String,
+        /// In path order
+        #[prost(message, repeated, tag = "4")]
+        pub breadcrumbs: ::prost::alloc::vec::Vec<PathBreadcrumb>,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LikeBreadcrumbs {
+        #[prost(message, repeated, tag = "1")]
+        pub likes: ::prost::alloc::vec::Vec<LikeBreadcrumb>,
+    }
+
+    /// Burst of likes by one account with no gap above the session gap
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LikeSession {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        /// Block of the session's first like
+        #[prost(uint64, tag = "2")]
+        pub start_block: u64,
+        /// Block of its last like
+        #[prost(uint64, tag = "3")]
+        pub end_block: u64,
+        #[prost(uint64, tag = "4")]
+        pub like_count: u64,
+    }
+
+    /// Like sessions closed in a block (map_like_sessions)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LikeSessions {
+        #[prost(message, repeated, tag = "1")]
+        pub sessions: ::prost::alloc::vec::Vec<LikeSession>,
+    }
+
+    /// Account that both likes and stakes a node
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct EngagedSupporter {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub account: ::prost::alloc::string::String,
+    }
+
+    /// Engaged supporters of a node
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct EngagedSupporters {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Number of supporters
+        #[prost(uint64, tag = "2")]
+        pub count: u64,
+        #[prost(message, repeated, tag = "3")]
+        pub supporters: ::prost::alloc::vec::Vec<EngagedSupporter>,
+    }
+
+    /// Entry of a per-tag node feed
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TaggedNode {
+        /// Node hash
+        #[prost(string, tag = "1")]
+        pub hash: ::prost::alloc::string::String,
+        /// Node's full tag set, sorted and deduplicated
+        #[prost(string, repeated, tag = "2")]
+        pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        /// Block the put landed in
+        #[prost(uint64, tag = "3")]
+        pub block_num: u64,
+        /// Transaction of the put
+        #[prost(string, tag = "4")]
+        pub tx_hash: ::prost::alloc::string::String,
+    }
+
+    /// Multi-tag (AND) search results, newest first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct SearchResults {
+        /// Query tags, sorted
+        #[prost(string, repeated, tag = "1")]
+        pub tags: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+        #[prost(message, repeated, tag = "2")]
+        pub nodes: ::prost::alloc::vec::Vec<TaggedNode>,
+        /// Feed entries examined
+        #[prost(uint64, tag = "3")]
+        pub scanned: u64,
+        /// False if the scan bound cut the search short
+        #[prost(bool, tag = "4")]
+        pub exhaustive: bool,
+    }
+
+    /// Tag co-occurring with the queried tag
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RelatedTag {
+        #[prost(string, tag = "1")]
+        pub tag: ::prost::alloc::string::String,
+        /// Puts carrying both tags
+        #[prost(uint64, tag = "2")]
+        pub count: u64,
+    }
+
+    /// Tags most often put together with `tag`, most frequent first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RelatedTags {
+        #[prost(string, tag = "1")]
+        pub tag: ::prost::alloc::string::String,
+        #[prost(message, repeated, tag = "2")]
+        pub related: ::prost::alloc::vec::Vec<RelatedTag>,
+    }
+
+    /// Node of an exported reply tree
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ThreadNode {
+        #[prost(string, tag = "1")]
+        pub hash: ::prost::alloc::string::String,
+        /// Empty if put before the start block
+        #[prost(string, tag = "2")]
+        pub author: ::prost::alloc::string::String,
+        /// 0 for the root
+        #[prost(uint32, tag = "3")]
+        pub depth: u32,
+    }
+
+    /// Parent -> child reply edge
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ThreadEdge {
+        #[prost(string, tag = "1")]
+        pub parent: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub child: ::prost::alloc::string::String,
+    }
+
+    /// Reply tree rooted at a node
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ThreadTree {
+        #[prost(string, tag = "1")]
+        pub root: ::prost::alloc::string::String,
+        /// Breadth-first order, root first
+        #[prost(message, repeated, tag = "2")]
+        pub nodes: ::prost::alloc::vec::Vec<ThreadNode>,
+        #[prost(message, repeated, tag = "3")]
+        pub edges: ::prost::alloc::vec::Vec<ThreadEdge>,
+        /// True if max_nodes was reached
+        #[prost(bool, tag = "4")]
+        pub truncated: bool,
+        /// Edges dropped because they pointed back into the tree
+        #[prost(uint64, tag = "5")]
+        pub cycles_broken: u64,
+    }
+
+    /// Payout actually made for a finalized submission
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FinalizePayout {
+        /// Finalize tx_hash (the submission's put hash)
+        #[prost(string, tag = "1")]
+        pub submission_hash: ::prost::alloc::string::String,
+        /// Transaction containing the finalize
+        #[prost(string, tag = "2")]
+        pub trx_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "3")]
+        pub block_num: u64,
+        #[prost(bool, tag = "4")]
+        pub accepted: bool,
+        #[prost(message, repeated, tag = "5")]
+        pub rewards: ::prost::alloc::vec::Vec<RewardEvent>,
+        /// Sum of reward quantities in the token's smallest unit
+        #[prost(int64, tag = "6")]
+        pub total_amount: i64,
+        /// Same total as an asset string, empty without rewards
+        #[prost(string, tag = "7")]
+        pub total_quantity: ::prost::alloc::string::String,
+    }
+
+    /// Submissions finalized in a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FinalizePayouts {
+        #[prost(message, repeated, tag = "1")]
+        pub payouts: ::prost::alloc::vec::Vec<FinalizePayout>,
+    }
+
+    /// Finalize re-judged against a hypothetical threshold
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct HypotheticalFinalize {
+        /// Finalized submission
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        /// Weighted outcome, from map_finalize_results
+        #[prost(bool, tag = "2")]
+        pub actual_accepted: bool,
+        /// tally_approval_percent >= threshold (false with no votes)
+        #[prost(bool, tag = "3")]
+        pub hypothetical_accepted: bool,
+        /// Weighted approval, from map_finalize_results
+        #[prost(uint64, tag = "4")]
+        pub approval_percent: u64,
+        /// votes_for / (votes_for + votes_against) * 100
+        #[prost(double, tag = "5")]
+        pub tally_approval_percent: f64,
+        #[prost(uint64, tag = "6")]
+        pub votes_for: u64,
+        #[prost(uint64, tag = "7")]
+        pub votes_against: u64,
+    }
+
+    /// Finalizes in the block under a hypothetical approval threshold
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct HypotheticalFinalizes {
+        #[prost(double, tag = "1")]
+        pub threshold_percent: f64,
+        #[prost(message, repeated, tag = "2")]
+        pub finalizes: ::prost::alloc::vec::Vec<HypotheticalFinalize>,
+    }
+
+    /// Vote that brought a submission's tally approval up to the threshold
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ThresholdCrossedEvent {
+        /// Submission (put) hash
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub crossed_at_block: u64,
+        /// for / (for + against) right after the vote
+        #[prost(double, tag = "3")]
+        pub approval_percent: f64,
+        #[prost(uint64, tag = "4")]
+        pub votes_for: u64,
+        #[prost(uint64, tag = "5")]
+        pub votes_against: u64,
+    }
+
+    /// Threshold crossings in a block (map_threshold_crossings)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ThresholdCrossings {
+        #[prost(double, tag = "1")]
+        pub threshold_percent: f64,
+        #[prost(message, repeated, tag = "2")]
+        pub events: ::prost::alloc::vec::Vec<ThresholdCrossedEvent>,
+    }
+
+    /// Up and down votes of a submission with its controversy score
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ControversialSubmission {
+        /// Submission (put) hash
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        /// Votes (or weight) with val > 0
+        #[prost(uint64, tag = "2")]
+        pub up: u64,
+        /// Votes (or weight) with val < 0
+        #[prost(uint64, tag = "3")]
+        pub down: u64,
+        /// (up + down) * min(up, down) / max(up, down)
+        #[prost(double, tag = "4")]
+        pub controversy: f64,
+    }
+
+    /// Most controversial submissions, highest first (ties by tx_hash)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ControversialSubmissions {
+        /// "count" or "weight"
+        #[prost(string, tag = "1")]
+        pub basis: ::prost::alloc::string::String,
+        #[prost(message, repeated, tag = "2")]
+        pub submissions: ::prost::alloc::vec::Vec<ControversialSubmission>,
+    }
+
+    /// Polaris actions contained in one transaction
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TransactionSummary {
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// Event types in execution order (e.g. \["STAKE", "VOTE"\])
+        #[prost(string, repeated, tag = "3")]
+        pub actions: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    }
+
+    /// Transactions in a block that contained Polaris actions
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TransactionSummaries {
+        #[prost(message, repeated, tag = "1")]
+        pub summaries: ::prost::alloc::vec::Vec<TransactionSummary>,
+    }
+
+    /// Events of one transaction, in execution order
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TransactionEvents {
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        #[prost(message, repeated, tag = "3")]
+        pub events: ::prost::alloc::vec::Vec<Event>,
+    }
+
+    /// Transactions in a block that produced events
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TransactionEventGroups {
+        #[prost(message, repeated, tag = "1")]
+        pub transactions: ::prost::alloc::vec::Vec<TransactionEvents>,
+    }
+
+    /// Approximate key count of one store
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StoreKeyCount {
+        /// Store module name
+        #[prost(string, tag = "1")]
+        pub store: ::prost::alloc::string::String,
+        /// Keys created since the start block
+        #[prost(uint64, tag = "2")]
+        pub keys: u64,
+    }
+
+    /// Periodic store growth report
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StoreSizeEstimate {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        #[prost(message, repeated, tag = "2")]
+        pub stores: ::prost::alloc::vec::Vec<StoreKeyCount>,
+        /// Sum over all tracked stores
+        #[prost(uint64, tag = "3")]
+        pub total_keys: u64,
+    }
+
+    /// Reference to the action a later unlike/unstake reverses
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ActionRef {
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+    }
+
+    /// Throughput of one event type over the rate window
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct EventRate {
+        /// Event type, or "ALL" for every event
+        #[prost(string, tag = "1")]
+        pub event_type: ::prost::alloc::string::String,
+        /// Events in the window
+        #[prost(uint64, tag = "2")]
+        pub events: u64,
+        /// events / window_blocks
+        #[prost(double, tag = "3")]
+        pub events_per_block: f64,
+    }
+
+    /// Rolling event rates ending at block_num
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct EventRates {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        /// Window length K in blocks
+        #[prost(uint64, tag = "2")]
+        pub window_blocks: u64,
+        #[prost(message, repeated, tag = "3")]
+        pub rates: ::prost::alloc::vec::Vec<EventRate>,
+    }
+
+    /// Age of the most recent event of one type
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct EventLiveness {
+        #[prost(string, tag = "1")]
+        pub event_type: ::prost::alloc::string::String,
+        /// False if no such event since the start block
+        #[prost(bool, tag = "2")]
+        pub seen: bool,
+        #[prost(uint64, tag = "3")]
+        pub last_block: u64,
+        /// Current block minus last_block
+        #[prost(uint64, tag = "4")]
+        pub blocks_since: u64,
+        /// Current block time minus the event's block time
+        #[prost(uint64, tag = "5")]
+        pub seconds_since: u64,
+    }
+
+    /// Activity health of the contract at block_num
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Liveness {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        /// Block timestamp (Unix seconds)
+        #[prost(uint64, tag = "2")]
+        pub timestamp: u64,
+        #[prost(message, repeated, tag = "3")]
+        pub types: ::prost::alloc::vec::Vec<EventLiveness>,
+    }
+
+    /// Attestation of a node by the account that put it
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct SelfAttestationEvent {
+        #[prost(string, tag = "1")]
+        pub attestor: ::prost::alloc::string::String,
+        /// Transaction of the attest action
+        #[prost(string, tag = "2")]
+        pub tx_hash: ::prost::alloc::string::String,
+        /// Put hash of the attested node
+        #[prost(string, tag = "3")]
+        pub node_id: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "4")]
+        pub block_num: u64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct SelfAttestations {
+        #[prost(message, repeated, tag = "1")]
+        pub attestations: ::prost::alloc::vec::Vec<SelfAttestationEvent>,
+    }
+
+    /// Cross-event invariant broken by an event
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct InvariantViolation {
+        /// Machine-readable check code, e.g. "UNLIKE_WITHOUT_LIKE"
+        #[prost(string, tag = "1")]
+        pub code: ::prost::alloc::string::String,
+        /// Transaction of the offending event
+        #[prost(string, tag = "2")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "3")]
+        pub block_num: u64,
+        /// Type of the offending event
+        #[prost(string, tag = "4")]
+        pub event_type: ::prost::alloc::string::String,
+        /// Human-readable description
+        #[prost(string, tag = "5")]
+        pub detail: ::prost::alloc::string::String,
+    }
+
+    /// Invariant violations found in a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct InvariantViolations {
+        #[prost(message, repeated, tag = "1")]
+        pub violations: ::prost::alloc::vec::Vec<InvariantViolation>,
+    }
+
+    /// Oracle account in force and where it was set (map_current_oracle)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct CurrentOracle {
+        #[prost(string, tag = "1")]
+        pub oracle: ::prost::alloc::string::String,
+        /// Block of the setoracle that set it
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// Transaction of that setoracle
+        #[prost(string, tag = "3")]
+        pub tx_hash: ::prost::alloc::string::String,
+        /// Block timestamp (Unix seconds)
+        #[prost(uint64, tag = "4")]
+        pub timestamp: u64,
+    }
+
+    /// Per-symbol stake totals of a block that differ between data paths
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StakeReconciliation {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        /// Symbol code, e.g. "MUS"
+        #[prost(string, tag = "2")]
+        pub symbol: ::prost::alloc::string::String,
+        /// Net STAKE/UNSTAKE amount of the block's events
+        #[prost(string, tag = "3")]
+        pub action_total: ::prost::alloc::string::String,
+        /// Net change of `stakes` table rows
+        #[prost(string, tag = "4")]
+        pub table_total: ::prost::alloc::string::String,
+        /// action_total - table_total
+        #[prost(string, tag = "5")]
+        pub delta: ::prost::alloc::string::String,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StakeReconciliations {
+        #[prost(message, repeated, tag = "1")]
+        pub reconciliations: ::prost::alloc::vec::Vec<StakeReconciliation>,
+    }
+
+    /// Governance parameters of the contract's `globals` row (map_contract_config)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ContractConfig {
+        /// Current election round
+        #[prost(uint64, tag = "1")]
+        pub round: u64,
+        /// Account allowed to update respect
+        #[prost(string, tag = "2")]
+        pub fractally_oracle: ::prost::alloc::string::String,
+        #[prost(string, tag = "3")]
+        pub token_contract: ::prost::alloc::string::String,
+        /// Precision and code, e.g. "4,MUS"
+        #[prost(string, tag = "4")]
+        pub token_symbol: ::prost::alloc::string::String,
+        /// Council attestor, empty when unset
+        #[prost(string, tag = "5")]
+        pub council_account: ::prost::alloc::string::String,
+        /// Approval needed to accept, in basis points
+        #[prost(uint64, tag = "6")]
+        pub approval_threshold_bp: u64,
+        #[prost(uint32, tag = "7")]
+        pub max_vote_weight: u32,
+        /// Respect needed to attest
+        #[prost(uint32, tag = "8")]
+        pub attestor_respect_threshold: u32,
+        #[prost(bool, tag = "9")]
+        pub paused: bool,
+        /// Voting windows per content type, in seconds
+        #[prost(uint32, tag = "10")]
+        pub vote_window_release: u32,
+        #[prost(uint32, tag = "11")]
+        pub vote_window_mint: u32,
+        #[prost(uint32, tag = "12")]
+        pub vote_window_resolve: u32,
+        #[prost(uint32, tag = "13")]
+        pub vote_window_claim: u32,
+        #[prost(uint32, tag = "14")]
+        pub vote_window_merge: u32,
+        #[prost(uint32, tag = "15")]
+        pub vote_window_default: u32,
+        /// Emission multipliers per content type
+        #[prost(uint64, tag = "16")]
+        pub multiplier_release: u64,
+        #[prost(uint64, tag = "17")]
+        pub multiplier_mint: u64,
+        #[prost(uint64, tag = "18")]
+        pub multiplier_resolve: u64,
+        #[prost(uint64, tag = "19")]
+        pub multiplier_add_claim: u64,
+        #[prost(uint64, tag = "20")]
+        pub multiplier_edit_claim: u64,
+        #[prost(uint64, tag = "21")]
+        pub multiplier_merge: u64,
+        /// Reward distribution, in basis points
+        #[prost(uint64, tag = "22")]
+        pub approved_author_pct: u64,
+        #[prost(uint64, tag = "23")]
+        pub approved_voters_pct: u64,
+        #[prost(uint64, tag = "24")]
+        pub approved_stakers_pct: u64,
+        #[prost(uint64, tag = "25")]
+        pub rejected_voters_pct: u64,
+        #[prost(uint64, tag = "26")]
+        pub rejected_stakers_pct: u64,
+        /// Block of the change
+        #[prost(uint64, tag = "27")]
+        pub block_num: u64,
+        /// Transaction of the change
+        #[prost(string, tag = "28")]
+        pub tx_hash: ::prost::alloc::string::String,
+    }
+
+    /// Share of an attestor's finalized submissions that were accepted (map_attestor_accuracy)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AttestorAccuracy {
+        #[prost(string, tag = "1")]
+        pub attestor: ::prost::alloc::string::String,
+        /// Attested submissions finalized as accepted
+        #[prost(uint64, tag = "2")]
+        pub agreements: u64,
+        /// Attested submissions finalized as rejected
+        #[prost(uint64, tag = "3")]
+        pub disagreements: u64,
+        /// agreements / (agreements + disagreements)
+        #[prost(double, tag = "4")]
+        pub accuracy: f64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AttestorAccuracies {
+        #[prost(message, repeated, tag = "1")]
+        pub attestors: ::prost::alloc::vec::Vec<AttestorAccuracy>,
+    }
+
+    /// Unlike of a node the account is not known to like (map_orphan_unlikes)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OrphanUnlikeEvent {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub node_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "3")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "4")]
+        pub block_num: u64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OrphanUnlikeEvents {
+        #[prost(message, repeated, tag = "1")]
+        pub events: ::prost::alloc::vec::Vec<OrphanUnlikeEvent>,
+    }
+
+    /// Likes given by an account minus its counted unlikes
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AccountNetLikes {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub net_likes: u64,
+    }
+
+    /// Net likes of the requested accounts, most generous first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AccountNetLikesList {
+        #[prost(message, repeated, tag = "1")]
+        pub accounts: ::prost::alloc::vec::Vec<AccountNetLikes>,
+    }
+
+    /// Row change of a contract table (map_table_deltas)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TableDelta {
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// INSERT, UPDATE or REMOVE
+        #[prost(string, tag = "3")]
+        pub operation: ::prost::alloc::string::String,
+        /// Contract account owning the table
+        #[prost(string, tag = "4")]
+        pub code: ::prost::alloc::string::String,
+        #[prost(string, tag = "5")]
+        pub scope: ::prost::alloc::string::String,
+        #[prost(string, tag = "6")]
+        pub table_name: ::prost::alloc::string::String,
+        #[prost(string, tag = "7")]
+        pub primary_key: ::prost::alloc::string::String,
+        /// Row before the change, empty for inserts
+        #[prost(string, tag = "8")]
+        pub old_data_json: ::prost::alloc::string::String,
+        /// Row after the change, empty for removals
+        #[prost(string, tag = "9")]
+        pub new_data_json: ::prost::alloc::string::String,
+        /// Binary row images, for sources without JSON
+        #[prost(bytes = "vec", tag = "10")]
+        pub old_data: ::prost::alloc::vec::Vec<u8>,
+        #[prost(bytes = "vec", tag = "11")]
+        pub new_data: ::prost::alloc::vec::Vec<u8>,
+        /// Action of the transaction that changed the row
+        #[prost(uint32, tag = "12")]
+        pub action_index: u32,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct TableDeltas {
+        #[prost(message, repeated, tag = "1")]
+        pub deltas: ::prost::alloc::vec::Vec<TableDelta>,
+    }
+
+    /// Lockup of one stake position (account, node, symbol)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StakeLock {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub node_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "3")]
+        pub symbol: ::prost::alloc::string::String,
+        /// Symbol precision, for formatting amounts
+        #[prost(uint32, tag = "4")]
+        pub precision: u32,
+        /// Latest stake timestamp (Unix seconds)
+        #[prost(uint64, tag = "5")]
+        pub staked_at: u64,
+        /// staked_at + lockup_seconds
+        #[prost(uint64, tag = "6")]
+        pub unlock_at: u64,
+    }
+
+    /// Stake taking a position from zero to a positive amount
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct PositionOpening {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub node_id: ::prost::alloc::string::String,
+        #[prost(string, tag = "3")]
+        pub symbol: ::prost::alloc::string::String,
+        /// Symbol precision, for formatting amounts
+        #[prost(uint32, tag = "4")]
+        pub precision: u32,
+        /// Block timestamp of the opening stake (Unix seconds)
+        #[prost(uint64, tag = "5")]
+        pub opened_at: u64,
+        #[prost(uint64, tag = "6")]
+        pub block_num: u64,
+        #[prost(string, tag = "7")]
+        pub tx_hash: ::prost::alloc::string::String,
+        /// Entry index in the symbol's opening feed
+        #[prost(uint64, tag = "8")]
+        pub index: u64,
+    }
+
+    /// Stake position open since opened_at
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OldestStake {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Net staked amount, e.g. "100.0000 MUS"
+        #[prost(string, tag = "3")]
+        pub quantity: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "4")]
+        pub opened_at: u64,
+        /// Current block time minus opened_at
+        #[prost(uint64, tag = "5")]
+        pub open_seconds: u64,
+    }
+
+    /// Longest-running open stake positions in a symbol, oldest first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct OldestStakes {
+        #[prost(string, tag = "1")]
+        pub symbol: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// Block timestamp (Unix seconds)
+        #[prost(uint64, tag = "3")]
+        pub timestamp: u64,
+        #[prost(message, repeated, tag = "4")]
+        pub stakes: ::prost::alloc::vec::Vec<OldestStake>,
+        /// False when the scan cap cut the search short
+        #[prost(bool, tag = "5")]
+        pub complete: bool,
+        /// Open positions passed over for being under min_stake
+        #[prost(uint64, tag = "6")]
+        pub below_min_stake: u64,
+    }
+
+    /// Open stake position that is still locked
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LockedStake {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Net staked amount, e.g. "100.0000 MUS"
+        #[prost(string, tag = "2")]
+        pub quantity: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "3")]
+        pub staked_at: u64,
+        #[prost(uint64, tag = "4")]
+        pub unlock_at: u64,
+        /// unlock_at minus the current block time
+        #[prost(uint64, tag = "5")]
+        pub seconds_remaining: u64,
+    }
+
+    /// Locked stakes of an account at block_num, soonest unlock first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LockedStakes {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// Block timestamp (Unix seconds)
+        #[prost(uint64, tag = "3")]
+        pub timestamp: u64,
+        #[prost(message, repeated, tag = "4")]
+        pub stakes: ::prost::alloc::vec::Vec<LockedStake>,
+    }
+
+    /// Stake or unstake above the map_large_stakes threshold
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LargeStakeEvent {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Asset string, e.g. "25000.0000 MUS"
+        #[prost(string, tag = "3")]
+        pub quantity: ::prost::alloc::string::String,
+        /// "STAKE" or "UNSTAKE"
+        #[prost(string, tag = "4")]
+        pub direction: ::prost::alloc::string::String,
+        #[prost(string, tag = "5")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "6")]
+        pub block_num: u64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct LargeStakeEvents {
+        #[prost(message, repeated, tag = "1")]
+        pub events: ::prost::alloc::vec::Vec<LargeStakeEvent>,
+    }
+
+    /// Unstake from a position opened fewer than N blocks earlier (map_stake_churn)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StakeChurnEvent {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Unstaked amount, e.g. "500.0000 MUS"
+        #[prost(string, tag = "3")]
+        pub quantity: ::prost::alloc::string::String,
+        /// Blocks since the position was opened
+        #[prost(uint64, tag = "4")]
+        pub held_blocks: u64,
+        /// Block of the opening stake
+        #[prost(uint64, tag = "5")]
+        pub opened_block: u64,
+        /// The unstake emptied the position
+        #[prost(bool, tag = "6")]
+        pub closed: bool,
+        /// Share of the position unstaked (1 when closed)
+        #[prost(double, tag = "7")]
+        pub fraction: f64,
+        #[prost(string, tag = "8")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "9")]
+        pub block_num: u64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StakeChurnEvents {
+        #[prost(message, repeated, tag = "1")]
+        pub events: ::prost::alloc::vec::Vec<StakeChurnEvent>,
+    }
+
+    /// Vote on a submission (one entry of store_submission_votes)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct SubmissionVote {
+        #[prost(string, tag = "1")]
+        pub voter: ::prost::alloc::string::String,
+        /// +1, 0, -1
+        #[prost(int32, tag = "2")]
+        pub val: i32,
+        #[prost(uint64, tag = "3")]
+        pub block_num: u64,
+    }
+
+    /// Burst of votes on a submission from recently seen accounts (map_brigading_signals)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BrigadingSignal {
+        /// Submission (put) hash
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        /// Votes in the window, including this block
+        #[prost(uint64, tag = "2")]
+        pub vote_count: u64,
+        /// Window length the votes were counted over
+        #[prost(uint64, tag = "3")]
+        pub window_blocks: u64,
+        /// Share of those votes from new accounts
+        #[prost(double, tag = "4")]
+        pub new_account_ratio: f64,
+        /// Votes from new accounts
+        #[prost(uint64, tag = "5")]
+        pub new_accounts: u64,
+        /// Share of votes with the most common value
+        #[prost(double, tag = "6")]
+        pub identical_ratio: f64,
+        #[prost(uint64, tag = "7")]
+        pub block_num: u64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BrigadingSignals {
+        #[prost(message, repeated, tag = "1")]
+        pub signals: ::prost::alloc::vec::Vec<BrigadingSignal>,
+    }
+
+    /// Approximate concentration of account stake totals
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StakeGini {
+        #[prost(string, tag = "1")]
+        pub symbol: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// 0 = equal, 1 = one account holds all; lower bound (see README)
+        #[prost(double, tag = "3")]
+        pub gini: f64,
+        /// Accounts with a positive stake total
+        #[prost(uint64, tag = "4")]
+        pub accounts: u64,
+        /// Non-empty histogram buckets behind the estimate
+        #[prost(uint32, tag = "5")]
+        pub buckets: u32,
+    }
+
+    /// Stake total of an account after a snapshot period (map_balance_snapshots)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BalanceSnapshot {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        /// Net stake over all nodes, e.g. "1500.0000 MUS"
+        #[prost(string, tag = "2")]
+        pub staked: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "3")]
+        pub block_num: u64,
+    }
+
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct BalanceSnapshots {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        #[prost(message, repeated, tag = "2")]
+        pub snapshots: ::prost::alloc::vec::Vec<BalanceSnapshot>,
+    }
+
+    /// Activity of a pulse period (map_pulse)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct ActivityPulse {
+        /// First block of the period
+        #[prost(uint64, tag = "1")]
+        pub from_block: u64,
+        /// Last block of the period, where the pulse is emitted
+        #[prost(uint64, tag = "2")]
+        pub to_block: u64,
+        #[prost(uint64, tag = "3")]
+        pub events_in_window: u64,
+        /// Distinct accounts involved in the period's events
+        #[prost(uint64, tag = "4")]
+        pub active_accounts: u64,
+        /// Stakes minus unstakes, e.g. "-25.0000 MUS"
+        #[prost(string, tag = "5")]
+        pub net_staked_change: ::prost::alloc::string::String,
+    }
+
+    /// Quantity kept out of the per-symbol totals (map_asset_anomalies)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AssetAnomaly {
+        #[prost(string, tag = "1")]
+        pub tx_hash: ::prost::alloc::string::String,
+        #[prost(uint64, tag = "2")]
+        pub block_num: u64,
+        /// STAKE, UNSTAKE or REWARD
+        #[prost(string, tag = "3")]
+        pub event_type: ::prost::alloc::string::String,
+        /// As found in the action
+        #[prost(string, tag = "4")]
+        pub quantity: ::prost::alloc::string::String,
+        /// malformed, unexpected_symbol or precision_mismatch
+        #[prost(string, tag = "5")]
+        pub reason: ::prost::alloc::string::String,
+        /// Expected "{precision},{SYMBOL}", empty if unknown
+        #[prost(string, tag = "6")]
+        pub expected: ::prost::alloc::string::String,
+    }
+
+    /// Asset anomalies found in a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct AssetAnomalies {
+        #[prost(message, repeated, tag = "1")]
+        pub anomalies: ::prost::alloc::vec::Vec<AssetAnomaly>,
+    }
+
+    /// Stake, unstake or reward event with its fiat value (map_fiat_values)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FiatValuedEvent {
+        #[prost(message, optional, tag = "1")]
+        pub event: ::core::option::Option<Event>,
+        /// quantity * price, e.g. "12.50"; in `currency`
+        #[prost(string, tag = "2")]
+        pub usd_value: ::prost::alloc::string::String,
+    }
+
+    /// Fiat-valued events in a block, at one constant price per run
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct FiatValuedEvents {
+        /// Price param as given, per whole token
+        #[prost(string, tag = "1")]
+        pub price: ::prost::alloc::string::String,
+        /// Label only, "USD" unless overridden
+        #[prost(string, tag = "2")]
+        pub currency: ::prost::alloc::string::String,
+        /// Token symbol the price applies to
+        #[prost(string, tag = "3")]
+        pub symbol: ::prost::alloc::string::String,
+        #[prost(message, repeated, tag = "4")]
+        pub events: ::prost::alloc::vec::Vec<FiatValuedEvent>,
+    }
+
+    /// Stake moved in and out during a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StakeFlow {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        /// Sum of STAKE quantities, e.g. "120.0000 MUS"
+        #[prost(string, tag = "2")]
+        pub inflow: ::prost::alloc::string::String,
+        /// Sum of UNSTAKE quantities
+        #[prost(string, tag = "3")]
+        pub outflow: ::prost::alloc::string::String,
+        /// inflow - outflow (negative on net outflow)
+        #[prost(string, tag = "4")]
+        pub net: ::prost::alloc::string::String,
+    }
+
+    /// Node whose recent unstakes outweigh its stakes (map_node_exit_signal)
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NodeExitSignal {
+        #[prost(string, tag = "1")]
+        pub node_id: ::prost::alloc::string::String,
+        /// Staked on the node in the window, e.g. "10.0000 MUS"
+        #[prost(string, tag = "2")]
+        pub staked: ::prost::alloc::string::String,
+        /// Unstaked from the node in the window
+        #[prost(string, tag = "3")]
+        pub unstaked: ::prost::alloc::string::String,
+        /// unstaked / staked, 0 when nothing was staked
+        #[prost(double, tag = "4")]
+        pub ratio: f64,
+    }
+
+    /// Nodes flagged at a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct NodeExitSignals {
+        #[prost(uint64, tag = "1")]
+        pub block_num: u64,
+        /// First block counted (start of its bucket)
+        #[prost(uint64, tag = "2")]
+        pub window_start_block: u64,
+        #[prost(message, repeated, tag = "3")]
+        pub signals: ::prost::alloc::vec::Vec<NodeExitSignal>,
+    }
+
+    /// Distinct accounts scored in an election round
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RoundParticipation {
+        #[prost(uint64, tag = "1")]
+        pub election_round: u64,
+        /// Distinct accounts updated in the round so far
+        #[prost(uint64, tag = "2")]
+        pub accounts: u64,
+        /// Same for the round before (0 if none seen)
+        #[prost(uint64, tag = "3")]
+        pub previous_accounts: u64,
+        /// accounts - previous_accounts
+        #[prost(int64, tag = "4")]
+        pub change: i64,
+    }
+
+    /// Election rounds with respect updates in a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RoundParticipations {
+        #[prost(message, repeated, tag = "1")]
+        pub rounds: ::prost::alloc::vec::Vec<RoundParticipation>,
+    }
+
+    /// Respect of one account in an election round against the round before
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RespectDelta {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        /// Respect in round - 1 (0 if not scored)
+        #[prost(uint32, tag = "2")]
+        pub previous: u32,
+        /// Respect in round (0 if dropped out)
+        #[prost(uint32, tag = "3")]
+        pub current: u32,
+        /// current - previous
+        #[prost(int64, tag = "4")]
+        pub change: i64,
+        #[prost(uint64, tag = "5")]
+        pub round: u64,
+    }
+
+    /// Accounts whose respect changed round-over-round, biggest movers first
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct RespectDeltas {
+        #[prost(message, repeated, tag = "1")]
+        pub deltas: ::prost::alloc::vec::Vec<RespectDelta>,
+    }
+
+    /// Current stake and respect of one account
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StakeRespectPair {
+        #[prost(string, tag = "1")]
+        pub account: ::prost::alloc::string::String,
+        /// Net staked per symbol, zero if none
+        #[prost(message, repeated, tag = "2")]
+        pub staked: ::prost::alloc::vec::Vec<TokenAmount>,
+        /// Unset if the account never received respect
+        #[prost(message, optional, tag = "3")]
+        pub respect: ::core::option::Option<RespectScore>,
+    }
+
+    /// Accounts whose stake or respect changed in a block
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct StakeRespectPairs {
+        #[prost(message, repeated, tag = "1")]
+        pub pairs: ::prost::alloc::vec::Vec<StakeRespectPair>,
+    }
+
     /// AnchoredEvent: Event with complete blockchain provenance
     /// This is the primary output for chain ingestion (T5)
     #[allow(clippy::derive_partial_eq_without_eq)]